@@ -1,5 +1,6 @@
 use crate::{
-    CoreStage, Events, Plugin, PluginGroup, PluginGroupBuilder, StartupSchedule, StartupStage,
+    CoreStage, EventRetention, Events, Plugin, PluginGroup, PluginGroupBuilder, StartupSchedule,
+    StartupStage,
 };
 pub use bevy_derive::AppLabel;
 use bevy_ecs::{
@@ -631,6 +632,29 @@ impl App {
             .add_system_to_stage(CoreStage::First, Events::<T>::update_system)
     }
 
+    /// Like [`add_event`](Self::add_event), except that the events are kept according to the
+    /// given [`EventRetention`] instead of the default one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_app::{prelude::*, EventRetention};
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # struct MyEvent;
+    /// # let mut app = App::new();
+    /// #
+    /// // keep the events until every `EventReader<MyEvent>` has read them
+    /// app.add_event_with_retention::<MyEvent>(EventRetention::UntilConsumed);
+    /// ```
+    pub fn add_event_with_retention<T>(&mut self, retention: EventRetention) -> &mut Self
+    where
+        T: Resource,
+    {
+        self.insert_resource(Events::<T>::with_retention(retention))
+            .add_system_to_stage(CoreStage::First, Events::<T>::update_system)
+    }
+
     /// Inserts a resource to the current [App] and overwrites any resource previously added of the same type.
     ///
     /// A resource in Bevy represents globally unique data. Resources must be added to Bevy Apps
@@ -850,6 +874,12 @@ impl App {
     /// The provided function `f` is called by the [`update`](Self::update) method. The `World`
     /// parameter represents the main app world, while the `App` parameter is just a mutable
     /// reference to the sub app itself.
+    ///
+    /// A sub app with a runner that only calls [`App::update`] runs independently of the main
+    /// app. Entities can be moved or copied between the worlds with [`World::move_entities_to`]
+    /// and [`World::clone_entities_to`], e.g. in the runner or between two sub apps retrieved with
+    /// [`sub_apps_mut`](Self::sub_apps_mut). This allows running several simulations in one
+    /// process, such as a server and a client for local multiplayer testing.
     pub fn add_sub_app(
         &mut self,
         label: impl AppLabel,
@@ -883,6 +913,61 @@ impl App {
             .ok_or(label)
     }
 
+    /// Retrieves two different "sub apps" stored inside this [App] at once, e.g. to move entities
+    /// between their worlds. This will panic if a sub app does not exist, or if both labels are
+    /// the same.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_app::{prelude::*, AppLabel};
+    /// # use bevy_ecs::{entity::EntityMap, prelude::*};
+    /// #
+    /// #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, AppLabel)]
+    /// enum Peer {
+    ///     Server,
+    ///     Client,
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_sub_app(Peer::Server, App::new(), |_, server| server.update())
+    ///     .add_sub_app(Peer::Client, App::new(), |_, client| client.update());
+    ///
+    /// let (server, client) = app.sub_apps_mut(Peer::Server, Peer::Client);
+    /// let player = server.world.spawn().id();
+    /// let entity_map = server
+    ///     .world
+    ///     .move_entities_to(&[player], &mut client.world, &EntityMap::default())
+    ///     .unwrap();
+    /// assert!(client.world.get_entity(entity_map.get(player).unwrap()).is_some());
+    /// ```
+    pub fn sub_apps_mut(
+        &mut self,
+        first: impl AppLabel,
+        second: impl AppLabel,
+    ) -> (&mut App, &mut App) {
+        let (first, second) = (&first as &dyn AppLabel, &second as &dyn AppLabel);
+        assert!(
+            first != second,
+            "Cannot retrieve the Sub-App with label '{:?}' twice",
+            first
+        );
+        let mut first_app = None;
+        let mut second_app = None;
+        for (label, sub_app) in self.sub_apps.iter_mut() {
+            if **label == *first {
+                first_app = Some(&mut sub_app.app);
+            } else if **label == *second {
+                second_app = Some(&mut sub_app.app);
+            }
+        }
+        match (first_app, second_app) {
+            (Some(first_app), Some(second_app)) => (first_app, second_app),
+            (None, _) => panic!("Sub-App with label '{:?}' does not exist", first),
+            (_, None) => panic!("Sub-App with label '{:?}' does not exist", second),
+        }
+    }
+
     /// Retrieves a "sub app" stored inside this [App]. This will panic if the sub app does not exist.
     pub fn sub_app(&self, label: impl AppLabel) -> &App {
         match self.get_sub_app(label) {
@@ -1,12 +1,71 @@
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 
-use crate::{app::AppExit, App};
+use crate::{app::AppExit, App, CoreStage};
+use bevy_ecs::{
+    event::Events,
+    schedule::ExclusiveSystemDescriptorCoercion,
+    system::IntoExclusiveSystem,
+    system::Resource,
+    world::{Mut, World},
+};
+use bevy_utils::HashMap;
 
 /// Configuration for automated testing on CI
 #[derive(Deserialize)]
 pub struct CiTestingConfig {
     /// Number of frames after wich Bevy should exit
     pub exit_after: Option<u32>,
+    /// Events sent to the app at given frames, e.g. to press keys
+    #[serde(default)]
+    pub events: Vec<CiTestingEvent>,
+}
+
+/// An event sent to the app by the [`CiTestingConfig`]
+///
+/// For example, with the input events registered by `bevy_input`:
+///
+/// ```ron
+/// (
+///     exit_after: Some(10),
+///     events: [
+///         (frame: 2, event: "KeyboardInput", value: "(scan_code: 0, key_code: Some(Space), state: Pressed)"),
+///     ],
+/// )
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub struct CiTestingEvent {
+    /// Frame at which the event is sent, starting at 0
+    pub frame: u32,
+    /// Name of the event type, as registered with [`App::add_ci_testing_event`]
+    pub event: String,
+    /// Value of the event, in RON
+    pub value: String,
+}
+
+type SendCiTestingEvent = fn(&mut World, &str) -> Result<(), ron::Error>;
+
+#[derive(Default)]
+struct CiTestingEventTypes {
+    frame: u32,
+    send_events: HashMap<String, SendCiTestingEvent>,
+}
+
+impl App {
+    /// Allows the [`CiTestingConfig`] to send events of type `E`, under the given name.
+    pub fn add_ci_testing_event<E>(&mut self, name: &str) -> &mut Self
+    where
+        E: Resource + DeserializeOwned,
+    {
+        self.world
+            .get_resource_or_insert_with(CiTestingEventTypes::default)
+            .send_events
+            .insert(name.to_string(), |world, value| {
+                let event = ron::from_str::<E>(value)?;
+                world.resource_mut::<Events<E>>().send(event);
+                Ok(())
+            });
+        self
+    }
 }
 
 fn ci_testing_exit_after(
@@ -22,6 +81,31 @@ fn ci_testing_exit_after(
     *current_frame += 1;
 }
 
+fn ci_testing_send_events(world: &mut World) {
+    world.resource_scope(|world, mut event_types: Mut<CiTestingEventTypes>| {
+        let events = world
+            .resource::<CiTestingConfig>()
+            .events
+            .iter()
+            .filter(|event| event.frame == event_types.frame)
+            .cloned()
+            .collect::<Vec<_>>();
+        for event in events {
+            let send_event = event_types
+                .send_events
+                .get(&event.event)
+                .unwrap_or_else(|| panic!("unknown CI testing event type {}", event.event));
+            send_event(world, &event.value).unwrap_or_else(|err| {
+                panic!(
+                    "error deserializing CI testing event {} {:?}: {}",
+                    event.event, event.value, err
+                )
+            });
+        }
+        event_types.frame += 1;
+    });
+}
+
 pub(crate) fn setup_app(app: &mut App) -> &mut App {
     let filename =
         std::env::var("CI_TESTING_CONFIG").unwrap_or_else(|_| "ci_testing_config.ron".to_string());
@@ -30,7 +114,12 @@ pub(crate) fn setup_app(app: &mut App) -> &mut App {
     )
     .expect("error deserializing CI testing configuration file");
     app.insert_resource(config)
-        .add_system(ci_testing_exit_after);
+        .init_resource::<CiTestingEventTypes>()
+        .add_system(ci_testing_exit_after)
+        .add_system_to_stage(
+            CoreStage::First,
+            ci_testing_send_events.exclusive_system().at_start(),
+        );
 
     app
 }
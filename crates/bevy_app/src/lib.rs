@@ -6,16 +6,24 @@ mod app;
 mod plugin;
 mod plugin_group;
 mod schedule_runner;
+mod testing;
 
 #[cfg(feature = "bevy_ci_testing")]
 mod ci_testing;
+#[cfg(feature = "bevy_reflect")]
+mod recording;
 
 pub use app::*;
 pub use bevy_derive::DynamicPlugin;
 pub use bevy_ecs::event::*;
+#[cfg(feature = "bevy_ci_testing")]
+pub use ci_testing::{CiTestingConfig, CiTestingEvent};
 pub use plugin::*;
 pub use plugin_group::*;
+#[cfg(feature = "bevy_reflect")]
+pub use recording::*;
 pub use schedule_runner::*;
+pub use testing::*;
 
 #[allow(missing_docs)]
 pub mod prelude {
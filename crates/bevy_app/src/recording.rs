@@ -0,0 +1,169 @@
+use crate::{App, CoreStage, Plugin};
+use bevy_ecs::{
+    recording::{CommandRecorder, WorldStateHash},
+    schedule::ExclusiveSystemDescriptorCoercion,
+    system::IntoExclusiveSystem,
+};
+use bevy_reflect::TypeRegistryArc;
+use std::fmt;
+
+/// Records the reflectable commands applied to the [`App`]'s world with a [`CommandRecorder`],
+/// starting a new frame at the start of each update.
+///
+/// Input resources can be recorded by inserting a [`CommandRecorder`] configured with
+/// [`CommandRecorder::with_input`] before adding this plugin.
+#[derive(Default)]
+pub struct CommandRecordingPlugin;
+
+impl Plugin for CommandRecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandRecorder>().add_system_to_stage(
+            CoreStage::First,
+            CommandRecorder::start_frame_system
+                .exclusive_system()
+                .at_start(),
+        );
+    }
+}
+
+/// The first difference found by [`check_determinism`] between two runs of an [`App`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nondeterminism {
+    /// The index of the update after which the worlds differed.
+    pub update: usize,
+    /// The type names of the components that differed.
+    pub components: Vec<String>,
+}
+
+impl fmt::Display for Nondeterminism {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the runs diverged after update {}, in components {:?}",
+            self.update, self.components
+        )
+    }
+}
+
+impl std::error::Error for Nondeterminism {}
+
+/// Builds two apps with `build_app`, updates them `updates` times side by side, and compares the
+/// [`WorldStateHash`] of their worlds after each update.
+///
+/// Only components registered in the apps' [`TypeRegistryArc`] with `ReflectComponent` type data
+/// are compared. This catches systems whose result depends on the order the parallel executor
+/// happened to run them in.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_app::{check_determinism, App};
+/// fn build_app() -> App {
+///     let mut app = App::new();
+///     app.add_system(|| {});
+///     app
+/// }
+///
+/// assert!(check_determinism(build_app, 10).is_ok());
+/// ```
+pub fn check_determinism(
+    build_app: impl Fn() -> App,
+    updates: usize,
+) -> Result<(), Nondeterminism> {
+    let mut apps = [build_app(), build_app()];
+    for update in 0..updates {
+        for app in apps.iter_mut() {
+            app.update();
+        }
+        let [hash_a, hash_b] = [world_state_hash(&apps[0]), world_state_hash(&apps[1])];
+        if hash_a != hash_b {
+            return Err(Nondeterminism {
+                update,
+                components: hash_a
+                    .diff(&hash_b)
+                    .into_iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn world_state_hash(app: &App) -> WorldStateHash {
+    match app.world.get_resource::<TypeRegistryArc>() {
+        Some(type_registry) => WorldStateHash::new(&app.world, &type_registry.read()),
+        None => WorldStateHash::new(&app.world, &Default::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_determinism, CommandRecordingPlugin};
+    use crate::App;
+    use bevy_ecs::{
+        prelude::*,
+        recording::CommandRecorder,
+        reflect::{ReflectCommand, ReflectComponent},
+        schedule::IntoSystemDescriptor,
+        system::Command,
+    };
+    use bevy_reflect::Reflect;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Value(u32);
+
+    #[derive(Reflect, Default)]
+    #[reflect(Command)]
+    struct SpawnValue(u32);
+
+    impl Command for SpawnValue {
+        fn write(self, world: &mut World) {
+            world.spawn().insert(Value(self.0));
+        }
+    }
+
+    #[test]
+    fn record_updates() {
+        let mut app = App::new();
+        app.register_type::<SpawnValue>()
+            .add_plugin(CommandRecordingPlugin)
+            .add_system(|mut commands: Commands| commands.add(SpawnValue(1)));
+        app.update();
+        app.update();
+
+        let log = app.world.resource_mut::<CommandRecorder>().take_log();
+        assert_eq!(log.frames.len(), 2);
+        assert!(log.frames.iter().all(|frame| frame.commands.len() == 1));
+    }
+
+    #[test]
+    fn determinism() {
+        fn build_app<Params>(system: impl IntoSystemDescriptor<Params>) -> App {
+            let mut app = App::new();
+            app.register_type::<Value>().add_system(system);
+            app
+        }
+
+        let spawn_value = |mut commands: Commands| {
+            commands.spawn().insert(Value(1));
+        };
+        assert_eq!(check_determinism(|| build_app(spawn_value), 3), Ok(()));
+
+        // each run sees a different value of the counter
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let spawn_counter = |mut commands: Commands| {
+            commands
+                .spawn()
+                .insert(Value(COUNTER.fetch_add(1, Ordering::Relaxed)));
+        };
+        let nondeterminism = check_determinism(|| build_app(spawn_counter), 3).unwrap_err();
+        assert_eq!(nondeterminism.update, 0);
+        assert_eq!(
+            nondeterminism.components,
+            vec![std::any::type_name::<Value>().to_string()]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::App;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::{Events, ManualEventReader},
+    query::{FilterFetch, WorldQuery},
+    system::Resource,
+};
+use bevy_utils::HashMap;
+use std::{
+    any::{Any, TypeId},
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+};
+
+/// A wrapper around an [`App`] to test it headlessly, one frame at a time.
+///
+/// Instead of running the app, the test steps it with [`step`](Self::step), sends it events with
+/// [`send_event`](Self::send_event), and checks the events it emitted with
+/// [`events`](Self::events) or [`assert_event`](Self::assert_event). The wrapped [`App`] is
+/// accessible through `Deref`, e.g. to read its resources.
+///
+/// To control the time in the app, insert a `TimeUpdateStrategy` resource from `bevy_core`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_app::{App, TestApp};
+/// # use bevy_ecs::prelude::*;
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Ping(u32);
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Pong(u32);
+///
+/// fn pong(mut pings: EventReader<Ping>, mut pongs: EventWriter<Pong>) {
+///     for ping in pings.iter() {
+///         pongs.send(Pong(ping.0));
+///     }
+/// }
+///
+/// let mut app = App::new();
+/// app.add_event::<Ping>().add_event::<Pong>().add_system(pong);
+///
+/// let mut app = TestApp::new(app);
+/// app.send_event(Ping(1)).step();
+/// app.assert_event(|pong: &Pong| pong.0 == 1);
+/// assert_eq!(app.frame(), 1);
+/// ```
+pub struct TestApp {
+    app: App,
+    frame: u32,
+    readers: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl TestApp {
+    /// Wraps `app`, which should not be run.
+    pub fn new(app: App) -> Self {
+        TestApp {
+            app,
+            frame: 0,
+            readers: HashMap::default(),
+        }
+    }
+
+    /// Returns the number of frames stepped so far.
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Updates the app once.
+    pub fn step(&mut self) -> &mut Self {
+        self.app.update();
+        self.frame += 1;
+        self
+    }
+
+    /// Updates the app `frames` times.
+    pub fn step_frames(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            self.step();
+        }
+        self
+    }
+
+    /// Sends an event to the app, to be read during the next step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event type wasn't added to the app.
+    pub fn send_event<E: Resource>(&mut self, event: E) -> &mut Self {
+        self.app
+            .world
+            .get_resource_mut::<Events<E>>()
+            .unwrap_or_else(|| panic!("The event {} wasn't added.", std::any::type_name::<E>()))
+            .send(event);
+        self
+    }
+
+    /// Returns the events of type `E` sent since the last call to this method, or to
+    /// [`assert_event`](Self::assert_event) and [`assert_no_event`](Self::assert_no_event).
+    ///
+    /// Events are dropped after two frames, so the events of a type must be checked at least
+    /// every other step to not miss any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event type wasn't added to the app.
+    pub fn events<E: Resource + Clone>(&mut self) -> Vec<E> {
+        let events = self
+            .app
+            .world
+            .get_resource::<Events<E>>()
+            .unwrap_or_else(|| panic!("The event {} wasn't added.", std::any::type_name::<E>()));
+        self.readers
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(ManualEventReader::<E>::default()))
+            .downcast_mut::<ManualEventReader<E>>()
+            .unwrap()
+            .iter(events)
+            .cloned()
+            .collect()
+    }
+
+    /// Asserts that an event matching `predicate` was sent since the last check of the events of
+    /// type `E`, and returns it.
+    pub fn assert_event<E: Resource + Clone + Debug>(
+        &mut self,
+        predicate: impl Fn(&E) -> bool,
+    ) -> E {
+        let events = self.events::<E>();
+        match events.iter().find(|event| predicate(event)) {
+            Some(event) => event.clone(),
+            None => panic!(
+                "No matching {} event was sent by frame {}, events: {:?}",
+                std::any::type_name::<E>(),
+                self.frame,
+                events
+            ),
+        }
+    }
+
+    /// Asserts that no event of type `E` was sent since the last check of the events of type `E`.
+    pub fn assert_no_event<E: Resource + Clone + Debug>(&mut self) {
+        let events = self.events::<E>();
+        assert!(
+            events.is_empty(),
+            "Unexpected {} events by frame {}: {:?}",
+            std::any::type_name::<E>(),
+            self.frame,
+            events
+        );
+    }
+
+    /// Returns the number of entities matching the filter `F`.
+    pub fn count<F: WorldQuery>(&mut self) -> usize
+    where
+        F::Fetch: FilterFetch,
+    {
+        self.app
+            .world
+            .query_filtered::<Entity, F>()
+            .iter(&self.app.world)
+            .count()
+    }
+
+    /// Returns the component `C` of the only entity that has it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no entity or more than one entity has the component.
+    pub fn single<C: Component>(&mut self) -> &C {
+        let mut query = self.app.world.query::<&C>();
+        let mut components = query.iter(&self.app.world);
+        match (components.next(), components.next()) {
+            (Some(component), None) => component,
+            _ => panic!(
+                "Expected exactly one entity with the component {}.",
+                std::any::type_name::<C>()
+            ),
+        }
+    }
+
+    /// Returns the wrapped app.
+    pub fn into_inner(self) -> App {
+        self.app
+    }
+}
+
+impl Deref for TestApp {
+    type Target = App;
+
+    fn deref(&self) -> &App {
+        &self.app
+    }
+}
+
+impl DerefMut for TestApp {
+    fn deref_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestApp;
+    use crate::App;
+    use bevy_ecs::prelude::*;
+
+    #[derive(Component)]
+    struct Enemy(u32);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Spawn(u32);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Spawned(Entity);
+
+    fn spawn_enemies(
+        mut commands: Commands,
+        mut spawns: EventReader<Spawn>,
+        mut spawned: EventWriter<Spawned>,
+    ) {
+        for spawn in spawns.iter() {
+            spawned.send(Spawned(commands.spawn().insert(Enemy(spawn.0)).id()));
+        }
+    }
+
+    #[test]
+    fn step_and_check_events() {
+        let mut app = App::new();
+        app.add_event::<Spawn>()
+            .add_event::<Spawned>()
+            .add_system(spawn_enemies);
+        let mut app = TestApp::new(app);
+
+        app.step();
+        app.assert_no_event::<Spawned>();
+        assert_eq!(app.count::<With<Enemy>>(), 0);
+
+        app.send_event(Spawn(3)).step_frames(2);
+        let Spawned(entity) = app.assert_event(|_: &Spawned| true);
+        assert_eq!(app.single::<Enemy>().0, 3);
+        assert_eq!(app.world.get::<Enemy>(entity).unwrap().0, 3);
+        assert_eq!(app.frame(), 3);
+
+        // events are only returned once
+        assert!(app.events::<Spawned>().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_event() {
+        let mut app = App::new();
+        app.add_event::<Spawned>();
+        TestApp::new(app).step().assert_event(|_: &Spawned| true);
+    }
+}
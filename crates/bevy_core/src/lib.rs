@@ -15,13 +15,14 @@ pub use time::*;
 pub mod prelude {
     //! The Bevy Core Prelude.
     #[doc(hidden)]
-    pub use crate::{DefaultTaskPoolOptions, Name, Time, Timer};
+    pub use crate::{DefaultTaskPoolOptions, Fixed, Name, Real, Time, Timer, Virtual};
 }
 
 use bevy_app::prelude::*;
 use bevy_ecs::{
+    component::Disabled,
     entity::Entity,
-    schedule::{ExclusiveSystemDescriptorCoercion, SystemLabel},
+    schedule::{ExclusiveSystemDescriptorCoercion, SystemLabel, SystemStage},
     system::IntoExclusiveSystem,
 };
 use bevy_utils::HashSet;
@@ -49,10 +50,14 @@ impl Plugin for CorePlugin {
             .create_default_pools(&mut app.world);
 
         app.init_resource::<Time>()
+            .init_resource::<Time<Real>>()
+            .init_resource::<Time<Virtual>>()
+            .init_resource::<Time<Fixed>>()
             .init_resource::<FixedTimesteps>()
             .register_type::<HashSet<String>>()
             .register_type::<Option<String>>()
             .register_type::<Entity>()
+            .register_type::<Disabled>()
             .register_type::<Name>()
             .register_type::<Range<f32>>()
             .register_type::<Timer>()
@@ -61,6 +66,11 @@ impl Plugin for CorePlugin {
             .add_system_to_stage(
                 CoreStage::First,
                 time_system.exclusive_system().label(CoreSystem::Time),
+            )
+            .add_stage_before(
+                CoreStage::Update,
+                FixedUpdateStage,
+                SystemStage::parallel().with_run_criteria(fixed_update_run_criteria),
             );
 
         register_rust_types(app);
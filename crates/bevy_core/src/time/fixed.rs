@@ -0,0 +1,232 @@
+use crate::Time;
+use bevy_utils::Duration;
+
+/// The context of a [`Time`] advancing by a constant timestep, [`Time<Fixed>`].
+///
+/// The delta of [`Time<Virtual>`](crate::Virtual) is accumulated into the
+/// [`overstep`](Time::overstep), and the clock advances by one [`timestep`](Time::timestep) each
+/// time enough of it was accumulated, up to [`max_steps_per_frame`](Time::max_steps_per_frame).
+///
+/// The [`Time<Fixed>`] resource is the clock of the [`FixedUpdateStage`](crate::FixedUpdateStage).
+/// While the systems of this stage run, the [`Time`] resource is a copy of it, so these systems
+/// see a delta equal to the timestep.
+#[derive(Debug, Clone, Copy)]
+pub struct Fixed {
+    timestep: Duration,
+    overstep: Duration,
+    max_steps_per_frame: Option<u32>,
+    step_count: u64,
+    frame_step_count: u32,
+}
+
+impl Default for Fixed {
+    fn default() -> Self {
+        Fixed {
+            timestep: Time::<Fixed>::DEFAULT_TIMESTEP,
+            overstep: Duration::from_secs(0),
+            max_steps_per_frame: None,
+            step_count: 0,
+            frame_step_count: 0,
+        }
+    }
+}
+
+impl Time<Fixed> {
+    /// The default timestep, a 60th of a second.
+    pub const DEFAULT_TIMESTEP: Duration = Duration::from_nanos(16_666_667);
+
+    /// Creates a fixed clock with the given timestep.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timestep` is zero.
+    pub fn from_duration(timestep: Duration) -> Self {
+        let mut time = Self::default();
+        time.set_timestep(timestep);
+        time
+    }
+
+    /// Creates a fixed clock with a timestep of `seconds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seconds` is zero, negative or not finite.
+    pub fn from_seconds(seconds: f64) -> Self {
+        let mut time = Self::default();
+        time.set_timestep_seconds(seconds);
+        time
+    }
+
+    /// Creates a fixed clock that steps `hz` times per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero, negative or not finite.
+    pub fn from_hz(hz: f64) -> Self {
+        let mut time = Self::default();
+        time.set_timestep_hz(hz);
+        time
+    }
+
+    /// The amount of time each step takes.
+    #[inline]
+    pub fn timestep(&self) -> Duration {
+        self.context().timestep
+    }
+
+    /// Sets the amount of time each step takes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timestep` is zero.
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        assert_ne!(
+            timestep,
+            Duration::ZERO,
+            "tried to set fixed timestep to zero"
+        );
+        self.context_mut().timestep = timestep;
+    }
+
+    /// Sets the amount of time each step takes, in seconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `seconds` is zero, negative or not finite.
+    pub fn set_timestep_seconds(&mut self, seconds: f64) {
+        assert!(seconds.is_finite(), "seconds is infinite");
+        assert!(seconds > 0.0, "seconds is zero or negative");
+        self.set_timestep(Duration::from_secs_f64(seconds));
+    }
+
+    /// Sets the number of steps made in a second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hz` is zero, negative or not finite.
+    pub fn set_timestep_hz(&mut self, hz: f64) {
+        assert!(hz.is_normal(), "hz is zero, subnormal or not finite");
+        assert!(hz > 0.0, "hz is negative");
+        self.set_timestep_seconds(1.0 / hz);
+    }
+
+    /// The amount of time accumulated toward the next step.
+    #[inline]
+    pub fn overstep(&self) -> Duration {
+        self.context().overstep
+    }
+
+    /// The percentage of a step accumulated toward the next step, as [`f32`].
+    #[inline]
+    pub fn overstep_percentage(&self) -> f32 {
+        self.overstep_percentage_f64() as f32
+    }
+
+    /// The percentage of a step accumulated toward the next step, as [`f64`].
+    #[inline]
+    pub fn overstep_percentage_f64(&self) -> f64 {
+        self.context().overstep.as_secs_f64() / self.context().timestep.as_secs_f64()
+    }
+
+    /// The maximum number of steps made in a frame, if any.
+    #[inline]
+    pub fn max_steps_per_frame(&self) -> Option<u32> {
+        self.context().max_steps_per_frame
+    }
+
+    /// Sets the maximum number of steps made in a frame. Once it is reached, the whole steps left
+    /// in the overstep are dropped, so that a frame taking longer than the steps it runs doesn't
+    /// make the next frames run ever more steps to catch up.
+    ///
+    /// With `None`, the number of steps is only bounded by the
+    /// [`max_delta`](Time::max_delta) of [`Time<Virtual>`](crate::Virtual).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_steps_per_frame` is zero.
+    pub fn set_max_steps_per_frame(&mut self, max_steps_per_frame: Option<u32>) {
+        assert_ne!(
+            max_steps_per_frame,
+            Some(0),
+            "tried to set max steps per frame to zero"
+        );
+        self.context_mut().max_steps_per_frame = max_steps_per_frame;
+    }
+
+    /// The number of steps made since startup. While the systems of a step run, it includes
+    /// that step, so it is the index of the step starting from 1.
+    #[inline]
+    pub fn step_count(&self) -> u64 {
+        self.context().step_count
+    }
+
+    /// Adds `delta` to the time accumulated toward the next step, starting a new frame.
+    pub(crate) fn accumulate(&mut self, delta: Duration) {
+        let context = self.context_mut();
+        context.overstep += delta;
+        context.frame_step_count = 0;
+    }
+
+    /// Advances the clock by one step if enough time was accumulated, and returns whether it did.
+    pub(crate) fn expend(&mut self) -> bool {
+        let timestep = self.timestep();
+        let context = self.context_mut();
+        if let Some(max_steps_per_frame) = context.max_steps_per_frame {
+            if context.frame_step_count >= max_steps_per_frame {
+                let overstep_nanos = context.overstep.as_nanos() % timestep.as_nanos();
+                context.overstep = Duration::from_nanos(overstep_nanos as u64);
+                return false;
+            }
+        }
+        match context.overstep.checked_sub(timestep) {
+            Some(overstep) => {
+                context.overstep = overstep;
+                context.step_count += 1;
+                context.frame_step_count += 1;
+                self.advance_by(timestep);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::{Fixed, Time};
+    use bevy_utils::Duration;
+
+    #[test]
+    fn accumulate_and_expend() {
+        let mut time = Time::<Fixed>::from_duration(Duration::from_millis(100));
+        time.accumulate(Duration::from_millis(250));
+        assert!(time.expend());
+        assert!(time.expend());
+        assert!(!time.expend());
+        assert_eq!(time.delta(), Duration::from_millis(100));
+        assert_eq!(time.time_since_startup(), Duration::from_millis(200));
+        assert_eq!(time.overstep(), Duration::from_millis(50));
+        assert_eq!(time.overstep_percentage_f64(), 0.5);
+        assert_eq!(time.step_count(), 2);
+    }
+
+    #[test]
+    fn max_steps_per_frame() {
+        let mut time = Time::<Fixed>::from_duration(Duration::from_millis(100));
+        time.set_max_steps_per_frame(Some(2));
+        time.accumulate(Duration::from_millis(450));
+        assert!(time.expend());
+        assert!(time.expend());
+        assert!(!time.expend());
+        // the steps that couldn't be made are dropped
+        assert_eq!(time.overstep(), Duration::from_millis(50));
+        assert_eq!(time.step_count(), 2);
+
+        time.accumulate(Duration::from_millis(100));
+        assert!(time.expend());
+        assert!(!time.expend());
+        assert_eq!(time.step_count(), 3);
+        assert_eq!(time.time_since_startup(), Duration::from_millis(300));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Time;
+use crate::{Fixed, Time, Virtual};
 use bevy_ecs::{
     archetype::{Archetype, ArchetypeComponentId},
     component::ComponentId,
@@ -64,9 +64,14 @@ impl FixedTimesteps {
 /// with ~4.167ms frames. However, the same criteria may not result in exactly 8.333ms passing
 /// between each execution.
 ///
-/// When using this run criteria, it is advised not to rely on [`Time::delta`] or any of it's
-/// variants for game simulation, but rather use the constant time delta used to initialize the
-/// [`FixedTimestep`] instead.
+/// The steps are accumulated from the delta of [`Time<Virtual>`](Virtual), so they are paused
+/// and scaled with the game. The [`Time`] resource is left untouched, so systems running with
+/// this criteria see the delta of the frame: use the step of a labeled timestep from the
+/// [`FixedTimesteps`] resource instead.
+///
+/// For a single fixed timestep driving the game simulation, prefer the
+/// [`FixedUpdateStage`](crate::FixedUpdateStage), whose systems read its clock as
+/// [`Res<Time<Fixed>>`](Fixed).
 ///
 /// For more fine tuned information about the execution status of a given fixed timestep,
 /// use the [`FixedTimesteps`] resource.
@@ -91,7 +96,7 @@ impl FixedTimestep {
     pub fn step(step: f64) -> Self {
         Self {
             state: LocalFixedTimestepState {
-                step,
+                time: Time::<Fixed>::from_seconds(step),
                 ..Default::default()
             },
             ..Default::default()
@@ -102,7 +107,7 @@ impl FixedTimestep {
     pub fn steps_per_second(rate: f64) -> Self {
         Self {
             state: LocalFixedTimestepState {
-                step: 1.0 / rate,
+                time: Time::<Fixed>::from_hz(rate),
                 ..Default::default()
             },
             ..Default::default()
@@ -119,13 +124,13 @@ impl FixedTimestep {
 
     fn prepare_system(
         mut state: LocalFixedTimestepState,
-    ) -> impl FnMut(Res<Time>, ResMut<FixedTimesteps>) -> ShouldRun {
-        move |time, mut fixed_timesteps| {
-            let should_run = state.update(&time);
+    ) -> impl FnMut(Res<Time<Virtual>>, ResMut<FixedTimesteps>) -> ShouldRun {
+        move |virtual_time, mut fixed_timesteps| {
+            let should_run = state.update(&virtual_time);
             if let Some(ref label) = state.label {
                 let res_state = fixed_timesteps.fixed_timesteps.get_mut(label).unwrap();
-                res_state.step = state.step;
-                res_state.accumulator = state.accumulator;
+                res_state.step = state.time.timestep().as_secs_f64();
+                res_state.accumulator = state.time.overstep().as_secs_f64();
             }
 
             should_run
@@ -133,33 +138,20 @@ impl FixedTimestep {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct LocalFixedTimestepState {
     label: Option<String>, // TODO: consider making this a TypedLabel
-    step: f64,
-    accumulator: f64,
+    time: Time<Fixed>,
     looping: bool,
 }
 
-impl Default for LocalFixedTimestepState {
-    fn default() -> Self {
-        Self {
-            step: 1.0 / 60.0,
-            accumulator: 0.0,
-            label: None,
-            looping: false,
-        }
-    }
-}
-
 impl LocalFixedTimestepState {
-    fn update(&mut self, time: &Time) -> ShouldRun {
+    fn update(&mut self, virtual_time: &Time<Virtual>) -> ShouldRun {
         if !self.looping {
-            self.accumulator += time.delta_seconds_f64();
+            self.time.accumulate(virtual_time.delta());
         }
 
-        if self.accumulator >= self.step {
-            self.accumulator -= self.step;
+        if self.time.expend() {
             self.looping = true;
             ShouldRun::YesAndCheckAgain
         } else {
@@ -214,7 +206,7 @@ impl System for FixedTimestep {
                 label.clone(),
                 FixedTimestepState {
                     accumulator: 0.0,
-                    step: self.state.step,
+                    step: self.state.time.timestep().as_secs_f64(),
                 },
             );
         }
@@ -229,8 +221,7 @@ impl System for FixedTimestep {
 mod test {
     use super::*;
     use bevy_ecs::prelude::*;
-    use bevy_utils::Instant;
-    use std::ops::{Add, Mul};
+    use std::ops::Mul;
     use std::time::Duration;
 
     type Count = usize;
@@ -239,10 +230,8 @@ mod test {
     #[test]
     fn test() {
         let mut world = World::default();
-        let mut time = Time::default();
-        let instance = Instant::now();
-        time.update_with_instant(instance);
-        world.insert_resource(time);
+        world.insert_resource(Time::<()>::default());
+        world.insert_resource(Time::<Virtual>::default());
         world.insert_resource(FixedTimesteps::default());
         world.insert_resource::<Count>(0);
         let mut schedule = Schedule::default();
@@ -261,32 +250,39 @@ mod test {
         assert_eq!(0., get_accumulator_deciseconds(&world));
 
         // let's progress less than one step
-        advance_time(&mut world, instance, 0.4);
+        advance_time(&mut world, 0.4);
         schedule.run(&mut world);
         assert_eq!(0, *world.resource::<Count>());
         assert_eq!(4., get_accumulator_deciseconds(&world));
 
         // finish the first step with 0.1s above the step length
-        advance_time(&mut world, instance, 0.6);
+        advance_time(&mut world, 0.6);
         schedule.run(&mut world);
         assert_eq!(1, *world.resource::<Count>());
         assert_eq!(1., get_accumulator_deciseconds(&world));
 
         // runs multiple times if the delta is multiple step lengths
-        advance_time(&mut world, instance, 1.7);
+        advance_time(&mut world, 1.7);
         schedule.run(&mut world);
         assert_eq!(3, *world.resource::<Count>());
         assert_eq!(2., get_accumulator_deciseconds(&world));
+
+        // the shared `Time` resource is not touched by the timestep
+        assert_eq!(
+            world.resource::<Time>().time_since_startup(),
+            Duration::from_secs(0)
+        );
     }
 
-    fn fixed_update(mut count: ResMut<Count>) {
+    fn fixed_update(mut count: ResMut<Count>, fixed_timesteps: Res<FixedTimesteps>) {
+        assert_eq!(fixed_timesteps.get(LABEL).unwrap().step(), 0.5);
         *count += 1;
     }
 
-    fn advance_time(world: &mut World, instance: Instant, seconds: f32) {
+    fn advance_time(world: &mut World, seconds: f32) {
         world
-            .resource_mut::<Time>()
-            .update_with_instant(instance.add(Duration::from_secs_f32(seconds)));
+            .resource_mut::<Time<Virtual>>()
+            .advance_to(Duration::from_secs_f32(seconds));
     }
 
     fn get_accumulator_deciseconds(world: &World) -> f64 {
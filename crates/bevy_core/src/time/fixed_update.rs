@@ -0,0 +1,118 @@
+use crate::{Fixed, Time, Virtual};
+use bevy_ecs::{
+    schedule::{ShouldRun, StageLabel},
+    system::{Local, Res, ResMut},
+};
+
+/// The label of the stage that runs its systems at the fixed timestep of the
+/// [`Time<Fixed>`](Fixed) resource, added by the [`CorePlugin`](crate::CorePlugin) before
+/// [`CoreStage::Update`](bevy_app::CoreStage::Update).
+///
+/// The stage runs as many steps as the [`Time<Virtual>`](Virtual) delta accumulated since the
+/// last frame allows, possibly none, and up to the
+/// [`max_steps_per_frame`](Time::max_steps_per_frame) of [`Time<Fixed>`](Fixed). While its
+/// systems run, the [`Time`] resource is a copy of [`Time<Fixed>`](Fixed), so its delta is the
+/// timestep. The [`Time<Fixed>`](Fixed) resource itself can be read with `Res<Time<Fixed>>`,
+/// e.g. for the index of the current step, starting from 1, given by its
+/// [`step_count`](Time::step_count).
+///
+/// Since the steps don't line up with the frames, rendering the state of the last step makes
+/// movement stutter. Instead, the state can be interpolated between the last two steps with
+/// [`Time::overstep_percentage`], e.g. with `PreviousTransform` from `bevy_transform`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_app::prelude::*;
+/// # use bevy_core::{CorePlugin, Fixed, FixedUpdateStage, Time};
+/// # use bevy_ecs::prelude::*;
+/// #[derive(Component)]
+/// struct Velocity(f32);
+///
+/// #[derive(Component)]
+/// struct Position(f32);
+///
+/// fn integrate(time: Res<Time>, mut query: Query<(&mut Position, &Velocity)>) {
+///     for (mut position, velocity) in query.iter_mut() {
+///         position.0 += velocity.0 * time.delta_seconds();
+///     }
+/// }
+///
+/// App::new()
+///     .insert_resource(Time::<Fixed>::from_hz(50.0))
+///     .add_plugin(CorePlugin)
+///     .add_system_to_stage(FixedUpdateStage, integrate);
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
+pub struct FixedUpdateStage;
+
+/// The run criteria of the [`FixedUpdateStage`], which runs it once per step of
+/// [`Time<Fixed>`](Fixed).
+pub(crate) fn fixed_update_run_criteria(
+    mut time: ResMut<Time>,
+    virtual_time: Res<Time<Virtual>>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut looping: Local<bool>,
+) -> ShouldRun {
+    if !*looping {
+        fixed_time.accumulate(virtual_time.delta());
+    }
+
+    if fixed_time.expend() {
+        *looping = true;
+        *time = fixed_time.as_generic();
+        ShouldRun::YesAndCheckAgain
+    } else {
+        *looping = false;
+        *time = virtual_time.as_generic();
+        ShouldRun::No
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CorePlugin, Fixed, FixedUpdateStage, Time, TimeUpdateStrategy};
+    use bevy_app::App;
+    use bevy_ecs::prelude::*;
+    use bevy_utils::Duration;
+
+    #[derive(Default)]
+    struct Steps(Vec<(u64, Duration)>);
+
+    fn record_step(mut steps: ResMut<Steps>, time: Res<Time>, fixed_time: Res<Time<Fixed>>) {
+        steps.0.push((fixed_time.step_count(), time.delta()));
+    }
+
+    #[test]
+    fn fixed_update() {
+        let mut app = App::new();
+        app.insert_resource(Time::<Fixed>::from_duration(Duration::from_millis(100)))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                150,
+            )))
+            .init_resource::<Steps>()
+            .add_plugin(CorePlugin)
+            .add_system_to_stage(FixedUpdateStage, record_step);
+
+        // the first update has no delta
+        app.update();
+        assert!(app.world.resource::<Steps>().0.is_empty());
+
+        app.update();
+        app.update();
+        let step = Duration::from_millis(100);
+        assert_eq!(
+            app.world.resource::<Steps>().0,
+            vec![(1, step), (2, step), (3, step)]
+        );
+        assert_eq!(
+            app.world.resource::<Time<Fixed>>().overstep(),
+            Duration::from_secs(0)
+        );
+        // `Time` is back to the virtual time after the stage
+        assert_eq!(
+            app.world.resource::<Time>().delta(),
+            Duration::from_millis(150)
+        );
+    }
+}
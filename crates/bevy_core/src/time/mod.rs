@@ -1,10 +1,18 @@
+mod fixed;
 mod fixed_timestep;
+mod fixed_update;
+mod real;
 mod stopwatch;
 #[allow(clippy::module_inception)]
 mod time;
 mod timer;
+mod virt;
 
+pub use fixed::*;
 pub use fixed_timestep::*;
+pub use fixed_update::*;
+pub use real::*;
 pub use stopwatch::*;
 pub use time::*;
 pub use timer::*;
+pub use virt::*;
@@ -0,0 +1,135 @@
+use crate::Time;
+use bevy_utils::{Duration, Instant};
+
+/// The context of a [`Time`] following the wall clock, [`Time<Real>`].
+///
+/// It is updated at the start of each frame from the current [`Instant`], or as configured by the
+/// [`TimeUpdateStrategy`](crate::TimeUpdateStrategy) resource. It keeps advancing when
+/// [`Time<Virtual>`](crate::Virtual) is paused or scaled, so it is suited to UI animations,
+/// diagnostics and anything else that must not depend on the game speed.
+#[derive(Debug, Clone, Copy)]
+pub struct Real {
+    startup: Instant,
+    last_update: Option<Instant>,
+}
+
+impl Default for Real {
+    fn default() -> Self {
+        Real {
+            startup: Instant::now(),
+            last_update: None,
+        }
+    }
+}
+
+impl Time<Real> {
+    /// Creates a real time clock that started at `startup`.
+    pub fn new(startup: Instant) -> Self {
+        Self::new_with(Real {
+            startup,
+            last_update: None,
+        })
+    }
+
+    /// Updates the internal time measurements.
+    pub fn update(&mut self) {
+        self.update_with_instant(Instant::now());
+    }
+
+    /// Updates the internal time measurements as if the current time was `instant`.
+    ///
+    /// The first update has a delta of zero.
+    pub fn update_with_instant(&mut self, instant: Instant) {
+        let delta = match self.context().last_update {
+            Some(last_update) => instant - last_update,
+            None => Duration::from_secs(0),
+        };
+        // the time between the startup and the first update is not part of any delta
+        let time_since_startup = instant - self.context().startup;
+        self.set(delta, time_since_startup);
+        self.context_mut().last_update = Some(instant);
+    }
+
+    /// Updates the internal time measurements as if `duration` had passed since the last update,
+    /// or since startup for the first update.
+    pub fn update_with_duration(&mut self, duration: Duration) {
+        let last_update = self.context().last_update.unwrap_or(self.context().startup);
+        self.update_with_instant(last_update + duration);
+    }
+
+    /// The [`Instant`] the app was started
+    #[inline]
+    pub fn startup(&self) -> Instant {
+        self.context().startup
+    }
+
+    /// The [`Instant`] when [`Time::update`] was last called, if it exists
+    #[inline]
+    pub fn last_update(&self) -> Option<Instant> {
+        self.context().last_update
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::{Real, Time};
+    use bevy_utils::{Duration, Instant};
+
+    #[test]
+    fn update_test() {
+        let start_instant = Instant::now();
+
+        // Create a `Time` for testing
+        let mut time = Time::<Real>::new(start_instant);
+
+        // Ensure `time` was constructed correctly
+        assert_eq!(time.delta(), Duration::from_secs(0));
+        assert_eq!(time.last_update(), None);
+        assert_eq!(time.startup(), start_instant);
+        assert_eq!(time.delta_seconds_f64(), 0.0);
+        assert_eq!(time.seconds_since_startup(), 0.0);
+        assert_eq!(time.time_since_startup(), Duration::from_secs(0));
+        assert_eq!(time.delta_seconds(), 0.0);
+
+        // Update `time` and check results
+        let first_update_instant = Instant::now();
+
+        time.update_with_instant(first_update_instant);
+
+        assert_eq!(time.delta(), Duration::from_secs(0));
+        assert_eq!(time.last_update(), Some(first_update_instant));
+        assert_eq!(time.startup(), start_instant);
+        assert_eq!(time.delta_seconds_f64(), 0.0);
+        assert_eq!(
+            time.seconds_since_startup(),
+            (first_update_instant - start_instant).as_secs_f64()
+        );
+        assert_eq!(
+            time.time_since_startup(),
+            (first_update_instant - start_instant)
+        );
+        assert_eq!(time.delta_seconds(), 0.0);
+
+        // Update `time` again and check results
+        let second_update_instant = Instant::now();
+
+        time.update_with_instant(second_update_instant);
+
+        assert_eq!(time.delta(), second_update_instant - first_update_instant);
+        assert_eq!(time.last_update(), Some(second_update_instant));
+        assert_eq!(time.startup(), start_instant);
+        // At this point its safe to use time.delta as a valid value
+        // because it's been previously verified to be correct
+        assert_eq!(time.delta_seconds_f64(), time.delta().as_secs_f64());
+        assert_eq!(
+            time.seconds_since_startup(),
+            (second_update_instant - start_instant).as_secs_f64()
+        );
+        assert_eq!(
+            time.time_since_startup(),
+            (second_update_instant - start_instant)
+        );
+        assert_eq!(time.delta_seconds(), time.delta().as_secs_f32());
+    }
+}
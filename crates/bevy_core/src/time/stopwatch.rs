@@ -101,6 +101,10 @@ impl Stopwatch {
     /// If the stopwatch is paused, ticking will not have any effect
     /// on elapsed time.
     ///
+    /// The delta of any clock can be used, e.g. [`Time::delta`](crate::Time::delta) to follow
+    /// the game time, or the delta of [`Time<Real>`](crate::Real) to keep ticking while the game
+    /// is paused.
+    ///
     /// # Examples
     /// ```
     /// # use bevy_core::*;
@@ -1,48 +1,75 @@
-use bevy_ecs::system::ResMut;
+use crate::{Real, Virtual};
+use bevy_ecs::system::{Res, ResMut};
 use bevy_utils::{Duration, Instant};
 
-/// Tracks elapsed time since the last update and since the App has started
-#[derive(Debug, Clone)]
-pub struct Time {
+/// A clock that tracks the elapsed time since the last update and since the App has started.
+///
+/// The kind of clock is given by its context `T`:
+/// - [`Time<Real>`](Real) follows the wall clock, even when the game is paused.
+/// - [`Time<Virtual>`](Virtual) follows the real time, scaled by a relative speed, stopped when
+///   paused and clamped to a maximum delta.
+/// - [`Time<Fixed>`](crate::Fixed) advances by a constant timestep, and is the clock of the
+///   [`FixedUpdateStage`](crate::FixedUpdateStage).
+///
+/// The [`Time`] resource, with the default `()` context, is the clock that systems should
+/// normally use. It is a copy of [`Time<Virtual>`](Virtual), except in the systems of the
+/// [`FixedUpdateStage`](crate::FixedUpdateStage), where it is a copy of
+/// [`Time<Fixed>`](crate::Fixed). This lets the same systems run either once per frame or at a
+/// fixed timestep.
+#[derive(Debug, Clone, Copy)]
+pub struct Time<T: Default = ()> {
+    context: T,
     delta: Duration,
-    last_update: Option<Instant>,
-    delta_seconds_f64: f64,
     delta_seconds: f32,
-    seconds_since_startup: f64,
+    delta_seconds_f64: f64,
     time_since_startup: Duration,
-    startup: Instant,
+    seconds_since_startup: f64,
+}
+
+impl<T: Default> Default for Time<T> {
+    fn default() -> Self {
+        Self::new_with(T::default())
+    }
 }
 
-impl Default for Time {
-    fn default() -> Time {
+impl<T: Default> Time<T> {
+    /// Creates a clock with the given context, that hasn't advanced yet.
+    pub fn new_with(context: T) -> Self {
         Time {
+            context,
             delta: Duration::from_secs(0),
-            last_update: None,
-            startup: Instant::now(),
+            delta_seconds: 0.0,
             delta_seconds_f64: 0.0,
-            seconds_since_startup: 0.0,
             time_since_startup: Duration::from_secs(0),
-            delta_seconds: 0.0,
+            seconds_since_startup: 0.0,
         }
     }
-}
 
-impl Time {
-    /// Updates the internal time measurements.
-    pub fn update(&mut self) {
-        self.update_with_instant(Instant::now());
+    /// Advances the clock by `delta`, which becomes the delta of the current tick.
+    pub fn advance_by(&mut self, delta: Duration) {
+        self.set(delta, self.time_since_startup + delta);
     }
 
-    pub(crate) fn update_with_instant(&mut self, instant: Instant) {
-        if let Some(last_update) = self.last_update {
-            self.delta = instant - last_update;
-            self.delta_seconds_f64 = self.delta.as_secs_f64();
-            self.delta_seconds = self.delta.as_secs_f32();
-        }
+    /// Advances the clock to `time_since_startup`, the delta of the current tick being the
+    /// difference with the previous time since startup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time_since_startup` is less than the current time since startup.
+    pub fn advance_to(&mut self, time_since_startup: Duration) {
+        assert!(
+            time_since_startup >= self.time_since_startup,
+            "tried to move the clock backwards to an earlier time since startup"
+        );
+        self.advance_by(time_since_startup - self.time_since_startup);
+    }
 
-        self.time_since_startup = instant - self.startup;
-        self.seconds_since_startup = self.time_since_startup.as_secs_f64();
-        self.last_update = Some(instant);
+    pub(crate) fn set(&mut self, delta: Duration, time_since_startup: Duration) {
+        self.delta = delta;
+        self.delta_seconds = delta.as_secs_f32();
+        self.delta_seconds_f64 = delta.as_secs_f64();
+        self.time_since_startup = time_since_startup;
+        self.seconds_since_startup = time_since_startup.as_secs_f64();
     }
 
     /// The delta between the current tick and last tick as a [`Duration`]
@@ -69,92 +96,153 @@ impl Time {
         self.seconds_since_startup
     }
 
-    /// The [`Instant`] the app was started
+    /// The ['Duration'] from startup to the last update
     #[inline]
-    pub fn startup(&self) -> Instant {
-        self.startup
+    pub fn time_since_startup(&self) -> Duration {
+        self.time_since_startup
     }
 
-    /// The ['Instant'] when [`Time::update`] was last called, if it exists
+    /// The context of the clock, which holds the state specific to its kind.
     #[inline]
-    pub fn last_update(&self) -> Option<Instant> {
-        self.last_update
+    pub fn context(&self) -> &T {
+        &self.context
     }
 
-    /// The ['Duration'] from startup to the last update
+    /// The mutable context of the clock, which holds the state specific to its kind.
     #[inline]
-    pub fn time_since_startup(&self) -> Duration {
-        self.time_since_startup
+    pub fn context_mut(&mut self) -> &mut T {
+        &mut self.context
+    }
+
+    /// Returns a copy of this clock without its context, e.g. to expose it as the [`Time`]
+    /// resource.
+    pub fn as_generic(&self) -> Time<()> {
+        Time {
+            context: (),
+            delta: self.delta,
+            delta_seconds: self.delta_seconds,
+            delta_seconds_f64: self.delta_seconds_f64,
+            time_since_startup: self.time_since_startup,
+            seconds_since_startup: self.seconds_since_startup,
+        }
+    }
+}
+
+/// Configures how [`Time<Real>`](Real) is updated at the start of each frame. When the resource
+/// is absent, the time is updated automatically.
+///
+/// The manual strategies make the time deterministic, e.g. to step an app in tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUpdateStrategy {
+    /// Uses the current [`Instant`].
+    Automatic,
+    /// Uses the given [`Instant`] as the time of the update.
+    ManualInstant(Instant),
+    /// Advances the time by the given [`Duration`] at each update.
+    ManualDuration(Duration),
+}
+
+// deriving `Default` for an enum requires Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl Default for TimeUpdateStrategy {
+    fn default() -> Self {
+        TimeUpdateStrategy::Automatic
     }
 }
 
-pub(crate) fn time_system(mut time: ResMut<Time>) {
-    time.update();
+/// Updates [`Time<Real>`](Real), advances [`Time<Virtual>`](Virtual) accordingly, and copies it
+/// to the [`Time`] resource.
+pub(crate) fn time_system(
+    mut real_time: ResMut<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut time: ResMut<Time>,
+    update_strategy: Option<Res<TimeUpdateStrategy>>,
+) {
+    match update_strategy.as_deref() {
+        None | Some(TimeUpdateStrategy::Automatic) => real_time.update(),
+        Some(TimeUpdateStrategy::ManualInstant(instant)) => {
+            real_time.update_with_instant(*instant);
+        }
+        Some(TimeUpdateStrategy::ManualDuration(duration)) => {
+            real_time.update_with_duration(*duration);
+        }
+    }
+    virtual_time.advance_with_raw_delta(real_time.delta());
+    *time = virtual_time.as_generic();
 }
 
 #[cfg(test)]
 #[allow(clippy::float_cmp)]
 mod tests {
-    use super::Time;
-    use bevy_utils::{Duration, Instant};
+    use super::{Time, TimeUpdateStrategy};
+    use crate::{CorePlugin, Real, Virtual};
+    use bevy_app::App;
+    use bevy_utils::Duration;
 
     #[test]
-    fn update_test() {
-        let start_instant = Instant::now();
-
-        // Create a `Time` for testing
-        let mut time = Time {
-            startup: start_instant,
-            ..Default::default()
-        };
-
-        // Ensure `time` was constructed correctly
+    fn advance() {
+        let mut time = Time::<()>::default();
         assert_eq!(time.delta(), Duration::from_secs(0));
-        assert_eq!(time.last_update(), None);
-        assert_eq!(time.startup(), start_instant);
-        assert_eq!(time.delta_seconds_f64(), 0.0);
-        assert_eq!(time.seconds_since_startup(), 0.0);
         assert_eq!(time.time_since_startup(), Duration::from_secs(0));
-        assert_eq!(time.delta_seconds(), 0.0);
 
-        // Update `time` and check results
-        let first_update_instant = Instant::now();
+        time.advance_by(Duration::from_millis(250));
+        assert_eq!(time.delta(), Duration::from_millis(250));
+        assert_eq!(time.delta_seconds(), 0.25);
+        assert_eq!(time.delta_seconds_f64(), 0.25);
+        assert_eq!(time.time_since_startup(), Duration::from_millis(250));
+        assert_eq!(time.seconds_since_startup(), 0.25);
+
+        time.advance_to(Duration::from_secs(1));
+        assert_eq!(time.delta(), Duration::from_millis(750));
+        assert_eq!(time.time_since_startup(), Duration::from_secs(1));
+        assert_eq!(time.seconds_since_startup(), 1.0);
+    }
+
+    #[test]
+    fn manual_duration() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                20,
+            )));
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let real_time = app.world.resource::<Time<Real>>();
+        assert_eq!(real_time.delta(), Duration::from_millis(20));
+        assert_eq!(real_time.time_since_startup(), Duration::from_millis(60));
 
-        time.update_with_instant(first_update_instant);
+        // the virtual time starts on the first update
+        let time = app.world.resource::<Time>();
+        assert_eq!(time.delta(), Duration::from_millis(20));
+        assert_eq!(time.time_since_startup(), Duration::from_millis(40));
+    }
 
+    #[test]
+    fn paused_and_scaled() {
+        let mut app = App::new();
+        app.add_plugin(CorePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app.update();
+
+        app.world
+            .resource_mut::<Time<Virtual>>()
+            .set_relative_speed(2.0);
+        app.update();
+        let time = app.world.resource::<Time>();
+        assert_eq!(time.delta(), Duration::from_millis(200));
+        assert_eq!(time.time_since_startup(), Duration::from_millis(200));
+
+        app.world.resource_mut::<Time<Virtual>>().pause();
+        app.update();
+        let time = app.world.resource::<Time>();
         assert_eq!(time.delta(), Duration::from_secs(0));
-        assert_eq!(time.last_update(), Some(first_update_instant));
-        assert_eq!(time.startup(), start_instant);
-        assert_eq!(time.delta_seconds_f64(), 0.0);
-        assert_eq!(
-            time.seconds_since_startup(),
-            (first_update_instant - start_instant).as_secs_f64()
-        );
-        assert_eq!(
-            time.time_since_startup(),
-            (first_update_instant - start_instant)
-        );
-        assert_eq!(time.delta_seconds, 0.0);
-
-        // Update `time` again and check results
-        let second_update_instant = Instant::now();
-
-        time.update_with_instant(second_update_instant);
-
-        assert_eq!(time.delta(), second_update_instant - first_update_instant);
-        assert_eq!(time.last_update(), Some(second_update_instant));
-        assert_eq!(time.startup(), start_instant);
-        // At this point its safe to use time.delta as a valid value
-        // because it's been previously verified to be correct
-        assert_eq!(time.delta_seconds_f64(), time.delta().as_secs_f64());
-        assert_eq!(
-            time.seconds_since_startup(),
-            (second_update_instant - start_instant).as_secs_f64()
-        );
-        assert_eq!(
-            time.time_since_startup(),
-            (second_update_instant - start_instant)
-        );
-        assert_eq!(time.delta_seconds(), time.delta().as_secs_f32());
+        assert_eq!(time.time_since_startup(), Duration::from_millis(200));
+        let real_time = app.world.resource::<Time<Real>>();
+        assert_eq!(real_time.delta(), Duration::from_millis(100));
+        assert_eq!(real_time.time_since_startup(), Duration::from_millis(300));
     }
 }
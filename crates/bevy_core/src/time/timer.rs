@@ -187,6 +187,10 @@ impl Timer {
     /// Non repeating timer will clamp at duration.
     /// Repeating timer will wrap around.
     ///
+    /// The delta of any clock can be used, e.g. [`Time::delta`](crate::Time::delta) to follow
+    /// the game time, or the delta of [`Time<Real>`](crate::Real) to keep ticking while the game
+    /// is paused.
+    ///
     /// See also [`Stopwatch::tick`](Stopwatch::tick).
     ///
     /// # Examples
@@ -0,0 +1,197 @@
+use crate::Time;
+use bevy_utils::Duration;
+
+/// The context of a [`Time`] following the game time, [`Time<Virtual>`].
+///
+/// It advances by the delta of [`Time<Real>`](crate::Real) at the start of each frame, with a few
+/// adjustments:
+/// - the delta is clamped to [`max_delta`](Time::max_delta), so that a hitch, e.g. while the
+///   window is dragged, doesn't make the game jump forward,
+/// - the delta is scaled by the [`relative_speed`](Time::relative_speed), e.g. for slow motion,
+/// - the clock doesn't advance while [`paused`](Time::is_paused).
+///
+/// The [`Time`] resource is a copy of this clock outside of fixed timesteps, so gameplay systems
+/// using it are paused and scaled with the game.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_core::{Time, Virtual};
+/// # use bevy_ecs::prelude::*;
+/// fn toggle_slow_motion(mut time: ResMut<Time<Virtual>>) {
+///     if time.relative_speed() == 1.0 {
+///         time.set_relative_speed(0.25);
+///     } else {
+///         time.set_relative_speed(1.0);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Virtual {
+    max_delta: Duration,
+    paused: bool,
+    relative_speed: f64,
+    effective_speed: f64,
+}
+
+impl Default for Virtual {
+    fn default() -> Self {
+        Virtual {
+            max_delta: Time::<Virtual>::DEFAULT_MAX_DELTA,
+            paused: false,
+            relative_speed: 1.0,
+            effective_speed: 1.0,
+        }
+    }
+}
+
+impl Time<Virtual> {
+    /// The default maximum delta of a frame, 250 milliseconds.
+    pub const DEFAULT_MAX_DELTA: Duration = Duration::from_millis(250);
+
+    /// Creates a virtual clock with the given maximum delta.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_delta` is zero.
+    pub fn from_max_delta(max_delta: Duration) -> Self {
+        let mut time = Self::default();
+        time.set_max_delta(max_delta);
+        time
+    }
+
+    /// The maximum delta of a frame. A longer real time delta only advances the clock by this
+    /// amount.
+    #[inline]
+    pub fn max_delta(&self) -> Duration {
+        self.context().max_delta
+    }
+
+    /// Sets the maximum delta of a frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_delta` is zero.
+    pub fn set_max_delta(&mut self, max_delta: Duration) {
+        assert_ne!(max_delta, Duration::ZERO, "tried to set max delta to zero");
+        self.context_mut().max_delta = max_delta;
+    }
+
+    /// The speed of the clock relative to the real time, as [`f32`].
+    #[inline]
+    pub fn relative_speed(&self) -> f32 {
+        self.context().relative_speed as f32
+    }
+
+    /// The speed of the clock relative to the real time, as [`f64`].
+    #[inline]
+    pub fn relative_speed_f64(&self) -> f64 {
+        self.context().relative_speed
+    }
+
+    /// The speed at which the clock advanced during the last update: zero if it was paused, the
+    /// relative speed otherwise.
+    #[inline]
+    pub fn effective_speed(&self) -> f32 {
+        self.context().effective_speed as f32
+    }
+
+    /// The speed at which the clock advanced during the last update, as [`f64`].
+    #[inline]
+    pub fn effective_speed_f64(&self) -> f64 {
+        self.context().effective_speed
+    }
+
+    /// Sets the speed of the clock relative to the real time, e.g. `0.5` to run the game at half
+    /// speed. It applies from the next update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is negative or not finite.
+    pub fn set_relative_speed(&mut self, ratio: f32) {
+        self.set_relative_speed_f64(ratio as f64);
+    }
+
+    /// Sets the speed of the clock relative to the real time, as [`f64`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is negative or not finite.
+    pub fn set_relative_speed_f64(&mut self, ratio: f64) {
+        assert!(ratio.is_finite(), "tried to go infinitely fast");
+        assert!(ratio >= 0.0, "tried to go back in time");
+        self.context_mut().relative_speed = ratio;
+    }
+
+    /// Stops the clock from the next update, until it is [unpaused](Self::unpause).
+    #[inline]
+    pub fn pause(&mut self) {
+        self.context_mut().paused = true;
+    }
+
+    /// Resumes the clock from the next update.
+    #[inline]
+    pub fn unpause(&mut self) {
+        self.context_mut().paused = false;
+    }
+
+    /// Returns `true` if the clock is paused.
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        self.context().paused
+    }
+
+    /// Advances the clock by the delta of the real time, adjusted by the context.
+    pub(crate) fn advance_with_raw_delta(&mut self, raw_delta: Duration) {
+        let clamped_delta = raw_delta.min(self.max_delta());
+        let effective_speed = if self.is_paused() {
+            0.0
+        } else {
+            self.relative_speed_f64()
+        };
+        let delta = if effective_speed == 1.0 {
+            clamped_delta
+        } else {
+            clamped_delta.mul_f64(effective_speed)
+        };
+        self.context_mut().effective_speed = effective_speed;
+        self.advance_by(delta);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use crate::{Time, Virtual};
+    use bevy_utils::Duration;
+
+    #[test]
+    fn max_delta() {
+        let mut time = Time::<Virtual>::from_max_delta(Duration::from_millis(100));
+        time.advance_with_raw_delta(Duration::from_millis(80));
+        assert_eq!(time.delta(), Duration::from_millis(80));
+        time.advance_with_raw_delta(Duration::from_secs(2));
+        assert_eq!(time.delta(), Duration::from_millis(100));
+        assert_eq!(time.time_since_startup(), Duration::from_millis(180));
+    }
+
+    #[test]
+    fn relative_speed_and_pause() {
+        let mut time = Time::<Virtual>::default();
+        time.set_relative_speed(0.5);
+        time.advance_with_raw_delta(Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::from_millis(50));
+        assert_eq!(time.effective_speed(), 0.5);
+
+        time.pause();
+        time.advance_with_raw_delta(Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::from_secs(0));
+        assert_eq!(time.effective_speed(), 0.0);
+        assert_eq!(time.relative_speed(), 0.5);
+
+        time.unpause();
+        time.advance_with_raw_delta(Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::from_millis(50));
+        assert_eq!(time.time_since_startup(), Duration::from_millis(100));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{Diagnostic, DiagnosticId, Diagnostics};
 use bevy_app::prelude::*;
-use bevy_core::Time;
+use bevy_core::{Real, Time};
 use bevy_ecs::system::{Res, ResMut};
 
 /// Adds "frame time" diagnostic to an App, specifically "frame time", "fps" and "frame count"
@@ -34,7 +34,7 @@ impl FrameTimeDiagnosticsPlugin {
 
     pub fn diagnostic_system(
         mut diagnostics: ResMut<Diagnostics>,
-        time: Res<Time>,
+        time: Res<Time<Real>>,
         mut state: ResMut<FrameTimeDiagnosticsState>,
     ) {
         state.frame_count += 1.0;
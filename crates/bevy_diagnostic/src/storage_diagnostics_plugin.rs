@@ -0,0 +1,212 @@
+use bevy_app::{App, Plugin};
+use bevy_ecs::{
+    archetype::ArchetypeId,
+    storage::TableId,
+    system::{IntoExclusiveSystem, ResMut},
+    world::World,
+};
+
+use crate::{Diagnostic, DiagnosticId, Diagnostics};
+
+/// Adds diagnostics about the archetypes and the storages of the ECS data to an App, specifically
+/// the number of archetypes, of fragmented archetypes, of tables and of sparse sets, the number
+/// of rows of the tables and the bytes allocated by the tables and the sparse sets.
+///
+/// The row count and memory usage of each table are stored in the [`StorageDiagnosticsState`]
+/// resource.
+pub struct StorageDiagnosticsPlugin {
+    /// Archetypes with fewer entities than this are counted as fragmented.
+    pub fragmentation_threshold: usize,
+}
+
+impl Default for StorageDiagnosticsPlugin {
+    fn default() -> Self {
+        StorageDiagnosticsPlugin {
+            fragmentation_threshold: 8,
+        }
+    }
+}
+
+/// The size of a table, measured by [`StorageDiagnosticsPlugin::diagnostic_system`].
+#[derive(Debug, Clone)]
+pub struct TableDiagnostics {
+    pub id: TableId,
+    /// The number of entities stored in the table.
+    pub len: usize,
+    /// The number of entities the table can store without reallocating.
+    pub capacity: usize,
+    /// The bytes allocated for the entities and components of the table.
+    pub allocated_bytes: usize,
+}
+
+/// State used by the [`StorageDiagnosticsPlugin`]
+pub struct StorageDiagnosticsState {
+    pub fragmentation_threshold: usize,
+    /// The size of each table, as of the last update.
+    pub tables: Vec<TableDiagnostics>,
+}
+
+impl Plugin for StorageDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StorageDiagnosticsState {
+            fragmentation_threshold: self.fragmentation_threshold,
+            tables: Vec::new(),
+        })
+        .add_startup_system(Self::setup_system)
+        .add_system(Self::diagnostic_system.exclusive_system());
+    }
+}
+
+impl StorageDiagnosticsPlugin {
+    pub const ARCHETYPE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(126680585041739627546218894840721156455);
+    pub const FRAGMENTED_ARCHETYPE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(59406757269225696982572572159359323362);
+    pub const TABLE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(22288686745580902238939811766919516300);
+    pub const TABLE_ROW_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(13348037848688542943385331183874091458);
+    pub const TABLE_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(162497157757224007708127365505778120477);
+    pub const SPARSE_SET_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(65170794007504022180375341788488017939);
+    pub const SPARSE_SET_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(30395750689619761961484904426262328145);
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(
+            Self::ARCHETYPE_COUNT,
+            "archetype_count",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(
+            Self::FRAGMENTED_ARCHETYPE_COUNT,
+            "fragmented_archetype_count",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(Self::TABLE_COUNT, "table_count", 20));
+        diagnostics.add(Diagnostic::new(
+            Self::TABLE_ROW_COUNT,
+            "table_row_count",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(Self::TABLE_BYTES, "table_bytes", 20).with_suffix("B"));
+        diagnostics.add(Diagnostic::new(
+            Self::SPARSE_SET_COUNT,
+            "sparse_set_count",
+            20,
+        ));
+        diagnostics
+            .add(Diagnostic::new(Self::SPARSE_SET_BYTES, "sparse_set_bytes", 20).with_suffix("B"));
+    }
+
+    pub fn diagnostic_system(world: &mut World) {
+        let fragmentation_threshold = match world.get_resource::<StorageDiagnosticsState>() {
+            Some(state) => state.fragmentation_threshold,
+            None => return,
+        };
+
+        // the empty and resource archetypes always exist, and resources are not stored in tables
+        let archetypes = world
+            .archetypes()
+            .iter()
+            .filter(|archetype| {
+                archetype.id() != ArchetypeId::EMPTY && archetype.id() != ArchetypeId::RESOURCE
+            })
+            .map(|archetype| archetype.len());
+        let mut archetype_count = 0;
+        let mut fragmented_archetype_count = 0;
+        for len in archetypes {
+            archetype_count += 1;
+            if len < fragmentation_threshold {
+                fragmented_archetype_count += 1;
+            }
+        }
+
+        let storages = world.storages();
+        let tables = storages
+            .tables
+            .iter()
+            .enumerate()
+            .map(|(index, table)| TableDiagnostics {
+                id: TableId::new(index),
+                len: table.len(),
+                capacity: table.capacity(),
+                allocated_bytes: table.allocated_bytes(),
+            })
+            .collect::<Vec<_>>();
+        let table_rows = tables.iter().map(|table| table.len).sum::<usize>();
+        let table_bytes = tables
+            .iter()
+            .map(|table| table.allocated_bytes)
+            .sum::<usize>();
+        let sparse_set_count = storages.sparse_sets.len();
+        let sparse_set_bytes = storages
+            .sparse_sets
+            .iter()
+            .map(|(_, sparse_set)| sparse_set.allocated_bytes())
+            .sum::<usize>();
+
+        if let Some(mut diagnostics) = world.get_resource_mut::<Diagnostics>() {
+            diagnostics.add_measurement(Self::ARCHETYPE_COUNT, archetype_count as f64);
+            diagnostics.add_measurement(
+                Self::FRAGMENTED_ARCHETYPE_COUNT,
+                fragmented_archetype_count as f64,
+            );
+            diagnostics.add_measurement(Self::TABLE_COUNT, tables.len() as f64);
+            diagnostics.add_measurement(Self::TABLE_ROW_COUNT, table_rows as f64);
+            diagnostics.add_measurement(Self::TABLE_BYTES, table_bytes as f64);
+            diagnostics.add_measurement(Self::SPARSE_SET_COUNT, sparse_set_count as f64);
+            diagnostics.add_measurement(Self::SPARSE_SET_BYTES, sparse_set_bytes as f64);
+        }
+        world.resource_mut::<StorageDiagnosticsState>().tables = tables;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StorageDiagnosticsPlugin, StorageDiagnosticsState};
+    use crate::{Diagnostics, DiagnosticsPlugin};
+    use bevy_app::App;
+    use bevy_ecs::component::Component;
+
+    // the fields only give the components a size
+    #[allow(dead_code)]
+    #[derive(Component)]
+    struct A(u64);
+
+    #[allow(dead_code)]
+    #[derive(Component)]
+    #[component(storage = "SparseSet")]
+    struct B(u64);
+
+    #[test]
+    fn storage_diagnostics() {
+        let mut app = App::new();
+        app.add_plugin(DiagnosticsPlugin)
+            .add_plugin(StorageDiagnosticsPlugin {
+                fragmentation_threshold: 2,
+            });
+        for i in 0..10 {
+            app.world.spawn().insert(A(i));
+        }
+        app.world.spawn().insert_bundle((A(0), B(0)));
+        app.update();
+
+        let diagnostics = app.world.resource::<Diagnostics>();
+        let value = |id| diagnostics.get(id).unwrap().value().unwrap();
+        assert_eq!(value(StorageDiagnosticsPlugin::ARCHETYPE_COUNT), 2.0);
+        assert_eq!(
+            value(StorageDiagnosticsPlugin::FRAGMENTED_ARCHETYPE_COUNT),
+            1.0
+        );
+        assert_eq!(value(StorageDiagnosticsPlugin::TABLE_ROW_COUNT), 11.0);
+        assert_eq!(value(StorageDiagnosticsPlugin::SPARSE_SET_COUNT), 1.0);
+        assert!(value(StorageDiagnosticsPlugin::TABLE_BYTES) >= 11.0 * 8.0);
+        assert!(value(StorageDiagnosticsPlugin::SPARSE_SET_BYTES) >= 8.0);
+
+        let state = app.world.resource::<StorageDiagnosticsState>();
+        let table = state.tables.iter().find(|table| table.len == 11).unwrap();
+        assert!(table.capacity >= 11);
+    }
+}
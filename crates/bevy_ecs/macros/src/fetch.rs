@@ -215,8 +215,11 @@ pub fn derive_world_query_impl(ast: DeriveInput) -> TokenStream {
     let (fetch_impl_generics, _, _) = fetch_generics.split_for_impl();
 
     // Replace lifetime `'world` with `'fetch`. See `replace_lifetime_for_type` for more details.
+    // Filters don't have a world lifetime, and may have no generics at all.
     let mut fetch_generics = ast.generics.clone();
-    *fetch_generics.params.first_mut().unwrap() = fetch_lifetime_param;
+    if !fetch_struct_attributes.is_filter {
+        *fetch_generics.params.first_mut().unwrap() = fetch_lifetime_param;
+    }
 
     let fetch_ty_generics = if fetch_struct_attributes.is_filter {
         ty_generics.clone()
@@ -371,6 +374,10 @@ pub fn derive_world_query_impl(ast: DeriveInput) -> TokenStream {
             fn matches_table(&self, _table: &#path::storage::Table) -> bool {
                 true #(&& self.#field_idents.matches_table(_table))*
             }
+
+            fn set_relation_target(&self, _relation_id: #path::component::ComponentId, _target: #path::entity::Entity) {
+                #(self.#field_idents.set_relation_target(_relation_id, _target);)*
+            }
         }
     };
 
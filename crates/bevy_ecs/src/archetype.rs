@@ -125,6 +125,7 @@ pub struct Archetype {
     sparse_set_components: Cow<'static, [ComponentId]>,
     pub(crate) unique_components: SparseSet<ComponentId, Column>,
     pub(crate) components: SparseSet<ComponentId, ArchetypeComponentInfo>,
+    disabled: bool,
 }
 
 impl Archetype {
@@ -174,6 +175,7 @@ impl Archetype {
             unique_components: SparseSet::new(),
             entities: Default::default(),
             edges: Default::default(),
+            disabled: false,
         }
     }
 
@@ -290,6 +292,13 @@ impl Archetype {
         self.components.contains(component_id)
     }
 
+    /// Returns `true` if the entities of this archetype have the
+    /// [`Disabled`](crate::component::Disabled) component.
+    #[inline]
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
     #[inline]
     pub fn get_storage_type(&self, component_id: ComponentId) -> Option<StorageType> {
         self.components
@@ -365,6 +374,9 @@ pub struct Archetypes {
     pub(crate) archetypes: Vec<Archetype>,
     pub(crate) archetype_component_count: usize,
     archetype_ids: HashMap<ArchetypeIdentity, ArchetypeId>,
+    /// The id of the [`Disabled`](crate::component::Disabled) component, which is registered
+    /// when the [`World`](crate::world::World) is created.
+    pub(crate) disabled_component: Option<ComponentId>,
 }
 
 impl Default for Archetypes {
@@ -373,6 +385,7 @@ impl Default for Archetypes {
             archetypes: Vec::new(),
             archetype_ids: Default::default(),
             archetype_component_count: 0,
+            disabled_component: None,
         };
         archetypes.get_id_or_insert(TableId::empty(), Vec::new(), Vec::new());
 
@@ -486,6 +499,7 @@ impl Archetypes {
 
         let archetypes = &mut self.archetypes;
         let archetype_component_count = &mut self.archetype_component_count;
+        let disabled_component = self.disabled_component;
         let mut next_archetype_component_id = move || {
             let id = ArchetypeComponentId(*archetype_component_count);
             *archetype_component_count += 1;
@@ -502,14 +516,17 @@ impl Archetypes {
                 let sparse_set_archetype_components = (0..sparse_set_components.len())
                     .map(|_| next_archetype_component_id())
                     .collect();
-                archetypes.push(Archetype::new(
+                let mut archetype = Archetype::new(
                     id,
                     table_id,
                     table_components,
                     sparse_set_components,
                     table_archetype_components,
                     sparse_set_archetype_components,
-                ));
+                );
+                archetype.disabled =
+                    matches!(disabled_component, Some(id) if archetype.contains(id));
+                archetypes.push(archetype);
                 id
             })
     }
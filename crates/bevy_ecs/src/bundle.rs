@@ -128,6 +128,31 @@ macro_rules! tuple_impl {
 
 all_tuples!(tuple_impl, 0, 15, C);
 
+/// Component data that can be written into storage one pointer at a time, in the order of a
+/// [`BundleInfo`]'s components.
+pub(crate) trait DynamicBundle {
+    /// Calls `func` on each value. The values are owned by the callee afterwards.
+    fn get_components(self, func: impl FnMut(*mut u8));
+}
+
+impl<T: Bundle> DynamicBundle for T {
+    #[inline]
+    fn get_components(self, func: impl FnMut(*mut u8)) {
+        Bundle::get_components(self, func);
+    }
+}
+
+/// A single component value without a Rust type, inserted with
+/// [`EntityMut::insert_by_id`](crate::world::EntityMut::insert_by_id).
+pub(crate) struct ComponentPtr(pub(crate) *mut u8);
+
+impl DynamicBundle for ComponentPtr {
+    #[inline]
+    fn get_components(self, mut func: impl FnMut(*mut u8)) {
+        func(self.0);
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BundleId(usize);
 
@@ -261,7 +286,7 @@ impl BundleInfo {
     /// `entity`, `bundle` must match this [`BundleInfo`]'s type
     #[inline]
     #[allow(clippy::too_many_arguments)]
-    unsafe fn write_components<T: Bundle>(
+    unsafe fn write_components<T: DynamicBundle>(
         &self,
         table: &mut Table,
         sparse_sets: &mut SparseSets,
@@ -412,7 +437,7 @@ impl<'a, 'b> BundleInserter<'a, 'b> {
     /// `entity` must currently exist in the source archetype for this inserter. `archetype_index`
     /// must be `entity`'s location in the archetype. `T` must match this [`BundleInfo`]'s type
     #[inline]
-    pub unsafe fn insert<T: Bundle>(
+    pub unsafe fn insert<T: DynamicBundle>(
         &mut self,
         entity: Entity,
         archetype_index: usize,
@@ -576,6 +601,8 @@ impl<'a, 'b> BundleSpawner<'a, 'b> {
 pub struct Bundles {
     bundle_infos: Vec<BundleInfo>,
     bundle_ids: HashMap<TypeId, BundleId>,
+    /// Bundles made of a single component, used to insert components by [`ComponentId`].
+    component_bundle_ids: HashMap<ComponentId, BundleId>,
 }
 
 impl Bundles {
@@ -608,6 +635,36 @@ impl Bundles {
         // SAFE: index either exists, or was initialized
         unsafe { self.bundle_infos.get_unchecked(id.0) }
     }
+
+    /// Returns the [`BundleInfo`] of a bundle containing only the component `component_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `component_id` is not registered in `components`.
+    pub(crate) fn init_component_info<'a>(
+        &'a mut self,
+        components: &Components,
+        component_id: ComponentId,
+    ) -> &'a BundleInfo {
+        let bundle_infos = &mut self.bundle_infos;
+        let id = self
+            .component_bundle_ids
+            .entry(component_id)
+            .or_insert_with(|| {
+                let component_info = components
+                    .get_info(component_id)
+                    .unwrap_or_else(|| panic!("{:?} is not a registered component", component_id));
+                let id = BundleId(bundle_infos.len());
+                bundle_infos.push(BundleInfo {
+                    id,
+                    component_ids: vec![component_id],
+                    storage_types: vec![component_info.storage_type()],
+                });
+                id
+            });
+        // SAFE: index either exists, or was initialized
+        unsafe { self.bundle_infos.get_unchecked(id.0) }
+    }
 }
 
 /// # Safety
@@ -11,7 +11,8 @@ use std::ops::{Deref, DerefMut};
 /// Using types that implement [`DetectChanges`], such as [`ResMut`], provide
 /// a way to query if a value has been mutated in another system.
 /// Normally change detecting is triggered by either [`DerefMut`] or [`AsMut`], however
-/// it can be manually triggered via [`DetectChanges::set_changed`].
+/// it can be manually triggered via [`DetectChanges::set_changed`], or avoided via
+/// [`DetectChanges::bypass_change_detection`].
 ///
 /// ```
 /// use bevy_ecs::prelude::*;
@@ -28,6 +29,9 @@ use std::ops::{Deref, DerefMut};
 /// ```
 ///
 pub trait DetectChanges {
+    /// The type of the value that is tracked.
+    type Inner: ?Sized;
+
     /// Returns true if (and only if) this value been added since the last execution of this
     /// system.
     fn is_added(&self) -> bool;
@@ -42,11 +46,25 @@ pub trait DetectChanges {
     ///
     /// **Note**: This operation is irreversible.
     fn set_changed(&mut self);
+
+    /// Returns the change tick recording the previous time this value was changed.
+    ///
+    /// Note that components and resources are also marked as changed upon insertion.
+    fn last_changed(&self) -> u32;
+
+    /// Returns a mutable reference to the value without flagging it as "changed".
+    ///
+    /// This is useful to mutate a value that other systems shouldn't react to, such as a cache,
+    /// or to only flag the value as changed with [`DetectChanges::set_changed`] when the new value
+    /// actually differs from the old one.
+    fn bypass_change_detection(&mut self) -> &mut Self::Inner;
 }
 
 macro_rules! change_detection_impl {
     ($name:ident < $( $generics:tt ),+ >, $target:ty, $($traits:ident)?) => {
         impl<$($generics),* $(: $traits)?> DetectChanges for $name<$($generics),*> {
+            type Inner = $target;
+
             #[inline]
             fn is_added(&self) -> bool {
                 self.ticks
@@ -67,6 +85,16 @@ macro_rules! change_detection_impl {
                     .component_ticks
                     .set_changed(self.ticks.change_tick);
             }
+
+            #[inline]
+            fn last_changed(&self) -> u32 {
+                self.ticks.component_ticks.changed
+            }
+
+            #[inline]
+            fn bypass_change_detection(&mut self) -> &mut Self::Inner {
+                self.value
+            }
         }
 
         impl<$($generics),* $(: $traits)?> Deref for $name<$($generics),*> {
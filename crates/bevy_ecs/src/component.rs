@@ -1,10 +1,16 @@
 //! Types for declaring and storing [`Component`]s.
 
+#[cfg(feature = "bevy_reflect")]
+use crate::reflect::ReflectComponent;
 use crate::{
+    entity::Entity,
     storage::{SparseSetIndex, Storages},
     system::Resource,
+    world::World,
 };
 pub use bevy_ecs_macros::Component;
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::Reflect;
 use std::{
     alloc::Layout,
     any::{Any, TypeId},
@@ -63,6 +69,46 @@ where
     type Storage = TableStorage;
 }
 
+/// Marker component for entities that are turned off without being despawned, such as pooled
+/// bullets.
+///
+/// Queries skip disabled entities, unless they refer to [`Disabled`] themselves, e.g. with
+/// `With<Disabled>`, or use the [`AllowDisabled`](crate::query::AllowDisabled) filter. Whether
+/// entities are disabled is known from their [`Archetype`](crate::archetype::Archetype), so skipping
+/// them has no per-entity cost.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ecs::{prelude::*, component::Disabled, query::AllowDisabled};
+/// #[derive(Component)]
+/// struct Bullet;
+///
+/// let mut world = World::new();
+/// world.spawn().insert(Bullet);
+/// let pooled = world.spawn().insert_bundle((Bullet, Disabled)).id();
+///
+/// assert_eq!(world.query::<&Bullet>().iter(&world).count(), 1);
+/// assert_eq!(world.query_filtered::<&Bullet, AllowDisabled>().iter(&world).count(), 2);
+///
+/// // enable the pooled bullet again
+/// world.entity_mut(pooled).remove::<Disabled>();
+/// assert_eq!(world.query::<&Bullet>().iter(&world).count(), 2);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, PartialEq)
+)]
+pub struct Disabled;
+
+// `Disabled` is stored in tables, so that disabled and enabled entities never share a table and
+// dense queries can skip whole tables.
+impl Component for Disabled {
+    type Storage = TableStorage;
+}
+
 /// The storage used for a specific component type.
 ///
 /// # Examples
@@ -93,6 +139,7 @@ impl Default for StorageType {
 pub struct ComponentInfo {
     id: ComponentId,
     descriptor: ComponentDescriptor,
+    hooks: ComponentHooks,
 }
 
 impl ComponentInfo {
@@ -131,8 +178,122 @@ impl ComponentInfo {
         self.descriptor.is_send_and_sync
     }
 
+    /// Returns the lifecycle hooks registered for this component.
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
     fn new(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
-        ComponentInfo { id, descriptor }
+        ComponentInfo {
+            id,
+            descriptor,
+            hooks: ComponentHooks::default(),
+        }
+    }
+}
+
+/// The point in a component's lifecycle at which a [`ComponentHook`] or an
+/// [observer](crate::world::World::observe) runs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LifecycleEvent {
+    /// The component was added to an entity that did not have it before.
+    Add,
+    /// The component was inserted into an entity, whether or not it replaced an existing value.
+    Insert,
+    /// The component is about to be removed from an entity, either directly or because the entity
+    /// is being despawned.
+    Remove,
+}
+
+/// A function that is called with the affected entity whenever a [`LifecycleEvent`] occurs for
+/// a component.
+///
+/// Hooks run synchronously with exclusive access to the [`World`]. [`LifecycleEvent::Add`] and
+/// [`LifecycleEvent::Insert`] hooks run after the component has been inserted, while
+/// [`LifecycleEvent::Remove`] hooks run before it is removed, so the component value can always
+/// be read from inside the hook.
+pub type ComponentHook = fn(&mut World, Entity, ComponentId);
+
+/// The lifecycle hooks of a single component type.
+///
+/// Hooks are registered with [`World::register_component_hooks`] and are run by
+/// [`EntityMut::insert_bundle`](crate::world::EntityMut::insert_bundle),
+/// [`EntityMut::remove_bundle`](crate::world::EntityMut::remove_bundle) and
+/// [`EntityMut::despawn`](crate::world::EntityMut::despawn), as well as every method built on top
+/// of them, and [`LifecycleEvent::Remove`] hooks are run by [`World::clear_entities`]. Entities
+/// spawned with [`World::spawn_batch`] do not trigger hooks.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Name(&'static str);
+///
+/// #[derive(Default)]
+/// struct NameCount(usize);
+///
+/// let mut world = World::new();
+/// world.init_resource::<NameCount>();
+/// world
+///     .register_component_hooks::<Name>()
+///     .on_add(|world, _, _| world.get_resource_mut::<NameCount>().unwrap().0 += 1)
+///     .on_remove(|world, _, _| world.get_resource_mut::<NameCount>().unwrap().0 -= 1);
+///
+/// let entity = world.spawn().insert(Name("alice")).id();
+/// assert_eq!(world.get_resource::<NameCount>().unwrap().0, 1);
+/// world.despawn(entity);
+/// assert_eq!(world.get_resource::<NameCount>().unwrap().0, 0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ComponentHooks {
+    on_add: Option<ComponentHook>,
+    on_insert: Option<ComponentHook>,
+    on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// Sets the hook that runs when the component is added to an entity that did not have it.
+    ///
+    /// This replaces any previously registered `on_add` hook.
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_add = Some(hook);
+        self
+    }
+
+    /// Sets the hook that runs every time the component is inserted into an entity, including
+    /// when it replaces an existing value.
+    ///
+    /// This replaces any previously registered `on_insert` hook.
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_insert = Some(hook);
+        self
+    }
+
+    /// Sets the hook that runs right before the component is removed from an entity.
+    ///
+    /// This replaces any previously registered `on_remove` hook.
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        self.on_remove = Some(hook);
+        self
+    }
+
+    /// Returns the hook registered for `event`, if any.
+    #[inline]
+    pub fn get(&self, event: LifecycleEvent) -> Option<ComponentHook> {
+        match event {
+            LifecycleEvent::Add => self.on_add,
+            LifecycleEvent::Insert => self.on_insert,
+            LifecycleEvent::Remove => self.on_remove,
+        }
+    }
+
+    /// Returns `true` if no hooks are registered.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.on_add.is_none() && self.on_insert.is_none() && self.on_remove.is_none()
     }
 }
 
@@ -209,6 +370,34 @@ impl ComponentDescriptor {
         }
     }
 
+    /// Creates a new `ComponentDescriptor` for a component that has no Rust type, such as a
+    /// component whose schema is loaded from a data file.
+    ///
+    /// `drop` is called with a pointer to each value of the component that is dropped by the
+    /// [`World`]. Pass `None` if the values do not need to be dropped.
+    ///
+    /// # Safety
+    /// - `layout` must be the layout of the values inserted for this component, and `drop` must be
+    ///   safe to call on any of them.
+    /// - The values must be safe to send and share across threads.
+    pub unsafe fn new_with_layout(
+        name: impl Into<String>,
+        storage_type: StorageType,
+        layout: Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            storage_type,
+            is_send_and_sync: true,
+            type_id: None,
+            layout,
+            drop: drop.unwrap_or(Self::drop_nothing),
+        }
+    }
+
+    unsafe fn drop_nothing(_x: *mut u8) {}
+
     fn new_non_send<T: Any>(storage_type: StorageType) -> Self {
         Self {
             name: std::any::type_name::<T>().to_string(),
@@ -261,6 +450,25 @@ impl Components {
         ComponentId(*index)
     }
 
+    /// Registers a new component described by `descriptor` and returns its [`ComponentId`].
+    ///
+    /// This always registers a new component, even if `descriptor` has the same name or type as
+    /// an existing one. Components registered this way are not returned by
+    /// [`Components::get_id`].
+    pub fn init_component_with_descriptor(
+        &mut self,
+        storages: &mut Storages,
+        descriptor: ComponentDescriptor,
+    ) -> ComponentId {
+        let id = ComponentId(self.components.len());
+        let info = ComponentInfo::new(id, descriptor);
+        if info.storage_type() == StorageType::SparseSet {
+            storages.sparse_sets.get_or_insert(&info);
+        }
+        self.components.push(info);
+        id
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.components.len()
@@ -285,6 +493,12 @@ impl Components {
         self.components.get_unchecked(id.0)
     }
 
+    /// Returns the lifecycle hooks of the component with the given `id`, so they can be changed.
+    #[inline]
+    pub fn get_hooks_mut(&mut self, id: ComponentId) -> Option<&mut ComponentHooks> {
+        self.components.get_mut(id.0).map(|info| &mut info.hooks)
+    }
+
     #[inline]
     pub fn get_id(&self, type_id: TypeId) -> Option<ComponentId> {
         self.indices.get(&type_id).map(|index| ComponentId(*index))
@@ -394,7 +608,7 @@ impl ComponentTicks {
     }
 }
 
-fn check_tick(last_change_tick: &mut u32, change_tick: u32) {
+pub(crate) fn check_tick(last_change_tick: &mut u32, change_tick: u32) {
     let tick_delta = change_tick.wrapping_sub(*last_change_tick);
     const MAX_DELTA: u32 = (u32::MAX / 4) * 3;
     // Clamp to max delta
@@ -1,12 +1,22 @@
 //! Event handling types.
 
 use crate::system::{Local, Res, ResMut, SystemParam};
-use crate::{self as bevy_ecs, system::Resource};
+use crate::{
+    self as bevy_ecs,
+    system::Resource,
+    world::{FromWorld, World},
+};
+use bevy_tasks::TaskPool;
 use bevy_utils::tracing::trace;
 use std::{
+    collections::{vec_deque, VecDeque},
     fmt::{self},
     hash::Hash,
     marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
 };
 
 /// An `EventId` uniquely identifies an event.
@@ -49,10 +59,31 @@ struct EventInstance<T> {
     pub event: T,
 }
 
-#[derive(Debug)]
-enum State {
-    A,
-    B,
+/// Determines how long an [`Events`] collection keeps the events sent to it.
+///
+/// Events are only ever dropped by [`Events::update`], [`Events::clear`] and [`Events::drain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRetention {
+    /// Events are dropped by the `n`th call to [`Events::update`] after they were sent. `n` must
+    /// be at least 1.
+    ///
+    /// The default is `Frames(2)`: events persist across a single frame boundary.
+    Frames(usize),
+    /// Events are dropped by [`Events::update`] once every registered reader has read them.
+    ///
+    /// [`EventReader`]s are registered when their system is initialized, and
+    /// [`ManualEventReader`]s are registered by [`Events::register_reader`]. A reader stops
+    /// holding back events once it is dropped, for example when its system is removed. When there
+    /// are no registered readers, events are dropped by the next update.
+    UntilConsumed,
+    /// Events are only dropped by [`Events::clear`] and [`Events::drain`].
+    Manual,
+}
+
+impl Default for EventRetention {
+    fn default() -> Self {
+        EventRetention::Frames(2)
+    }
 }
 
 /// An event collection that represents the events that occurred within the last two
@@ -78,6 +109,10 @@ enum State {
 /// If events are not handled by the end of the frame after they are updated, they will be
 /// dropped silently.
 ///
+/// Readers that do not run every frame, such as systems with a fixed timestep, can miss events
+/// this way. An [`EventRetention`] other than the default can be used to keep events for more
+/// frames, or until every registered reader has read them.
+///
 /// # Example
 /// ```
 /// use bevy_ecs::event::Events;
@@ -107,15 +142,16 @@ enum State {
 ///
 /// # Details
 ///
-/// [`Events`] is implemented using a variation of a double buffer strategy.
-/// Each call to [`update`](Events::update) swaps buffers and clears out the oldest one.
-/// - [`EventReader`]s will read events from both buffers.
+/// [`Events`] stores events in a single queue, oldest first.
+/// Each call to [`update`](Events::update) marks the end of a frame and drops the events that
+/// are past their [`EventRetention`]. With the default retention:
 /// - [`EventReader`]s that read at least once per update will never drop events.
 /// - [`EventReader`]s that read once within two updates might still receive some events
 /// - [`EventReader`]s that read after two updates are guaranteed to drop all events that occurred
 /// before those updates.
 ///
-/// The buffers in [`Events`] will grow indefinitely if [`update`](Events::update) is never called.
+/// The events in [`Events`] will accumulate indefinitely if [`update`](Events::update) is never
+/// called.
 ///
 /// An alternative call pattern would be to call [`update`](Events::update)
 /// manually across frames to control when events are cleared.
@@ -128,23 +164,25 @@ enum State {
 ///
 #[derive(Debug)]
 pub struct Events<T> {
-    events_a: Vec<EventInstance<T>>,
-    events_b: Vec<EventInstance<T>>,
-    a_start_event_count: usize,
-    b_start_event_count: usize,
+    /// The stored events, oldest first. Their ids are contiguous and end at `event_count`.
+    events: VecDeque<EventInstance<T>>,
+    /// The `event_count` at each of the latest updates, oldest first.
+    update_event_counts: VecDeque<usize>,
     event_count: usize,
-    state: State,
+    retention: EventRetention,
+    /// The read positions of the registered readers. It is behind a lock, so that an
+    /// [`EventReader`] can register itself while reading.
+    readers: Mutex<Vec<Weak<AtomicUsize>>>,
 }
 
 impl<T> Default for Events<T> {
     fn default() -> Self {
         Events {
-            a_start_event_count: 0,
-            b_start_event_count: 0,
+            events: VecDeque::new(),
+            update_event_counts: VecDeque::new(),
             event_count: 0,
-            events_a: Vec::new(),
-            events_b: Vec::new(),
-            state: State::A,
+            retention: EventRetention::default(),
+            readers: Mutex::new(Vec::new()),
         }
     }
 }
@@ -158,12 +196,40 @@ fn map_instance_event<T>(event_instance: &EventInstance<T>) -> &T {
 }
 
 /// Reads events of type `T` in order and tracks which events have already been read.
+///
+/// The reader is registered with the [`Events<T>`] resource when its system is initialized, so
+/// that it holds back events with an [`EventRetention::UntilConsumed`] retention. If the resource
+/// doesn't exist yet at that point, the reader registers itself the first time it reads events.
 #[derive(SystemParam)]
 pub struct EventReader<'w, 's, T: Resource> {
-    last_event_count: Local<'s, (usize, PhantomData<T>)>,
+    reader: Local<'s, LocalEventReader<T>>,
     events: Res<'w, Events<T>>,
 }
 
+/// The [`Local`] state of an [`EventReader`].
+#[doc(hidden)]
+pub struct LocalEventReader<T>(ManualEventReader<T>);
+
+impl<T: Resource> FromWorld for LocalEventReader<T> {
+    fn from_world(world: &mut World) -> Self {
+        LocalEventReader(match world.get_resource_mut::<Events<T>>() {
+            Some(mut events) => events.register_reader(),
+            None => ManualEventReader::default(),
+        })
+    }
+}
+
+impl<T: Resource> LocalEventReader<T> {
+    /// Returns the reader, after registering it if its system was initialized before `events`
+    /// was inserted.
+    fn registered(&mut self, events: &Events<T>) -> &mut ManualEventReader<T> {
+        if self.0.cursor.is_none() {
+            self.0.cursor = Some(events.add_reader(self.0.last_event_count));
+        }
+        &mut self.0
+    }
+}
+
 /// Sends events of type `T`.
 #[derive(SystemParam)]
 pub struct EventWriter<'w, 's, T: Resource> {
@@ -194,6 +260,8 @@ impl<'w, 's, T: Resource> EventWriter<'w, 's, T> {
 
 pub struct ManualEventReader<T> {
     last_event_count: usize,
+    /// The read position shared with [`Events`], if this reader is registered.
+    cursor: Option<Arc<AtomicUsize>>,
     _marker: PhantomData<T>,
 }
 
@@ -201,6 +269,7 @@ impl<T> Default for ManualEventReader<T> {
     fn default() -> Self {
         ManualEventReader {
             last_event_count: 0,
+            cursor: None,
             _marker: Default::default(),
         }
     }
@@ -210,7 +279,7 @@ impl<T> Default for ManualEventReader<T> {
 impl<T: Resource> ManualEventReader<T> {
     /// See [`EventReader::iter`]
     pub fn iter<'a>(&'a mut self, events: &'a Events<T>) -> impl DoubleEndedIterator<Item = &'a T> {
-        internal_event_reader(&mut self.last_event_count, events).map(|(e, _)| e)
+        self.iter_with_id(events).map(|(e, _)| e)
     }
 
     /// See [`EventReader::iter_with_id`]
@@ -218,7 +287,20 @@ impl<T: Resource> ManualEventReader<T> {
         &'a mut self,
         events: &'a Events<T>,
     ) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> {
-        internal_event_reader(&mut self.last_event_count, events)
+        internal_event_reader(&mut self.last_event_count, self.cursor.as_deref(), events)
+    }
+
+    /// See [`EventReader::par_iter`]
+    pub fn par_iter<'a>(&mut self, events: &'a Events<T>) -> EventParIter<'a, T> {
+        let slices = events.unread_slices(self.last_event_count);
+        self.last_event_count = events.event_count;
+        if let Some(cursor) = &self.cursor {
+            cursor.store(self.last_event_count, Ordering::Relaxed);
+        }
+        EventParIter {
+            slices,
+            batch_size: None,
+        }
     }
 
     /// See [`EventReader::len`]
@@ -230,37 +312,41 @@ impl<T: Resource> ManualEventReader<T> {
     pub fn is_empty(&self, events: &Events<T>) -> bool {
         self.len(events) == 0
     }
+
+    /// See [`EventReader::missed`]
+    pub fn missed(&self, events: &Events<T>) -> usize {
+        events
+            .first_event_id()
+            .saturating_sub(self.last_event_count)
+    }
+
+    /// Returns `true` if this reader holds back events with an
+    /// [`EventRetention::UntilConsumed`] retention.
+    pub fn is_registered(&self) -> bool {
+        self.cursor.is_some()
+    }
 }
 
 /// Like [`iter_with_id`](EventReader::iter_with_id) except not emitting any traces for read
 /// messages.
 fn internal_event_reader<'a, T>(
     last_event_count: &'a mut usize,
+    cursor: Option<&'a AtomicUsize>,
     events: &'a Events<T>,
 ) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> {
-    // if the reader has seen some of the events in a buffer, find the proper index offset.
-    // otherwise read all events in the buffer
-    let a_index = if *last_event_count > events.a_start_event_count {
-        *last_event_count - events.a_start_event_count
-    } else {
-        0
-    };
-    let b_index = if *last_event_count > events.b_start_event_count {
-        *last_event_count - events.b_start_event_count
-    } else {
-        0
-    };
-    let a = events.events_a.get(a_index..).unwrap_or_default();
-    let b = events.events_b.get(b_index..).unwrap_or_default();
-    let unread_count = a.len() + b.len();
-    *last_event_count = events.event_count - unread_count;
-    let iterator = match events.state {
-        State::A => b.iter().chain(a.iter()),
-        State::B => a.iter().chain(b.iter()),
+    let unread = events.unread(*last_event_count);
+    // skip the events that were dropped before this reader could read them
+    *last_event_count = events.event_count - unread.len();
+    let mut update_count = move |count: usize| {
+        *last_event_count = count.max(*last_event_count);
+        if let Some(cursor) = cursor {
+            cursor.store(*last_event_count, Ordering::Relaxed);
+        }
     };
-    iterator
+    update_count(0);
+    unread
         .map(map_instance_event_with_id)
-        .inspect(move |(_, id)| *last_event_count = (id.id + 1).max(*last_event_count))
+        .inspect(move |(_, id)| update_count(id.id + 1))
 }
 
 impl<'w, 's, T: Resource> EventReader<'w, 's, T> {
@@ -273,24 +359,160 @@ impl<'w, 's, T: Resource> EventReader<'w, 's, T> {
 
     /// Like [`iter`](Self::iter), except also returning the [`EventId`] of the events.
     pub fn iter_with_id(&mut self) -> impl DoubleEndedIterator<Item = (&T, EventId<T>)> {
-        internal_event_reader(&mut self.last_event_count.0, &self.events).map(|(event, id)| {
-            trace!("EventReader::iter() -> {}", id);
-            (event, id)
-        })
+        let events = &*self.events;
+        self.reader
+            .registered(events)
+            .iter_with_id(events)
+            .map(|(event, id)| {
+                trace!("EventReader::iter() -> {}", id);
+                (event, id)
+            })
+    }
+
+    /// Returns an [`EventParIter`] that processes the events this [`EventReader`] has not seen
+    /// yet in parallel. Like [`iter`](Self::iter), this marks all those events as read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    /// use bevy_tasks::ComputeTaskPool;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// struct Damage(usize);
+    ///
+    /// fn total_damage(
+    ///     mut events: EventReader<Damage>,
+    ///     pool: Res<ComputeTaskPool>,
+    ///     mut total: ResMut<usize>,
+    /// ) {
+    ///     let sum = AtomicUsize::new(0);
+    ///     events.par_iter().for_each(&pool, |damage| {
+    ///         sum.fetch_add(damage.0, Ordering::Relaxed);
+    ///     });
+    ///     *total += sum.into_inner();
+    /// }
+    /// # bevy_ecs::system::assert_is_system(total_damage);
+    /// ```
+    pub fn par_iter(&mut self) -> EventParIter<'_, T> {
+        let events = &*self.events;
+        self.reader.registered(events).par_iter(events)
     }
 
     /// Determines the number of events available to be read from this [`EventReader`] without consuming any.
     pub fn len(&self) -> usize {
-        self.events.event_reader_len(self.last_event_count.0)
+        self.events.event_reader_len(self.reader.0.last_event_count)
     }
 
     /// Determines if are any events available to be read without consuming any.
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the number of events that were dropped before this [`EventReader`] read them,
+    /// since it last read events.
+    ///
+    /// A non-zero value means events were lost, usually because the reader's system does not run
+    /// every frame. See [`EventRetention`] for ways to keep events longer.
+    pub fn missed(&self) -> usize {
+        self.reader.0.missed(&self.events)
+    }
+}
+
+/// Processes events in parallel on a [`TaskPool`].
+///
+/// Created by [`EventReader::par_iter`] and [`ManualEventReader::par_iter`].
+pub struct EventParIter<'a, T> {
+    slices: [&'a [EventInstance<T>]; 2],
+    batch_size: Option<usize>,
+}
+
+impl<'a, T: Resource> EventParIter<'a, T> {
+    /// Sets the number of events processed by each task.
+    ///
+    /// By default, the events are split evenly between the threads of the [`TaskPool`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is 0.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "The batch size must be at least 1.");
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Returns the number of events that will be processed.
+    pub fn len(&self) -> usize {
+        self.slices[0].len() + self.slices[1].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Runs `f` on each event in parallel using the given [`TaskPool`].
+    pub fn for_each<FN: Fn(&'a T) + Send + Sync + Clone>(self, task_pool: &TaskPool, f: FN) {
+        self.for_each_with_id(task_pool, move |event, _| f(event));
+    }
+
+    /// Like [`for_each`](Self::for_each), except also passing the [`EventId`] of the events.
+    pub fn for_each_with_id<FN: Fn(&'a T, EventId<T>) + Send + Sync + Clone>(
+        self,
+        task_pool: &TaskPool,
+        f: FN,
+    ) {
+        let batch_size = self
+            .batch_size
+            .unwrap_or_else(|| self.len() / task_pool.thread_num().max(1))
+            .max(1);
+        task_pool.scope(|scope| {
+            for slice in self.slices {
+                for batch in slice.chunks(batch_size) {
+                    let f = f.clone();
+                    scope.spawn(async move {
+                        for instance in batch {
+                            f(&instance.event, instance.event_id);
+                        }
+                    });
+                }
+            }
+        });
+    }
 }
 
 impl<T: Resource> Events<T> {
+    /// Creates an empty event collection with the given [`EventRetention`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `retention` is [`EventRetention::Frames(0)`](EventRetention::Frames).
+    pub fn with_retention(retention: EventRetention) -> Self {
+        let mut events = Self::default();
+        events.set_retention(retention);
+        events
+    }
+
+    /// Returns the [`EventRetention`] of this collection.
+    #[inline]
+    pub fn retention(&self) -> EventRetention {
+        self.retention
+    }
+
+    /// Sets the [`EventRetention`] of this collection. It is applied by the next
+    /// [`update`](Self::update).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `retention` is [`EventRetention::Frames(0)`](EventRetention::Frames).
+    pub fn set_retention(&mut self, retention: EventRetention) {
+        assert_ne!(
+            retention,
+            EventRetention::Frames(0),
+            "Events must be kept for at least one frame."
+        );
+        self.retention = retention;
+    }
+
     /// "Sends" an `event` by writing it to the current event buffer. [`EventReader`]s can then read
     /// the event.
     pub fn send(&mut self, event: T) {
@@ -302,10 +524,7 @@ impl<T: Resource> Events<T> {
 
         let event_instance = EventInstance { event_id, event };
 
-        match self.state {
-            State::A => self.events_a.push(event_instance),
-            State::B => self.events_b.push(event_instance),
-        }
+        self.events.push_back(event_instance);
 
         self.event_count += 1;
     }
@@ -320,10 +539,7 @@ impl<T: Resource> Events<T> {
 
     /// Gets a new [`ManualEventReader`]. This will include all events already in the event buffers.
     pub fn get_reader(&self) -> ManualEventReader<T> {
-        ManualEventReader {
-            last_event_count: 0,
-            _marker: PhantomData,
-        }
+        ManualEventReader::default()
     }
 
     /// Gets a new [`ManualEventReader`]. This will ignore all events already in the event buffers.
@@ -331,25 +547,82 @@ impl<T: Resource> Events<T> {
     pub fn get_reader_current(&self) -> ManualEventReader<T> {
         ManualEventReader {
             last_event_count: self.event_count,
+            cursor: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets a new [`ManualEventReader`] that is registered with this collection. Like
+    /// [`get_reader`](Self::get_reader), it will include all events already in the event buffers.
+    ///
+    /// With an [`EventRetention::UntilConsumed`] retention, events are kept until every
+    /// registered reader has read them, or has been dropped.
+    pub fn register_reader(&mut self) -> ManualEventReader<T> {
+        ManualEventReader {
+            last_event_count: 0,
+            cursor: Some(self.add_reader(0)),
             _marker: PhantomData,
         }
     }
 
-    /// Swaps the event buffers and clears the oldest event buffer. In general, this should be
-    /// called once per frame/update.
+    /// Registers the read position of a reader that has read the events before `event_count`.
+    fn add_reader(&self, event_count: usize) -> Arc<AtomicUsize> {
+        let cursor = Arc::new(AtomicUsize::new(event_count));
+        let mut readers = self.readers.lock().unwrap();
+        readers.retain(|reader| reader.strong_count() > 0);
+        readers.push(Arc::downgrade(&cursor));
+        cursor
+    }
+
+    /// Returns the number of events each registered reader has not read yet.
+    ///
+    /// A growing lag means that a reader does not keep up with the events sent.
+    pub fn reader_lags(&self) -> impl Iterator<Item = usize> + '_ {
+        self.readers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|cursor| {
+                self.event_count
+                    .saturating_sub(cursor.load(Ordering::Relaxed))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Marks the end of a frame, and drops the events that are past the [`EventRetention`] of
+    /// this collection. In general, this should be called once per frame/update.
     pub fn update(&mut self) {
-        match self.state {
-            State::A => {
-                self.events_b.clear();
-                self.state = State::B;
-                self.b_start_event_count = self.event_count;
+        self.update_event_counts.push_back(self.event_count);
+        let frames = match self.retention {
+            EventRetention::Frames(frames) => frames,
+            EventRetention::UntilConsumed | EventRetention::Manual => 1,
+        };
+        while self.update_event_counts.len() > frames {
+            self.update_event_counts.pop_front();
+        }
+
+        let drop_before = match self.retention {
+            EventRetention::Frames(frames) if self.update_event_counts.len() == frames => {
+                self.update_event_counts[0]
             }
-            State::B => {
-                self.events_a.clear();
-                self.state = State::A;
-                self.a_start_event_count = self.event_count;
+            EventRetention::Frames(_) | EventRetention::Manual => return,
+            EventRetention::UntilConsumed => {
+                let readers = self.readers.get_mut().unwrap();
+                readers.retain(|reader| reader.strong_count() > 0);
+                readers
+                    .iter()
+                    .filter_map(Weak::upgrade)
+                    .map(|cursor| cursor.load(Ordering::Relaxed))
+                    .min()
+                    .unwrap_or(self.event_count)
             }
-        }
+        };
+        let dropped = drop_before
+            .saturating_sub(self.first_event_id())
+            .min(self.events.len());
+        self.events.drain(..dropped);
     }
 
     /// A system that calls [`Events::update`] once per frame.
@@ -357,43 +630,27 @@ impl<T: Resource> Events<T> {
         events.update();
     }
 
-    #[inline]
-    fn reset_start_event_count(&mut self) {
-        self.a_start_event_count = self.event_count;
-        self.b_start_event_count = self.event_count;
-    }
-
     /// Removes all events.
     #[inline]
     pub fn clear(&mut self) {
-        self.reset_start_event_count();
-        self.events_a.clear();
-        self.events_b.clear();
+        self.events.clear();
     }
 
     /// Returns true if there are no events in this collection.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.events_a.is_empty() && self.events_b.is_empty()
+        self.events.is_empty()
+    }
+
+    /// Returns the number of events in this collection.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.events.len()
     }
 
     /// Creates a draining iterator that removes all events.
     pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
-        self.reset_start_event_count();
-
-        let map = |i: EventInstance<T>| i.event;
-        match self.state {
-            State::A => self
-                .events_b
-                .drain(..)
-                .map(map)
-                .chain(self.events_a.drain(..).map(map)),
-            State::B => self
-                .events_a
-                .drain(..)
-                .map(map)
-                .chain(self.events_b.drain(..).map(map)),
-        }
+        self.events.drain(..).map(|i| i.event)
     }
 
     /// Iterates over events that happened since the last "update" call.
@@ -403,33 +660,44 @@ impl<T: Resource> Events<T> {
     /// If events happen outside that window, they will not be handled. For example, any events that
     /// happen after this call and before the next `update()` call will be dropped.
     pub fn iter_current_update_events(&self) -> impl DoubleEndedIterator<Item = &T> {
-        match self.state {
-            State::A => self.events_a.iter().map(map_instance_event),
-            State::B => self.events_b.iter().map(map_instance_event),
-        }
+        let last_update_count = self.update_event_counts.back().copied().unwrap_or(0);
+        self.unread(last_update_count).map(map_instance_event)
     }
+}
 
-    /// Determines how many events are in the reader after the given `last_event_count` parameter
-    fn event_reader_len(&self, last_event_count: usize) -> usize {
-        let a_count = if last_event_count <= self.a_start_event_count {
-            self.events_a.len()
-        } else {
-            self.events_a
-                .len()
-                .checked_sub(last_event_count - self.a_start_event_count)
-                .unwrap_or_default()
-        };
+impl<T> Events<T> {
+    /// Returns the id of the oldest event in this collection, or the id of the next event if it
+    /// is empty.
+    #[inline]
+    fn first_event_id(&self) -> usize {
+        self.event_count - self.events.len()
+    }
+
+    /// Returns the index of the first event a reader at `last_event_count` has not read.
+    #[inline]
+    fn unread_index(&self, last_event_count: usize) -> usize {
+        last_event_count
+            .saturating_sub(self.first_event_id())
+            .min(self.events.len())
+    }
 
-        let b_count = if last_event_count <= self.b_start_event_count {
-            self.events_b.len()
+    fn unread(&self, last_event_count: usize) -> vec_deque::Iter<'_, EventInstance<T>> {
+        self.events.range(self.unread_index(last_event_count)..)
+    }
+
+    fn unread_slices(&self, last_event_count: usize) -> [&[EventInstance<T>]; 2] {
+        let index = self.unread_index(last_event_count);
+        let (a, b) = self.events.as_slices();
+        if index < a.len() {
+            [&a[index..], b]
         } else {
-            self.events_b
-                .len()
-                .checked_sub(last_event_count - self.b_start_event_count)
-                .unwrap_or_default()
-        };
+            [&[], &b[index - a.len()..]]
+        }
+    }
 
-        a_count + b_count
+    /// Determines how many events are in the reader after the given `last_event_count` parameter
+    fn event_reader_len(&self, last_event_count: usize) -> usize {
+        self.events.len() - self.unread_index(last_event_count)
     }
 }
 
@@ -448,10 +716,7 @@ impl<T> std::iter::Extend<T> for Events<T> {
             EventInstance { event_id, event }
         });
 
-        match self.state {
-            State::A => self.events_a.extend(events),
-            State::B => self.events_b.extend(events),
-        }
+        self.events.extend(events);
 
         trace!(
             "Events::extend() -> ids: ({}..{})",
@@ -689,4 +954,167 @@ mod tests {
             vec![EmptyTestEvent::default()]
         );
     }
+
+    #[test]
+    fn test_events_retention_frames() {
+        let mut events = Events::<E>::with_retention(EventRetention::Frames(3));
+        let mut reader = events.get_reader();
+        events.send(E(0));
+        events.update();
+        events.send(E(1));
+        events.update();
+        assert_eq!(events.len(), 2);
+        events.update();
+        assert_eq!(reader.missed(&events), 1);
+        assert!(reader.iter(&events).eq([E(1)].iter()));
+        assert_eq!(reader.missed(&events), 0);
+
+        events.set_retention(EventRetention::Frames(1));
+        events.send(E(2));
+        assert!(events.iter_current_update_events().eq([E(2)].iter()));
+        events.update();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_events_retention_until_consumed() {
+        let mut events = Events::<E>::with_retention(EventRetention::UntilConsumed);
+        let mut fast = events.register_reader();
+        let mut slow = events.register_reader();
+        assert!(slow.is_registered());
+
+        for i in 0..3 {
+            events.send(E(i));
+            assert_eq!(fast.iter(&events).count(), 1);
+            events.update();
+        }
+        assert_eq!(events.len(), 3);
+        assert_eq!(events.reader_lags().collect::<Vec<_>>(), vec![0, 3]);
+
+        // a partially read iterator only releases the events that were read
+        assert_eq!(slow.iter(&events).next(), Some(&E(0)));
+        events.update();
+        assert_eq!(events.len(), 2);
+        assert!(slow.iter(&events).eq([E(1), E(2)].iter()));
+        events.update();
+        assert!(events.is_empty());
+
+        // dropped readers no longer hold back events
+        events.send(E(3));
+        assert_eq!(fast.iter(&events).count(), 1);
+        drop(slow);
+        events.update();
+        assert!(events.is_empty());
+        assert_eq!(events.reader_lags().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_events_retention_manual() {
+        let mut events = Events::<E>::with_retention(EventRetention::Manual);
+        let mut reader = events.get_reader();
+        events.send(E(0));
+        events.update();
+        events.update();
+        events.send(E(1));
+        events.update();
+        assert!(reader.iter(&events).eq([E(0), E(1)].iter()));
+        events.clear();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_events_retention_zero_frames() {
+        Events::<E>::with_retention(EventRetention::Frames(0));
+    }
+
+    #[test]
+    fn test_event_reader_systems_are_registered() {
+        use crate::schedule::{ParallelSystemDescriptorCoercion, ShouldRun, Stage, SystemStage};
+
+        #[derive(Default)]
+        struct Read(Vec<usize>);
+
+        fn read(mut reader: EventReader<E>, mut read: ResMut<Read>) {
+            read.0.extend(reader.iter().map(|e| e.0));
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Events::<E>::with_retention(EventRetention::UntilConsumed));
+        world.init_resource::<Read>();
+        let mut frame = 0;
+        let mut stage =
+            SystemStage::single_threaded().with_system(read.with_run_criteria(move || {
+                frame += 1;
+                if frame % 3 == 0 {
+                    ShouldRun::Yes
+                } else {
+                    ShouldRun::No
+                }
+            }));
+        for i in 0..7 {
+            world.get_resource_mut::<Events<E>>().unwrap().send(E(i));
+            stage.run(&mut world);
+            world.get_resource_mut::<Events<E>>().unwrap().update();
+        }
+        assert_eq!(
+            world.get_resource::<Read>().unwrap().0,
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(world.get_resource::<Events<E>>().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_event_reader_registered_on_first_read() {
+        use crate::system::{IntoSystem, System};
+
+        #[derive(Default)]
+        struct Read(Vec<usize>);
+
+        fn read(mut reader: EventReader<E>, mut read: ResMut<Read>) {
+            read.0.extend(reader.iter().map(|e| e.0));
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Read>();
+        let mut system = IntoSystem::into_system(read);
+        // the system is initialized before the events exist
+        system.initialize(&mut world);
+
+        world.insert_resource(Events::<E>::with_retention(EventRetention::UntilConsumed));
+        system.run((), &mut world);
+        let mut events = world.get_resource_mut::<Events<E>>().unwrap();
+        events.send(E(0));
+        events.update();
+        events.update();
+        system.run((), &mut world);
+        assert_eq!(world.get_resource::<Read>().unwrap().0, vec![0]);
+    }
+
+    #[test]
+    fn test_events_par_iter() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let pool = TaskPool::new();
+        let mut events = Events::<E>::with_retention(EventRetention::Frames(3));
+        let mut reader = events.register_reader();
+        // wrap the underlying queue around, so that the events are split in two slices
+        events.extend((0..10).map(E));
+        events.update();
+        assert_eq!(reader.iter(&events).count(), 10);
+        events.update();
+        events.update();
+        events.extend((10..100).map(E));
+
+        let sum = AtomicUsize::new(0);
+        let par_iter = reader.par_iter(&events).batch_size(7);
+        assert_eq!(par_iter.len(), 90);
+        par_iter.for_each(&pool, |e| {
+            sum.fetch_add(e.0, Ordering::Relaxed);
+        });
+        assert_eq!(sum.into_inner(), (10..100).sum());
+        assert!(reader.is_empty(&events));
+        assert_eq!(events.reader_lags().collect::<Vec<_>>(), vec![0]);
+        assert!(reader.par_iter(&events).is_empty());
+    }
 }
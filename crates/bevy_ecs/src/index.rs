@@ -0,0 +1,409 @@
+//! Types for looking up entities by the value of one of their components.
+//!
+//! An index maps each value of an indexed component to the entities that have it. Indexes are
+//! opt-in: a component is indexed once [`World::init_index`] has been called for it, or once a
+//! system with an [`Index`] parameter for it has been initialized.
+
+use crate::{
+    archetype::Archetype,
+    change_detection::ResMut,
+    component::{check_tick, Component, LifecycleEvent},
+    entity::Entity,
+    query::{AllowDisabled, Changed, EntityFetch, QueryState, ReadFetch},
+    system::{In, ResMutState, SystemMeta, SystemParam, SystemParamFetch, SystemParamState},
+    world::World,
+};
+use bevy_utils::HashMap;
+use std::{hash::Hash, marker::PhantomData, ops::Deref};
+
+/// A resource that maps each value of the component `T` to the entities that have it.
+///
+/// The index is updated right away when `T` is removed from an entity, and lazily, through
+/// change detection, when `T` is inserted or mutated: the changes are applied every time an
+/// [`Index<T>`] system parameter is fetched.
+///
+/// [`Disabled`](crate::component::Disabled) entities are indexed as well, so that they can be
+/// found again once they are enabled.
+pub struct ComponentIndex<T> {
+    entities: HashMap<T, Vec<Entity>>,
+    values: HashMap<Entity, T>,
+    last_update_tick: u32,
+}
+
+impl<T: Component + Hash + Eq + Clone> ComponentIndex<T> {
+    /// Returns an entity that has the component value `value`, if there is any.
+    ///
+    /// If several entities have the same value, the one that got it first is returned.
+    #[inline]
+    pub fn get(&self, value: &T) -> Option<Entity> {
+        self.get_all(value).first().copied()
+    }
+
+    /// Returns all entities that have the component value `value`, in the order they got it.
+    #[inline]
+    pub fn get_all(&self, value: &T) -> &[Entity] {
+        self.entities.get(value).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns `true` if any entity has the component value `value`.
+    #[inline]
+    pub fn contains(&self, value: &T) -> bool {
+        self.entities.contains_key(value)
+    }
+
+    /// Returns the number of distinct component values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Returns an iterator over every distinct component value and the entities that have it.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &[Entity])> {
+        self.entities
+            .iter()
+            .map(|(value, entities)| (value, entities.as_slice()))
+    }
+
+    fn insert(&mut self, entity: Entity, value: &T) {
+        if let Some(old_value) = self.values.get(&entity) {
+            if old_value == value {
+                return;
+            }
+            self.remove(entity);
+        }
+        self.entities.entry(value.clone()).or_default().push(entity);
+        self.values.insert(entity, value.clone());
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(value) = self.values.remove(&entity) {
+            if let Some(entities) = self.entities.get_mut(&value) {
+                entities.retain(|e| *e != entity);
+                if entities.is_empty() {
+                    self.entities.remove(&value);
+                }
+            }
+        }
+    }
+}
+
+/// The [`ComponentIndex`] resources of a [`World`], so that their stored tick can be checked by
+/// [`World::check_change_ticks`].
+#[derive(Default)]
+pub(crate) struct ComponentIndices {
+    check_change_ticks: Vec<fn(&mut World, u32)>,
+}
+
+fn check_index_change_tick<T: Component + Hash + Eq + Clone>(world: &mut World, change_tick: u32) {
+    if let Some(mut index) = world.get_resource_mut::<ComponentIndex<T>>() {
+        check_tick(&mut index.last_update_tick, change_tick);
+    }
+}
+
+fn remove_from_index<T: Component + Hash + Eq + Clone>(
+    In(entity): In<Entity>,
+    mut index: ResMut<ComponentIndex<T>>,
+) {
+    index.remove(entity);
+}
+
+impl World {
+    /// Starts indexing the component `T` by value, so that entities can be looked up with the
+    /// [`ComponentIndex<T>`] resource or the [`Index<T>`] system parameter. Does nothing if `T`
+    /// is already indexed.
+    ///
+    /// ```
+    /// use bevy_ecs::{index::ComponentIndex, prelude::*};
+    ///
+    /// #[derive(Component, Clone, PartialEq, Eq, Hash)]
+    /// struct NetworkId(u64);
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn().insert(NetworkId(7)).id();
+    /// world.init_index::<NetworkId>();
+    ///
+    /// let index = world.get_resource::<ComponentIndex<NetworkId>>().unwrap();
+    /// assert_eq!(index.get(&NetworkId(7)), Some(entity));
+    /// ```
+    pub fn init_index<T: Component + Hash + Eq + Clone>(&mut self) {
+        if self.contains_resource::<ComponentIndex<T>>() {
+            return;
+        }
+        let mut index = ComponentIndex {
+            entities: HashMap::default(),
+            values: HashMap::default(),
+            // values inserted from now on have a newer tick, so they are picked up by the next
+            // update
+            last_update_tick: self.increment_change_tick(),
+        };
+        for (entity, value) in self
+            .query_filtered::<(Entity, &T), AllowDisabled>()
+            .iter(self)
+        {
+            index.insert(entity, value);
+        }
+        self.insert_resource(index);
+        self.observe::<T, _>(LifecycleEvent::Remove, remove_from_index::<T>);
+        self.component_indices
+            .check_change_ticks
+            .push(check_index_change_tick::<T>);
+    }
+
+    /// Clamps the last update tick of every [`ComponentIndex`], so that the changes made since
+    /// then are still detected after the change tick wraps around.
+    pub(crate) fn check_index_change_ticks(&mut self, change_tick: u32) {
+        for check_change_tick in self.component_indices.check_change_ticks.clone() {
+            check_change_tick(self, change_tick);
+        }
+    }
+}
+
+/// A [`SystemParam`] that looks up entities by the value of their component `T`.
+///
+/// The index is kept in the [`ComponentIndex<T>`] resource, which is created with
+/// [`World::init_index`] when the system is initialized if it does not exist yet. Fetching the
+/// parameter applies the changes to `T` made since the last time any [`Index<T>`] was fetched, so
+/// only changed components are visited.
+///
+/// Because it updates the shared index, this parameter has mutable access to
+/// [`ComponentIndex<T>`], and read access to `T`.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{index::Index, prelude::*};
+///
+/// #[derive(Component, Clone, PartialEq, Eq, Hash)]
+/// struct NetworkId(u64);
+///
+/// struct Wanted(NetworkId);
+/// struct Found(Option<Entity>);
+///
+/// fn find(index: Index<NetworkId>, wanted: Res<Wanted>, mut found: ResMut<Found>) {
+///     found.0 = index.get(&wanted.0);
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(Wanted(NetworkId(3)));
+/// world.insert_resource(Found(None));
+/// let mut stage = SystemStage::single(find);
+/// stage.run(&mut world);
+/// assert_eq!(world.get_resource::<Found>().unwrap().0, None);
+///
+/// let entity = world.spawn().insert(NetworkId(3)).id();
+/// stage.run(&mut world);
+/// assert_eq!(world.get_resource::<Found>().unwrap().0, Some(entity));
+/// ```
+pub struct Index<'w, 's, T: Component + Hash + Eq + Clone> {
+    index: &'w ComponentIndex<T>,
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's, T: Component + Hash + Eq + Clone> Deref for Index<'w, 's, T> {
+    type Target = ComponentIndex<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.index
+    }
+}
+
+impl<'w, 's, T: Component + Hash + Eq + Clone> SystemParam for Index<'w, 's, T> {
+    type Fetch = IndexState<T>;
+}
+
+/// The [`SystemParamState`] of [`Index<T>`].
+pub struct IndexState<T: Component + Hash + Eq + Clone> {
+    index: ResMutState<ComponentIndex<T>>,
+    changed: QueryState<(Entity, &'static T), (Changed<T>, AllowDisabled)>,
+}
+
+// SAFE: the access of the ComponentIndex<T> resource and of the query is applied to SystemMeta by
+// their own states, which panic on conflicting access.
+unsafe impl<T: Component + Hash + Eq + Clone> SystemParamState for IndexState<T> {
+    fn init(world: &mut World, system_meta: &mut SystemMeta) -> Self {
+        world.init_index::<T>();
+        Self {
+            index: ResMutState::init(world, system_meta),
+            changed: SystemParamState::init(world, system_meta),
+        }
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype, system_meta: &mut SystemMeta) {
+        SystemParamState::new_archetype(&mut self.changed, archetype, system_meta);
+    }
+}
+
+impl<'w, 's, T: Component + Hash + Eq + Clone> SystemParamFetch<'w, 's> for IndexState<T> {
+    type Item = Index<'w, 's, T>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'s mut Self,
+        system_meta: &SystemMeta,
+        world: &'w World,
+        change_tick: u32,
+    ) -> Self::Item {
+        let index = ResMutState::get_param(&mut state.index, system_meta, world, change_tick).value;
+        for (entity, value) in state
+            .changed
+            .iter_unchecked_manual::<(EntityFetch, ReadFetch<T>)>(
+                world,
+                index.last_update_tick,
+                change_tick,
+            )
+        {
+            index.insert(entity, value);
+        }
+        index.last_update_tick = change_tick;
+        Index {
+            index,
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentIndex, Index};
+    use crate::{
+        self as bevy_ecs,
+        component::{Component, Disabled},
+        entity::Entity,
+        schedule::{ParallelSystemDescriptorCoercion, Stage, SystemStage},
+        system::{Commands, Query, ResMut},
+        world::World,
+    };
+
+    #[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+    struct Name(&'static str);
+
+    #[derive(Default)]
+    struct Found(Vec<Vec<Entity>>);
+
+    fn find(name: &'static str) -> impl FnMut(Index<Name>, ResMut<Found>) {
+        move |index: Index<Name>, mut found: ResMut<Found>| {
+            found.0.push(index.get_all(&Name(name)).to_vec());
+        }
+    }
+
+    #[test]
+    fn index_follows_changes() {
+        let mut world = World::new();
+        world.init_resource::<Found>();
+        let a = world.spawn().insert(Name("a")).id();
+        let mut stage = SystemStage::single_threaded().with_system(find("a"));
+        stage.run(&mut world);
+
+        let b = world.spawn().insert(Name("a")).id();
+        stage.run(&mut world);
+        world.get_mut::<Name>(a).unwrap().0 = "c";
+        stage.run(&mut world);
+        world.entity_mut(b).remove::<Name>();
+        stage.run(&mut world);
+        world.entity_mut(a).insert(Name("a"));
+        world.despawn(a);
+        stage.run(&mut world);
+
+        assert_eq!(
+            world.get_resource::<Found>().unwrap().0,
+            vec![vec![a], vec![a, b], vec![b], vec![], vec![]]
+        );
+        let index = world.get_resource::<ComponentIndex<Name>>().unwrap();
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn index_sees_changes_from_systems() {
+        fn rename(mut query: Query<&mut Name>) {
+            for mut name in query.iter_mut() {
+                name.0 = "b";
+            }
+        }
+
+        fn spawn(mut commands: Commands) {
+            commands.spawn().insert(Name("a"));
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Found>();
+        let mut stage = SystemStage::single_threaded()
+            .with_system(spawn.label("spawn"))
+            .with_system(find("a").label("find").after("spawn"))
+            .with_system(rename.after("find"));
+        stage.run(&mut world);
+        stage.run(&mut world);
+        stage.run(&mut world);
+
+        // every run finds the entity spawned by the previous one, which is renamed afterwards
+        let found = &world.get_resource::<Found>().unwrap().0;
+        assert_eq!(found.len(), 3);
+        assert!(found[0].is_empty());
+        assert_eq!(found[1].len(), 1);
+        assert_eq!(found[2].len(), 1);
+        assert_ne!(found[1], found[2]);
+        let index = world.get_resource::<ComponentIndex<Name>>().unwrap();
+        assert_eq!(index.get_all(&Name("b")), found[1].as_slice());
+    }
+
+    #[test]
+    fn clear_entities_clears_index() {
+        let mut world = World::new();
+        world.spawn().insert(Name("a"));
+        world.init_index::<Name>();
+        world.clear_entities();
+
+        let index = world.get_resource::<ComponentIndex<Name>>().unwrap();
+        assert!(index.is_empty());
+        // entity ids are reused after a clear, so a stale entry would be attributed to them
+        let b = world.spawn().insert(Name("b")).id();
+        world.init_resource::<Found>();
+        SystemStage::single_threaded()
+            .with_system(find("a"))
+            .run(&mut world);
+        assert_eq!(world.get_resource::<Found>().unwrap().0, vec![vec![]]);
+        let index = world.get_resource::<ComponentIndex<Name>>().unwrap();
+        assert_eq!(index.get(&Name("b")), Some(b));
+    }
+
+    #[test]
+    fn last_update_tick_is_clamped() {
+        const MAX_DELTA: u32 = (u32::MAX / 4) * 3;
+
+        let mut world = World::new();
+        world.init_index::<Name>();
+        *world.change_tick.get_mut() += MAX_DELTA + 1;
+        world.check_change_ticks();
+
+        let change_tick = world.change_tick();
+        let index = world.get_resource::<ComponentIndex<Name>>().unwrap();
+        assert!(change_tick.wrapping_sub(index.last_update_tick) <= MAX_DELTA);
+    }
+
+    #[test]
+    fn index_includes_disabled_entities() {
+        let mut world = World::new();
+        world.init_resource::<Found>();
+        let a = world.spawn().insert_bundle((Name("a"), Disabled)).id();
+        world.init_index::<Name>();
+        let mut stage = SystemStage::single_threaded().with_system(find("a"));
+
+        let b = world.spawn().insert_bundle((Name("b"), Disabled)).id();
+        stage.run(&mut world);
+        world.get_mut::<Name>(b).unwrap().0 = "a";
+        stage.run(&mut world);
+        world.entity_mut(a).remove::<Disabled>();
+        world.entity_mut(b).remove::<Disabled>();
+        stage.run(&mut world);
+
+        assert_eq!(
+            world.get_resource::<Found>().unwrap().0,
+            vec![vec![a], vec![a, b], vec![a, b]]
+        );
+    }
+}
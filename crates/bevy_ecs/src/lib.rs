@@ -6,10 +6,16 @@ pub mod change_detection;
 pub mod component;
 pub mod entity;
 pub mod event;
+pub mod index;
+pub mod observer;
 pub mod query;
 #[cfg(feature = "bevy_reflect")]
+pub mod recording;
+#[cfg(feature = "bevy_reflect")]
 pub mod reflect;
+pub mod relation;
 pub mod schedule;
+pub mod snapshot;
 pub mod storage;
 pub mod system;
 pub mod world;
@@ -23,18 +29,22 @@ pub mod prelude {
     pub use crate::{
         bundle::Bundle,
         change_detection::DetectChanges,
-        component::Component,
+        component::{Component, Disabled},
         entity::Entity,
         event::{EventReader, EventWriter},
-        query::{Added, AnyOf, ChangeTrackers, Changed, Or, QueryState, With, Without},
+        query::{
+            Added, AllowDisabled, AnyOf, ChangeTrackers, Changed, Or, QueryState, With, Without,
+        },
+        relation::Relation,
         schedule::{
-            AmbiguitySetLabel, ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
-            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaLabel, RunCriteriaPiping,
-            Schedule, Stage, StageLabel, State, SystemLabel, SystemSet, SystemStage,
+            AmbiguitySetLabel, CurrentState, ExclusiveSystemDescriptorCoercion, IntoRunCondition,
+            NextState, ParallelSystemDescriptorCoercion, RunCriteria,
+            RunCriteriaDescriptorCoercion, RunCriteriaLabel, RunCriteriaPiping, Schedule, Stage,
+            StageLabel, State, SystemGraph, SystemLabel, SystemSet, SystemStage,
         },
         system::{
             Commands, In, IntoChainSystem, IntoExclusiveSystem, IntoSystem, Local, NonSend,
-            NonSendMut, Query, QuerySet, RemovedComponents, Res, ResMut, System,
+            NonSendMut, Query, QuerySet, RemovedComponents, RemovedResources, Res, ResMut, System,
         },
         world::{FromWorld, Mut, World},
     };
@@ -54,7 +64,7 @@ mod tests {
         },
         world::{Mut, World},
     };
-    use bevy_tasks::TaskPool;
+    use bevy_tasks::{ParallelIterator, TaskPool};
     use parking_lot::Mutex;
     use std::{
         any::TypeId,
@@ -415,6 +425,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn par_iter_dense() {
+        let mut world = World::new();
+        let task_pool = TaskPool::default();
+        let e1 = world.spawn().insert(A(1)).id();
+        let e2 = world.spawn().insert(A(2)).id();
+        let e3 = world.spawn().insert_bundle((A(3), B(1))).id();
+        let mut results = world
+            .query::<(Entity, &A)>()
+            .par_iter(&world)
+            .batch_size(2)
+            .map(|(e, &A(i))| (e, i))
+            .collect::<Vec<_>>(&task_pool);
+        results.sort();
+        assert_eq!(results, [(e1, 1), (e2, 2), (e3, 3)]);
+
+        let mut query = world.query::<&mut A>();
+        query
+            .par_iter_mut(&mut world)
+            .for_each(&task_pool, |mut a| a.0 *= 10);
+        assert_eq!(
+            query
+                .par_iter(&world)
+                .map(|a| a.0)
+                .sum::<usize, usize>(&task_pool),
+            60
+        );
+    }
+
+    #[test]
+    fn par_iter_sparse() {
+        let mut world = World::new();
+        let task_pool = TaskPool::default();
+        for i in 0..100 {
+            let mut entity = world.spawn();
+            entity.insert(SparseStored(i));
+            if i % 3 == 0 {
+                entity.insert(A(i as usize));
+            }
+        }
+        let mut query = world.query::<&mut SparseStored>();
+        query
+            .par_iter_mut(&mut world)
+            .for_each(&task_pool, |mut s| s.0 += 1);
+        let results = Mutex::new(Vec::new());
+        world
+            .query::<&SparseStored>()
+            .par_iter(&world)
+            .batch_size(7)
+            .for_each(&task_pool, |s| results.lock().push(s.0));
+        let mut results = results.into_inner();
+        results.sort_unstable();
+        assert_eq!(results, (1..101).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn par_iter_changed() {
+        let mut world = World::new();
+        let task_pool = TaskPool::default();
+        let entities = (0..50)
+            .map(|i| world.spawn().insert_bundle((A(i), SparseStored(0))).id())
+            .collect::<Vec<_>>();
+        world.clear_trackers();
+        for entity in entities.iter().step_by(5) {
+            world.get_mut::<A>(*entity).unwrap().0 += 1;
+        }
+        let mut query = world.query_filtered::<&A, Changed<A>>();
+        assert_eq!(query.par_iter(&world).count(&task_pool), 10);
+
+        let mut query = world.query_filtered::<Entity, (Changed<A>, With<SparseStored>)>();
+        let mut results = query.par_iter(&world).collect::<Vec<_>>(&task_pool);
+        results.sort();
+        assert_eq!(
+            results,
+            entities.iter().step_by(5).copied().collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn query_missing_component() {
         let mut world = World::new();
@@ -0,0 +1,319 @@
+//! Types for running systems in response to component lifecycle events.
+//!
+//! An observer is a system that takes the affected [`Entity`] as its [`In`](crate::system::In)
+//! parameter and is run immediately whenever a [`LifecycleEvent`] occurs for the component it
+//! observes. Observers are registered with
+//! [`World::observe`](crate::world::World::observe).
+
+use crate::{
+    component::{ComponentId, Components, LifecycleEvent},
+    entity::Entity,
+    storage::SparseSet,
+    system::StoredSystem,
+};
+
+pub(crate) type Observer = StoredSystem<Entity, ()>;
+
+/// The observers of a single component, grouped by [`LifecycleEvent`].
+#[derive(Default)]
+struct ComponentObservers {
+    on_add: Vec<Observer>,
+    on_insert: Vec<Observer>,
+    on_remove: Vec<Observer>,
+}
+
+impl ComponentObservers {
+    fn get_mut(&mut self, event: LifecycleEvent) -> &mut Vec<Observer> {
+        match event {
+            LifecycleEvent::Add => &mut self.on_add,
+            LifecycleEvent::Insert => &mut self.on_insert,
+            LifecycleEvent::Remove => &mut self.on_remove,
+        }
+    }
+}
+
+/// Stores every observer registered in a [`World`](crate::world::World).
+#[derive(Default)]
+pub struct Observers {
+    observers: SparseSet<ComponentId, ComponentObservers>,
+    count: usize,
+}
+
+impl Observers {
+    /// Returns the total number of registered observers.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns `true` if any observer is registered for `component_id`.
+    #[inline]
+    pub fn contains(&self, component_id: ComponentId) -> bool {
+        self.observers.contains(component_id)
+    }
+
+    /// Returns `true` if a hook or an observer is registered for any [`LifecycleEvent`] of
+    /// `component_id`.
+    #[inline]
+    pub(crate) fn has_listeners(&self, components: &Components, component_id: ComponentId) -> bool {
+        self.contains(component_id)
+            || components
+                .get_info(component_id)
+                .map(|info| !info.hooks().is_empty())
+                .unwrap_or(false)
+    }
+
+    pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
+        for observers in self.observers.values_mut() {
+            for observer in observers
+                .on_add
+                .iter_mut()
+                .chain(&mut observers.on_insert)
+                .chain(&mut observers.on_remove)
+            {
+                observer.check_change_tick(change_tick);
+            }
+        }
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        component_id: ComponentId,
+        event: LifecycleEvent,
+        observer: Observer,
+    ) {
+        self.observers
+            .get_or_insert_with(component_id, ComponentObservers::default)
+            .get_mut(event)
+            .push(observer);
+        self.count += 1;
+    }
+
+    pub(crate) fn take(
+        &mut self,
+        component_id: ComponentId,
+        event: LifecycleEvent,
+    ) -> Vec<Observer> {
+        self.observers
+            .get_mut(component_id)
+            .map(|observers| std::mem::take(observers.get_mut(event)))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn restore(
+        &mut self,
+        component_id: ComponentId,
+        event: LifecycleEvent,
+        taken: Vec<Observer>,
+    ) {
+        let observers = self
+            .observers
+            .get_or_insert_with(component_id, ComponentObservers::default)
+            .get_mut(event);
+        // keep observers registered while `taken` was running after the existing ones
+        let added = std::mem::replace(observers, taken);
+        observers.extend(added);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        component::{Component, ComponentId, LifecycleEvent},
+        entity::Entity,
+        system::{Commands, In, Query, ResMut},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct A(usize);
+
+    #[derive(Component)]
+    #[component(storage = "SparseSet")]
+    struct B;
+
+    #[derive(Component)]
+    struct C;
+
+    #[derive(Default)]
+    struct Log(Vec<(&'static str, Entity)>);
+
+    fn log(world: &mut World, entity: Entity, message: &'static str) {
+        world
+            .get_resource_mut::<Log>()
+            .unwrap()
+            .0
+            .push((message, entity));
+    }
+
+    #[test]
+    fn hooks_run_on_insert_remove_and_despawn() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world
+            .register_component_hooks::<A>()
+            .on_add(|world, entity, _| log(world, entity, "add"))
+            .on_insert(|world, entity, _| log(world, entity, "insert"))
+            .on_remove(|world, entity, _| log(world, entity, "remove"));
+
+        let e = world.spawn().insert(A(0)).id();
+        world.entity_mut(e).insert(A(1));
+        world.entity_mut(e).remove::<A>();
+        world.entity_mut(e).insert_bundle((A(2), B));
+        world.entity_mut(e).remove_bundle_intersection::<(A, B)>();
+        world.entity_mut(e).insert(A(3));
+        world.despawn(e);
+
+        assert_eq!(
+            world.get_resource::<Log>().unwrap().0,
+            vec![
+                ("add", e),
+                ("insert", e),
+                ("insert", e),
+                ("remove", e),
+                ("add", e),
+                ("insert", e),
+                ("remove", e),
+                ("add", e),
+                ("insert", e),
+                ("remove", e),
+            ]
+        );
+    }
+
+    #[test]
+    fn hooks_see_component_values() {
+        #[derive(Default)]
+        struct Sum(usize);
+
+        let mut world = World::new();
+        world.init_resource::<Sum>();
+        fn value(world: &World, entity: Entity) -> usize {
+            world.get::<A>(entity).unwrap().0
+        }
+        world
+            .register_component_hooks::<A>()
+            .on_insert(|world, entity, _| {
+                world.get_resource_mut::<Sum>().unwrap().0 += value(world, entity);
+            })
+            .on_remove(|world, entity, _| {
+                world.get_resource_mut::<Sum>().unwrap().0 -= value(world, entity);
+            });
+
+        let e1 = world.spawn().insert(A(3)).id();
+        let e2 = world.spawn().insert(A(4)).id();
+        assert_eq!(world.get_resource::<Sum>().unwrap().0, 7);
+        world.despawn(e1);
+        assert_eq!(world.get_resource::<Sum>().unwrap().0, 4);
+        assert_eq!(world.entity_mut(e2).remove::<A>().unwrap().0, 4);
+        assert_eq!(world.get_resource::<Sum>().unwrap().0, 0);
+    }
+
+    #[test]
+    fn removing_missing_components_does_not_run_hooks() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world
+            .register_component_hooks::<A>()
+            .on_remove(|world, entity, _| log(world, entity, "remove"));
+
+        let e = world.spawn().insert(B).id();
+        assert!(world.entity_mut(e).remove::<A>().is_none());
+        world.entity_mut(e).insert(A(0));
+        assert!(world.entity_mut(e).remove_bundle::<(A, C)>().is_none());
+        assert!(world.get_resource::<Log>().unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn observers_run_with_system_params() {
+        #[derive(Default)]
+        struct Total(usize);
+
+        fn add_total(In(entity): In<Entity>, query: Query<&A>, mut total: ResMut<Total>) {
+            total.0 += query.get(entity).unwrap().0;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Total>();
+        world.observe::<A, _>(LifecycleEvent::Insert, add_total);
+        assert_eq!(world.observers().len(), 1);
+
+        world.spawn().insert(A(1));
+        world.spawn().insert_bundle((A(2), B));
+        world.spawn().insert(B).insert(A(3));
+        assert_eq!(world.get_resource::<Total>().unwrap().0, 6);
+    }
+
+    #[test]
+    fn observer_commands_are_applied() {
+        fn tag(In(entity): In<Entity>, mut commands: Commands) {
+            commands.entity(entity).insert(B);
+        }
+
+        let mut world = World::new();
+        world.observe::<A, _>(LifecycleEvent::Add, tag);
+        let e = world.spawn().insert(A(0)).id();
+        assert!(world.get::<B>(e).is_some());
+    }
+
+    #[test]
+    fn observer_despawning_entity_stops_removal() {
+        fn despawn(In(entity): In<Entity>, mut commands: Commands) {
+            commands.entity(entity).despawn();
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.observe::<B, _>(LifecycleEvent::Remove, despawn);
+        world
+            .register_component_hooks::<A>()
+            .on_remove(|world, entity, _| log(world, entity, "remove"));
+
+        let e = world.spawn().insert_bundle((A(0), B)).id();
+        world.entity_mut(e).remove::<B>();
+        assert!(world.get_entity(e).is_none());
+        assert_eq!(world.get_resource::<Log>().unwrap().0, vec![("remove", e)]);
+    }
+
+    #[test]
+    fn observers_do_not_trigger_themselves() {
+        #[derive(Default)]
+        struct Count(usize);
+
+        fn reinsert(In(entity): In<Entity>, mut commands: Commands, mut count: ResMut<Count>) {
+            count.0 += 1;
+            commands.entity(entity).insert(A(count.0));
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Count>();
+        world.observe::<A, _>(LifecycleEvent::Insert, reinsert);
+        let e = world.spawn().insert(A(0)).id();
+        assert_eq!(world.get_resource::<Count>().unwrap().0, 1);
+        assert_eq!(world.get::<A>(e).unwrap().0, 1);
+    }
+
+    #[test]
+    fn hook_receives_component_id() {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let id = world.init_component::<A>();
+        world.components_mut().get_hooks_mut(id).unwrap().on_add(
+            |world, entity, id: ComponentId| {
+                assert_eq!(
+                    Some(id),
+                    world.components().get_id(std::any::TypeId::of::<A>())
+                );
+                log(world, entity, "add");
+            },
+        );
+        let e = world.spawn().insert(A(0)).id();
+        assert_eq!(world.get_resource::<Log>().unwrap().0, vec![("add", e)]);
+    }
+}
@@ -152,6 +152,11 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
         &self.access
     }
 
+    #[inline]
+    pub fn access_mut(&mut self) -> &mut Access<T> {
+        &mut self.access
+    }
+
     pub fn add_read(&mut self, index: T) {
         self.access.add_read(index.clone());
         self.add_with(index);
@@ -190,6 +195,14 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
     pub fn read_all(&mut self) {
         self.access.read_all();
     }
+
+    /// Returns `true` if `index` is read or written, or used in a `With` or `Without` filter.
+    pub fn contains(&self, index: T) -> bool {
+        let sparse_set_index = index.sparse_set_index();
+        self.access.has_read(index)
+            || self.with.contains(sparse_set_index)
+            || self.without.contains(sparse_set_index)
+    }
 }
 
 pub struct FilteredAccessSet<T: SparseSetIndex> {
@@ -0,0 +1,1026 @@
+use crate::{
+    archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
+    change_detection::{Mut, Ticks},
+    component::{ComponentId, ComponentTicks, Disabled, StorageType},
+    entity::Entity,
+    query::{Access, FilteredAccess, QueryEntityError},
+    system::{check_system_change_tick, System},
+    world::{World, WorldId},
+};
+use fixedbitset::FixedBitSet;
+use std::{any::TypeId, borrow::Cow, marker::PhantomData, ptr::NonNull};
+use thiserror::Error;
+
+/// A single term of a [`DynamicQuery`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicTerm {
+    /// Fetches the component immutably. Only matches entities that have it.
+    Read(ComponentId),
+    /// Fetches the component mutably. Only matches entities that have it.
+    Write(ComponentId),
+    /// Fetches the component immutably if the entity has it.
+    OptionalRead(ComponentId),
+    /// Fetches the component mutably if the entity has it.
+    OptionalWrite(ComponentId),
+    /// Only matches entities that have the component, without fetching it.
+    With(ComponentId),
+    /// Only matches entities that do not have the component.
+    Without(ComponentId),
+}
+
+impl DynamicTerm {
+    /// Returns the id of the component this term refers to.
+    #[inline]
+    pub fn component_id(&self) -> ComponentId {
+        match *self {
+            DynamicTerm::Read(id)
+            | DynamicTerm::Write(id)
+            | DynamicTerm::OptionalRead(id)
+            | DynamicTerm::OptionalWrite(id)
+            | DynamicTerm::With(id)
+            | DynamicTerm::Without(id) => id,
+        }
+    }
+
+    /// Returns `true` if this term produces a [`DynamicComponent`] in each query item.
+    #[inline]
+    pub fn is_fetched(&self) -> bool {
+        !matches!(self, DynamicTerm::With(_) | DynamicTerm::Without(_))
+    }
+
+    #[inline]
+    fn is_write(&self) -> bool {
+        matches!(self, DynamicTerm::Write(_) | DynamicTerm::OptionalWrite(_))
+    }
+
+    #[inline]
+    fn is_optional(&self) -> bool {
+        matches!(
+            self,
+            DynamicTerm::OptionalRead(_) | DynamicTerm::OptionalWrite(_)
+        )
+    }
+
+    fn matches_archetype(&self, archetype: &Archetype) -> bool {
+        match *self {
+            DynamicTerm::Read(id) | DynamicTerm::Write(id) | DynamicTerm::With(id) => {
+                archetype.contains(id)
+            }
+            DynamicTerm::Without(id) => !archetype.contains(id),
+            DynamicTerm::OptionalRead(_) | DynamicTerm::OptionalWrite(_) => true,
+        }
+    }
+}
+
+/// An error that occurs when building a [`DynamicQuery`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DynamicQueryError {
+    #[error("Component {0:?} is not registered in this World")]
+    UnknownComponent(ComponentId),
+    #[error("Component {0:?} is accessed mutably and also accessed by another term of this query")]
+    ConflictingAccess(ComponentId),
+}
+
+/// Builds a [`DynamicQuery`] from a list of [`DynamicTerm`]s.
+///
+/// Created by [`DynamicQuery::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct DynamicQueryBuilder {
+    terms: Vec<DynamicTerm>,
+}
+
+impl DynamicQueryBuilder {
+    /// Adds `term` to the query.
+    pub fn term(mut self, term: DynamicTerm) -> Self {
+        self.terms.push(term);
+        self
+    }
+
+    /// Adds a [`DynamicTerm::Read`] term to the query.
+    pub fn read(self, component_id: ComponentId) -> Self {
+        self.term(DynamicTerm::Read(component_id))
+    }
+
+    /// Adds a [`DynamicTerm::Write`] term to the query.
+    pub fn write(self, component_id: ComponentId) -> Self {
+        self.term(DynamicTerm::Write(component_id))
+    }
+
+    /// Adds a [`DynamicTerm::OptionalRead`] term to the query.
+    pub fn optional_read(self, component_id: ComponentId) -> Self {
+        self.term(DynamicTerm::OptionalRead(component_id))
+    }
+
+    /// Adds a [`DynamicTerm::OptionalWrite`] term to the query.
+    pub fn optional_write(self, component_id: ComponentId) -> Self {
+        self.term(DynamicTerm::OptionalWrite(component_id))
+    }
+
+    /// Adds a [`DynamicTerm::With`] term to the query.
+    pub fn with(self, component_id: ComponentId) -> Self {
+        self.term(DynamicTerm::With(component_id))
+    }
+
+    /// Adds a [`DynamicTerm::Without`] term to the query.
+    pub fn without(self, component_id: ComponentId) -> Self {
+        self.term(DynamicTerm::Without(component_id))
+    }
+
+    /// Builds the query for `world`.
+    ///
+    /// Returns an error if a term refers to a component that is not registered in `world`, or if
+    /// a component is written by one term and read or written by another.
+    pub fn build(self, world: &World) -> Result<DynamicQuery, DynamicQueryError> {
+        let mut component_access = FilteredAccess::default();
+        let mut storage_types = Vec::with_capacity(self.terms.len());
+        for term in &self.terms {
+            let id = term.component_id();
+            let info = world
+                .components()
+                .get_info(id)
+                .ok_or(DynamicQueryError::UnknownComponent(id))?;
+            storage_types.push(info.storage_type());
+            if term.is_fetched() {
+                let access = component_access.access();
+                if access.has_write(id) || (term.is_write() && access.has_read(id)) {
+                    return Err(DynamicQueryError::ConflictingAccess(id));
+                }
+            }
+            match *term {
+                DynamicTerm::Read(id) => {
+                    component_access.add_read(id);
+                    component_access.add_with(id);
+                }
+                DynamicTerm::Write(id) => {
+                    component_access.add_write(id);
+                    component_access.add_with(id);
+                }
+                DynamicTerm::OptionalRead(id) => {
+                    component_access.add_read(id);
+                }
+                DynamicTerm::OptionalWrite(id) => {
+                    component_access.add_write(id);
+                }
+                DynamicTerm::With(id) => component_access.add_with(id),
+                DynamicTerm::Without(id) => component_access.add_without(id),
+            }
+        }
+
+        let allows_disabled = matches!(
+            world.components().get_id(TypeId::of::<Disabled>()),
+            Some(id) if component_access.contains(id)
+        );
+        let mut query = DynamicQuery {
+            world_id: world.id(),
+            terms: self.terms,
+            storage_types,
+            archetype_generation: ArchetypeGeneration::initial(),
+            matched_archetypes: Default::default(),
+            matched_archetype_ids: Vec::new(),
+            component_access,
+            archetype_component_access: Default::default(),
+            allows_disabled,
+        };
+        query.update_archetypes(world);
+        Ok(query)
+    }
+}
+
+/// A query whose terms are only known at runtime, such as queries built by scripts or editors.
+///
+/// Unlike [`QueryState`](crate::query::QueryState), a [`DynamicQuery`] is built from
+/// [`ComponentId`]s and yields type-erased [`DynamicComponent`]s. Its access is tracked with the
+/// same [`FilteredAccess`] as static queries, so [`DynamicQuery::into_system`] can run it in
+/// parallel with other systems.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{prelude::*, query::DynamicQuery};
+///
+/// #[derive(Component)]
+/// struct Position(f32);
+/// #[derive(Component)]
+/// struct Velocity(f32);
+///
+/// let mut world = World::new();
+/// world.spawn().insert_bundle((Position(0.0), Velocity(1.0)));
+/// world.spawn().insert(Position(0.0));
+///
+/// let position = world.init_component::<Position>();
+/// let velocity = world.init_component::<Velocity>();
+/// let mut query = DynamicQuery::builder()
+///     .write(position)
+///     .read(velocity)
+///     .build(&world)
+///     .unwrap();
+///
+/// for mut item in query.iter_mut(&mut world) {
+///     // SAFE: the components were fetched by id in the same order as the terms
+///     unsafe {
+///         let velocity = item.components()[1].as_ref().unwrap().deref::<Velocity>().0;
+///         let position = item.components_mut()[0].as_mut().unwrap();
+///         position.deref_mut::<Position>().unwrap().0 += velocity;
+///     }
+/// }
+/// ```
+pub struct DynamicQuery {
+    world_id: WorldId,
+    terms: Vec<DynamicTerm>,
+    storage_types: Vec<StorageType>,
+    archetype_generation: ArchetypeGeneration,
+    matched_archetypes: FixedBitSet,
+    matched_archetype_ids: Vec<ArchetypeId>,
+    component_access: FilteredAccess<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+    /// Whether the query matches [`Disabled`] entities, which it does if a term refers to the
+    /// [`Disabled`] component.
+    allows_disabled: bool,
+}
+
+impl DynamicQuery {
+    /// Returns a [`DynamicQueryBuilder`] with no terms.
+    pub fn builder() -> DynamicQueryBuilder {
+        DynamicQueryBuilder::default()
+    }
+
+    /// Returns the terms of this query, in the order they were added.
+    #[inline]
+    pub fn terms(&self) -> &[DynamicTerm] {
+        &self.terms
+    }
+
+    /// Returns the [`ComponentId`] access of this query.
+    #[inline]
+    pub fn component_access(&self) -> &FilteredAccess<ComponentId> {
+        &self.component_access
+    }
+
+    /// Returns the [`ArchetypeComponentId`] access of this query for the archetypes it has seen.
+    #[inline]
+    pub fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    /// Checks that `world` is the world this query was built for, and matches the archetypes
+    /// created since the last update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `world.id()` does not equal the id of the world this query was built for.
+    pub fn update_archetypes(&mut self, world: &World) {
+        self.validate_world(world);
+        let archetypes = world.archetypes();
+        let new_generation = archetypes.generation();
+        let old_generation = std::mem::replace(&mut self.archetype_generation, new_generation);
+        for archetype_index in old_generation.value()..new_generation.value() {
+            self.new_archetype(&archetypes[ArchetypeId::new(archetype_index)]);
+        }
+    }
+
+    #[inline]
+    pub fn validate_world(&self, world: &World) {
+        assert!(
+            world.id() == self.world_id,
+            "Attempted to use a DynamicQuery with a mismatched World. DynamicQueries can only be used with the World they were built for.",
+        );
+    }
+
+    /// Matches `archetype` against the terms of this query.
+    pub fn new_archetype(&mut self, archetype: &Archetype) {
+        if (archetype.is_disabled() && !self.allows_disabled)
+            || !self
+                .terms
+                .iter()
+                .all(|term| term.matches_archetype(archetype))
+        {
+            return;
+        }
+        for term in self.terms.iter().filter(|term| term.is_fetched()) {
+            let id = term.component_id();
+            if let Some(archetype_component_id) = archetype.get_archetype_component_id(id) {
+                if term.is_write() {
+                    self.archetype_component_access
+                        .add_write(archetype_component_id);
+                } else {
+                    self.archetype_component_access
+                        .add_read(archetype_component_id);
+                }
+            }
+        }
+        let archetype_index = archetype.id().index();
+        if !self.matched_archetypes.contains(archetype_index) {
+            self.matched_archetypes.grow(archetype_index + 1);
+            self.matched_archetypes.set(archetype_index, true);
+            self.matched_archetype_ids.push(archetype.id());
+        }
+    }
+
+    /// Gets the query item for `entity`. Components fetched by write terms are read-only.
+    pub fn get<'w>(
+        &mut self,
+        world: &'w World,
+        entity: Entity,
+    ) -> Result<DynamicQueryItem<'w>, QueryEntityError> {
+        self.update_archetypes(world);
+        // SAFE: components fetched with read-only access
+        unsafe {
+            self.get_unchecked_manual(
+                world,
+                entity,
+                false,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Gets the query item for `entity`, with mutable access to the components fetched by write
+    /// terms.
+    pub fn get_mut<'w>(
+        &mut self,
+        world: &'w mut World,
+        entity: Entity,
+    ) -> Result<DynamicQueryItem<'w>, QueryEntityError> {
+        self.update_archetypes(world);
+        // SAFE: query has unique world access
+        unsafe {
+            self.get_unchecked_manual(
+                world,
+                entity,
+                true,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// # Safety
+    ///
+    /// If `writable` is `true`, the caller must ensure nothing else accesses the components
+    /// written by this query.
+    unsafe fn get_unchecked_manual<'w>(
+        &self,
+        world: &'w World,
+        entity: Entity,
+        writable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Result<DynamicQueryItem<'w>, QueryEntityError> {
+        let location = world
+            .entities
+            .get(entity)
+            .ok_or(QueryEntityError::NoSuchEntity)?;
+        if !self
+            .matched_archetypes
+            .contains(location.archetype_id.index())
+        {
+            return Err(QueryEntityError::QueryDoesNotMatch);
+        }
+        let archetype = &world.archetypes[location.archetype_id];
+        Ok(self.fetch(
+            world,
+            archetype,
+            location.index,
+            writable,
+            last_change_tick,
+            change_tick,
+        ))
+    }
+
+    /// Returns an [`Iterator`] over the query items. Components fetched by write terms are
+    /// read-only.
+    pub fn iter<'w, 's>(&'s mut self, world: &'w World) -> DynamicQueryIter<'w, 's> {
+        self.update_archetypes(world);
+        // SAFE: components fetched with read-only access
+        unsafe {
+            self.iter_unchecked_manual(
+                world,
+                false,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns an [`Iterator`] over the query items, with mutable access to the components
+    /// fetched by write terms.
+    pub fn iter_mut<'w, 's>(&'s mut self, world: &'w mut World) -> DynamicQueryIter<'w, 's> {
+        self.update_archetypes(world);
+        // SAFE: query has unique world access
+        unsafe {
+            self.iter_unchecked_manual(
+                world,
+                true,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns an [`Iterator`] over the query items, with mutable access to the components
+    /// fetched by write terms, without updating the query's archetypes.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure nothing else
+    /// accesses the components written by this query while the iterator or its items are alive.
+    pub unsafe fn iter_unchecked_manual<'w, 's>(
+        &'s self,
+        world: &'w World,
+        writable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> DynamicQueryIter<'w, 's> {
+        DynamicQueryIter {
+            world,
+            query: self,
+            archetype_ids: self.matched_archetype_ids.iter(),
+            archetype: None,
+            index: 0,
+            writable,
+            last_change_tick,
+            change_tick,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `archetype` must be matched by this query and `index` must be in bounds. If `writable` is
+    /// `true`, the caller must ensure nothing else accesses the components written by this query.
+    unsafe fn fetch<'w>(
+        &self,
+        world: &'w World,
+        archetype: &'w Archetype,
+        index: usize,
+        writable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> DynamicQueryItem<'w> {
+        let entity = archetype.entities()[index];
+        let table_row = archetype.entity_table_row(index);
+        let table = &world.storages().tables[archetype.table_id()];
+        let components = self
+            .terms
+            .iter()
+            .zip(&self.storage_types)
+            .filter(|(term, _)| term.is_fetched())
+            .map(|(term, storage_type)| {
+                let id = term.component_id();
+                if term.is_optional() && !archetype.contains(id) {
+                    return None;
+                }
+                let (ptr, ticks) = match storage_type {
+                    StorageType::Table => {
+                        let column = table.get_column(id).unwrap();
+                        (
+                            column.get_data_unchecked(table_row),
+                            column.get_ticks_mut_ptr_unchecked(table_row),
+                        )
+                    }
+                    StorageType::SparseSet => world
+                        .storages()
+                        .sparse_sets
+                        .get(id)
+                        .unwrap()
+                        .get_with_ticks(entity)
+                        .unwrap(),
+                };
+                Some(DynamicComponent::new(
+                    id,
+                    ptr,
+                    ticks,
+                    writable && term.is_write(),
+                    last_change_tick,
+                    change_tick,
+                ))
+            })
+            .collect();
+        DynamicQueryItem { entity, components }
+    }
+
+    /// Turns this query into a [`System`] that calls `func` with the query items every time it
+    /// runs. The system has mutable access to the components fetched by write terms, and can run
+    /// in parallel with any system whose access does not conflict with the query.
+    ///
+    /// # Panics
+    ///
+    /// The system panics when initialized in a different world than the one this query was built
+    /// for.
+    pub fn into_system<F>(self, func: F) -> DynamicQuerySystem<F>
+    where
+        F: FnMut(DynamicQueryIter) + Send + Sync + 'static,
+    {
+        DynamicQuerySystem {
+            query: self,
+            func,
+            last_change_tick: 0,
+        }
+    }
+}
+
+/// Iterator over the items of a [`DynamicQuery`].
+pub struct DynamicQueryIter<'w, 's> {
+    world: &'w World,
+    query: &'s DynamicQuery,
+    archetype_ids: std::slice::Iter<'s, ArchetypeId>,
+    archetype: Option<&'w Archetype>,
+    index: usize,
+    writable: bool,
+    last_change_tick: u32,
+    change_tick: u32,
+}
+
+impl<'w, 's> Iterator for DynamicQueryIter<'w, 's> {
+    type Item = DynamicQueryItem<'w>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.archetype {
+                Some(archetype) if self.index < archetype.len() => {
+                    let index = self.index;
+                    self.index += 1;
+                    // SAFE: the archetype is matched by the query, the index is in bounds, and
+                    // each entity is only yielded once
+                    return Some(unsafe {
+                        self.query.fetch(
+                            self.world,
+                            archetype,
+                            index,
+                            self.writable,
+                            self.last_change_tick,
+                            self.change_tick,
+                        )
+                    });
+                }
+                _ => {
+                    let id = self.archetype_ids.next()?;
+                    self.archetype = Some(&self.world.archetypes[*id]);
+                    self.index = 0;
+                }
+            }
+        }
+    }
+}
+
+/// The result of a [`DynamicQuery`] for a single entity.
+pub struct DynamicQueryItem<'w> {
+    entity: Entity,
+    components: Vec<Option<DynamicComponent<'w>>>,
+}
+
+impl<'w> DynamicQueryItem<'w> {
+    /// Returns the entity this item belongs to.
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Returns the fetched components, with one entry per read, write or optional term, in the
+    /// order of the terms. Entries of optional terms are `None` if the entity does not have the
+    /// component.
+    #[inline]
+    pub fn components(&self) -> &[Option<DynamicComponent<'w>>] {
+        &self.components
+    }
+
+    /// Returns the fetched components mutably. See [`DynamicQueryItem::components`].
+    #[inline]
+    pub fn components_mut(&mut self) -> &mut [Option<DynamicComponent<'w>>] {
+        &mut self.components
+    }
+
+    /// Returns the fetched components. See [`DynamicQueryItem::components`].
+    #[inline]
+    pub fn into_components(self) -> Vec<Option<DynamicComponent<'w>>> {
+        self.components
+    }
+}
+
+/// A type-erased reference to a component fetched by a [`DynamicQuery`] or by
+/// [`EntityRef::get_by_id`](crate::world::EntityRef::get_by_id).
+///
+/// The component can be viewed through [`ReflectComponent`](crate::reflect::ReflectComponent)
+/// with [`DynamicComponent::reflect`], or as a concrete type with [`DynamicComponent::deref`].
+pub struct DynamicComponent<'w> {
+    id: ComponentId,
+    ptr: NonNull<u8>,
+    ticks: *mut ComponentTicks,
+    writable: bool,
+    last_change_tick: u32,
+    change_tick: u32,
+    marker: PhantomData<&'w mut u8>,
+}
+
+impl<'w> DynamicComponent<'w> {
+    /// # Safety
+    /// `ptr` and `ticks` must point to the value and ticks of the component `id` and stay valid
+    /// for `'w`. If `writable` is `true`, nothing else may access them during `'w`.
+    #[inline]
+    pub(crate) unsafe fn new(
+        id: ComponentId,
+        ptr: *mut u8,
+        ticks: *mut ComponentTicks,
+        writable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        Self {
+            id,
+            ptr: NonNull::new_unchecked(ptr),
+            ticks,
+            writable,
+            last_change_tick,
+            change_tick,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the id of the component.
+    #[inline]
+    pub fn id(&self) -> ComponentId {
+        self.id
+    }
+
+    /// Returns `true` if the component can be accessed mutably.
+    #[inline]
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Returns a pointer to the component.
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns a mutable pointer to the component and flags it as changed, or `None` if the
+    /// component was not fetched mutably.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> Option<*mut u8> {
+        if !self.writable {
+            return None;
+        }
+        self.set_changed();
+        Some(self.ptr.as_ptr())
+    }
+
+    /// Returns the change ticks of the component.
+    #[inline]
+    pub fn ticks(&self) -> &ComponentTicks {
+        // SAFE: the ticks are only written through `&mut self`
+        unsafe { &*self.ticks }
+    }
+
+    /// Returns `true` if the component was added since the last time the query ran.
+    #[inline]
+    pub fn is_added(&self) -> bool {
+        self.ticks()
+            .is_added(self.last_change_tick, self.change_tick)
+    }
+
+    /// Returns `true` if the component was changed since the last time the query ran.
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.ticks()
+            .is_changed(self.last_change_tick, self.change_tick)
+    }
+
+    fn set_changed(&mut self) {
+        // SAFE: the component was fetched mutably, so nothing else accesses its ticks
+        unsafe { (*self.ticks).set_changed(self.change_tick) }
+    }
+
+    /// Returns the component as a `T`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the type of the component.
+    #[inline]
+    pub unsafe fn deref<T>(&self) -> &T {
+        &*self.ptr.cast::<T>().as_ptr()
+    }
+
+    /// Returns the component as a mutable `T`, or `None` if the component was not fetched
+    /// mutably. The component is flagged as changed when the returned [`Mut`] is dereferenced
+    /// mutably.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the type of the component.
+    #[inline]
+    pub unsafe fn deref_mut<T>(&mut self) -> Option<Mut<'_, T>> {
+        if !self.writable {
+            return None;
+        }
+        Some(Mut {
+            value: &mut *self.ptr.cast::<T>().as_ptr(),
+            ticks: Ticks {
+                component_ticks: &mut *self.ticks,
+                last_change_tick: self.last_change_tick,
+                change_tick: self.change_tick,
+            },
+        })
+    }
+
+    /// Returns the component as a reflected value.
+    ///
+    /// # Safety
+    ///
+    /// `reflect_component` must have been created for the type of the component.
+    #[cfg(feature = "bevy_reflect")]
+    #[inline]
+    pub unsafe fn reflect(
+        &self,
+        reflect_component: &crate::reflect::ReflectComponent,
+    ) -> &dyn bevy_reflect::Reflect {
+        reflect_component.reflect_ptr(self.ptr.as_ptr())
+    }
+
+    /// Returns the component as a mutable reflected value, or `None` if the component was not
+    /// fetched mutably. The component is flagged as changed when the returned
+    /// [`ReflectMut`](crate::reflect::ReflectMut) is dereferenced mutably.
+    ///
+    /// # Safety
+    ///
+    /// `reflect_component` must have been created for the type of the component.
+    #[cfg(feature = "bevy_reflect")]
+    #[inline]
+    pub unsafe fn reflect_mut(
+        &mut self,
+        reflect_component: &crate::reflect::ReflectComponent,
+    ) -> Option<crate::reflect::ReflectMut<'_>> {
+        if !self.writable {
+            return None;
+        }
+        Some(crate::reflect::ReflectMut {
+            value: reflect_component.reflect_ptr_mut(self.ptr.as_ptr()),
+            ticks: Ticks {
+                component_ticks: &mut *self.ticks,
+                last_change_tick: self.last_change_tick,
+                change_tick: self.change_tick,
+            },
+        })
+    }
+}
+
+/// A [`System`] that runs a [`DynamicQuery`], created by [`DynamicQuery::into_system`].
+pub struct DynamicQuerySystem<F> {
+    query: DynamicQuery,
+    func: F,
+    last_change_tick: u32,
+}
+
+impl<F> DynamicQuerySystem<F> {
+    /// Returns the query run by this system.
+    #[inline]
+    pub fn query(&self) -> &DynamicQuery {
+        &self.query
+    }
+}
+
+impl<F> System for DynamicQuerySystem<F>
+where
+    F: FnMut(DynamicQueryIter) + Send + Sync + 'static,
+{
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(std::any::type_name::<F>())
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype) {
+        self.query.new_archetype(archetype);
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        self.query.component_access.access()
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.query.archetype_component_access
+    }
+
+    fn is_send(&self) -> bool {
+        true
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), world: &World) {
+        let change_tick = world.increment_change_tick();
+        let iter =
+            self.query
+                .iter_unchecked_manual(world, true, self.last_change_tick, change_tick);
+        (self.func)(iter);
+        self.last_change_tick = change_tick;
+    }
+
+    fn apply_buffers(&mut self, _world: &mut World) {}
+
+    fn initialize(&mut self, world: &mut World) {
+        self.query.validate_world(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        let name = self.name();
+        check_system_change_tick(&mut self.last_change_tick, change_tick, name.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynamicQuery, DynamicQueryError};
+    use crate::{
+        self as bevy_ecs,
+        component::Component,
+        query::QueryEntityError,
+        schedule::{Stage, SystemStage},
+        world::World,
+    };
+
+    #[derive(Component, Debug, PartialEq)]
+    struct A(usize);
+
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct B(usize);
+
+    #[derive(Component)]
+    struct C;
+
+    #[test]
+    fn dynamic_query_terms() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), B(10))).id();
+        let e2 = world.spawn().insert(A(2)).id();
+        world.spawn().insert_bundle((A(3), C));
+        world.spawn().insert(B(40));
+        let a = world.init_component::<A>();
+        let b = world.init_component::<B>();
+        let c = world.init_component::<C>();
+
+        let mut query = DynamicQuery::builder()
+            .read(a)
+            .optional_read(b)
+            .without(c)
+            .build(&world)
+            .unwrap();
+        let mut results = query
+            .iter(&world)
+            .map(|item| unsafe {
+                let components = item.components();
+                (
+                    item.entity(),
+                    components[0].as_ref().unwrap().deref::<A>().0,
+                    components[1].as_ref().map(|b| b.deref::<B>().0),
+                )
+            })
+            .collect::<Vec<_>>();
+        results.sort();
+        assert_eq!(results, vec![(e1, 1, Some(10)), (e2, 2, None)]);
+
+        let mut query = DynamicQuery::builder()
+            .with(b)
+            .read(a)
+            .build(&world)
+            .unwrap();
+        let item = query.get(&world, e1).unwrap();
+        assert_eq!(item.components().len(), 1);
+        assert!(matches!(
+            query.get(&world, e2),
+            Err(QueryEntityError::QueryDoesNotMatch)
+        ));
+    }
+
+    #[test]
+    fn dynamic_query_write() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), B(2))).id();
+        let e2 = world.spawn().insert(B(3)).id();
+        let a = world.init_component::<A>();
+        let b = world.init_component::<B>();
+
+        let mut query = DynamicQuery::builder()
+            .write(b)
+            .optional_read(a)
+            .build(&world)
+            .unwrap();
+        for mut item in query.iter(&world) {
+            assert!(item.components_mut()[0]
+                .as_mut()
+                .unwrap()
+                .as_mut_ptr()
+                .is_none());
+        }
+        for mut item in query.iter_mut(&mut world) {
+            let components = item.components_mut();
+            let add = components[1]
+                .as_ref()
+                .map(|a| unsafe { a.deref::<A>().0 })
+                .unwrap_or(100);
+            let b = components[0].as_mut().unwrap();
+            assert!(b.is_writable());
+            unsafe { b.deref_mut::<B>().unwrap().0 += add };
+        }
+        assert_eq!(world.get::<B>(e1), Some(&B(3)));
+        assert_eq!(world.get::<B>(e2), Some(&B(103)));
+    }
+
+    #[test]
+    fn dynamic_query_errors() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        let mut other = World::new();
+        other.init_component::<A>();
+        let b = other.init_component::<B>();
+
+        assert_eq!(
+            DynamicQuery::builder().read(a).write(a).build(&world).err(),
+            Some(DynamicQueryError::ConflictingAccess(a))
+        );
+        assert_eq!(
+            DynamicQuery::builder()
+                .optional_write(a)
+                .read(a)
+                .build(&world)
+                .err(),
+            Some(DynamicQueryError::ConflictingAccess(a))
+        );
+        assert_eq!(
+            DynamicQuery::builder().read(b).build(&world).err(),
+            Some(DynamicQueryError::UnknownComponent(b))
+        );
+        assert!(DynamicQuery::builder()
+            .read(a)
+            .read(a)
+            .with(a)
+            .build(&world)
+            .is_ok());
+    }
+
+    #[test]
+    fn dynamic_query_change_detection() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        world.spawn().insert(A(0));
+        world.insert_resource(Vec::<usize>::new());
+
+        let query = DynamicQuery::builder().write(a).build(&world).unwrap();
+        let system = query.into_system(|iter| {
+            for mut item in iter {
+                let a = item.components_mut()[0].as_mut().unwrap();
+                if a.is_changed() {
+                    unsafe { a.deref_mut::<A>().unwrap().0 += 1 };
+                }
+            }
+        });
+        let mut stage = SystemStage::parallel().with_system(system);
+        stage.run(&mut world);
+        stage.run(&mut world);
+        world.spawn().insert(A(10));
+        stage.run(&mut world);
+
+        let mut values = world
+            .query::<&A>()
+            .iter(&world)
+            .map(|a| a.0)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, vec![1, 11]);
+    }
+
+    #[test]
+    fn dynamic_query_system_access() {
+        use crate::system::{IntoSystem, Query, System};
+
+        fn read_a(_query: Query<&A>) {}
+        fn write_b(_query: Query<&mut B>) {}
+
+        let mut world = World::new();
+        world.spawn().insert_bundle((A(0), B(0)));
+        let a = world.init_component::<A>();
+
+        let mut dynamic = DynamicQuery::builder()
+            .write(a)
+            .build(&world)
+            .unwrap()
+            .into_system(|_| {});
+        dynamic.initialize(&mut world);
+        let mut read_a = IntoSystem::into_system(read_a);
+        read_a.initialize(&mut world);
+        let mut write_b = IntoSystem::into_system(write_b);
+        write_b.initialize(&mut world);
+        for archetype in world.archetypes().iter() {
+            dynamic.new_archetype(archetype);
+            read_a.new_archetype(archetype);
+            write_b.new_archetype(archetype);
+        }
+
+        assert!(!dynamic
+            .archetype_component_access()
+            .is_compatible(read_a.archetype_component_access()));
+        assert!(dynamic
+            .archetype_component_access()
+            .is_compatible(write_b.archetype_component_access()));
+    }
+}
@@ -397,6 +397,12 @@ pub unsafe trait FetchState: Send + Sync + Sized {
     );
     fn matches_archetype(&self, archetype: &Archetype) -> bool;
     fn matches_table(&self, table: &Table) -> bool;
+
+    /// Sets the target of the [`RelatedTo`](crate::query::RelatedTo) filters in this state whose
+    /// [`Relation`](crate::relation::Relation) component is `relation_id`. States that don't
+    /// contain such a filter ignore it.
+    #[inline]
+    fn set_relation_target(&self, _relation_id: ComponentId, _target: Entity) {}
 }
 
 /// A fetch that is read only.
@@ -985,6 +991,10 @@ unsafe impl<T: FetchState> FetchState for OptionState<T> {
     fn matches_table(&self, _table: &Table) -> bool {
         true
     }
+
+    fn set_relation_target(&self, relation_id: ComponentId, target: Entity) {
+        self.state.set_relation_target(relation_id, target);
+    }
 }
 
 impl<'w, 's, T: Fetch<'w, 's>> Fetch<'w, 's> for OptionFetch<T> {
@@ -1359,6 +1369,11 @@ macro_rules! impl_tuple_fetch {
                 let ($($name,)*) = self;
                 true $(&& $name.matches_table(_table))*
             }
+
+            fn set_relation_target(&self, _relation_id: ComponentId, _target: Entity) {
+                let ($($name,)*) = self;
+                $($name.set_relation_target(_relation_id, _target);)*
+            }
         }
 
         impl<$($name: WorldQuery),*> WorldQuery for ($($name,)*) {
@@ -1470,6 +1485,11 @@ macro_rules! impl_anytuple_fetch {
                 let ($($name,)*) = &self.0;
                 false $(|| $name.matches_table(_table))*
             }
+
+            fn set_relation_target(&self, _relation_id: ComponentId, _target: Entity) {
+                let ($($name,)*) = &self.0;
+                $($name.set_relation_target(_relation_id, _target);)*
+            }
         }
 
         impl<$($name: WorldQuery),*> WorldQuery for AnyOf<($($name,)*)> {
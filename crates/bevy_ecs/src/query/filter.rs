@@ -1,13 +1,16 @@
 use crate::{
     archetype::{Archetype, ArchetypeComponentId},
-    component::{Component, ComponentId, ComponentStorage, ComponentTicks, StorageType},
+    component::{Component, ComponentId, ComponentStorage, ComponentTicks, Disabled, StorageType},
     entity::Entity,
-    query::{Access, Fetch, FetchState, FilteredAccess, ReadOnlyFetch, WorldQuery},
+    query::{
+        Access, Fetch, FetchState, FilteredAccess, ReadFetch, ReadOnlyFetch, ReadState, WorldQuery,
+    },
+    relation::Relation,
     storage::{ComponentSparseSet, Table, Tables},
     world::World,
 };
 use bevy_ecs_macros::all_tuples;
-use std::{cell::UnsafeCell, marker::PhantomData, ptr};
+use std::{cell::UnsafeCell, marker::PhantomData, ptr, sync::Mutex};
 
 /// Extension trait for [`Fetch`] containing methods used by query filters.
 /// This trait exists to allow "short circuit" behaviors for relevant query filter fetches.
@@ -292,6 +295,263 @@ impl<'w, 's, T: Component> Fetch<'w, 's> for WithoutFetch<T> {
 // SAFETY: no component access or archetype component access
 unsafe impl<T> ReadOnlyFetch for WithoutFetch<T> {}
 
+/// Filter that lets a query match [`Disabled`] entities as well as enabled ones.
+///
+/// Queries skip disabled entities by default, unless they refer to the [`Disabled`] component
+/// themselves. This filter does not change which enabled entities match.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs::component::Component;
+/// # use bevy_ecs::query::AllowDisabled;
+/// # use bevy_ecs::system::IntoSystem;
+/// # use bevy_ecs::system::Query;
+/// #
+/// # #[derive(Component)]
+/// # struct Name { name: &'static str };
+/// #
+/// fn all_names_system(query: Query<&Name, AllowDisabled>) {
+///     for name in query.iter() {
+///         println!("{} may be disabled", name.name);
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(all_names_system);
+/// ```
+pub struct AllowDisabled;
+
+impl WorldQuery for AllowDisabled {
+    type Fetch = AllowDisabledFetch;
+    type State = AllowDisabledState;
+    type ReadOnlyFetch = AllowDisabledFetch;
+}
+
+/// The [`Fetch`] of [`AllowDisabled`].
+pub struct AllowDisabledFetch;
+
+/// The [`FetchState`] of [`AllowDisabled`].
+pub struct AllowDisabledState {
+    component_id: ComponentId,
+}
+
+// SAFETY: `update_component_access` and `update_archetype_component_access` add read access for
+// the `Disabled` component, which is never fetched
+unsafe impl FetchState for AllowDisabledState {
+    fn init(world: &mut World) -> Self {
+        Self {
+            component_id: world.init_component::<Disabled>(),
+        }
+    }
+
+    #[inline]
+    fn update_component_access(&self, access: &mut FilteredAccess<ComponentId>) {
+        // only the read is added, as the query also matches entities without `Disabled`
+        access.access_mut().add_read(self.component_id);
+    }
+
+    #[inline]
+    fn update_archetype_component_access(
+        &self,
+        archetype: &Archetype,
+        access: &mut Access<ArchetypeComponentId>,
+    ) {
+        if let Some(archetype_component_id) =
+            archetype.get_archetype_component_id(self.component_id)
+        {
+            access.add_read(archetype_component_id);
+        }
+    }
+
+    fn matches_archetype(&self, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    fn matches_table(&self, _table: &Table) -> bool {
+        true
+    }
+}
+
+impl<'w, 's> Fetch<'w, 's> for AllowDisabledFetch {
+    type Item = bool;
+    type State = AllowDisabledState;
+
+    unsafe fn init(
+        _world: &World,
+        _state: &Self::State,
+        _last_change_tick: u32,
+        _change_tick: u32,
+    ) -> Self {
+        Self
+    }
+
+    const IS_DENSE: bool = true;
+
+    #[inline]
+    unsafe fn set_table(&mut self, _state: &Self::State, _table: &Table) {}
+
+    #[inline]
+    unsafe fn set_archetype(
+        &mut self,
+        _state: &Self::State,
+        _archetype: &Archetype,
+        _tables: &Tables,
+    ) {
+    }
+
+    #[inline]
+    unsafe fn archetype_fetch(&mut self, _archetype_index: usize) -> Self::Item {
+        true
+    }
+
+    #[inline]
+    unsafe fn table_fetch(&mut self, _table_row: usize) -> bool {
+        true
+    }
+}
+
+// SAFETY: only reads access
+unsafe impl ReadOnlyFetch for AllowDisabledFetch {}
+
+/// Filter that selects entities with a [`Relation<R>`] to a target entity.
+///
+/// The target is set with [`Query::set_relation_target`](crate::system::Query::set_relation_target)
+/// or [`QueryState::set_relation_target`](crate::query::QueryState::set_relation_target), and is
+/// kept until it is set again. Until a target is set, no entity matches.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs::component::Component;
+/// # use bevy_ecs::entity::Entity;
+/// # use bevy_ecs::query::RelatedTo;
+/// # use bevy_ecs::system::IntoSystem;
+/// # use bevy_ecs::system::Query;
+/// #
+/// # struct Owns;
+/// # #[derive(Component)]
+/// # struct Name { name: &'static str };
+/// #
+/// fn owners_system(mut query: Query<&Name, RelatedTo<Owns>>, items: Query<(Entity, &Name)>) {
+///     for (item, item_name) in items.iter() {
+///         query.set_relation_target::<Owns>(item);
+///         for name in query.iter() {
+///             println!("{} owns {}", name.name, item_name.name);
+///         }
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(owners_system);
+/// ```
+pub struct RelatedTo<R>(PhantomData<R>);
+
+impl<R: Send + Sync + 'static> WorldQuery for RelatedTo<R> {
+    type Fetch = RelatedToFetch<R>;
+    type State = RelatedToState<R>;
+    type ReadOnlyFetch = RelatedToFetch<R>;
+}
+
+/// The [`Fetch`] of [`RelatedTo`].
+pub struct RelatedToFetch<R: Send + Sync + 'static> {
+    fetch: ReadFetch<Relation<R>>,
+    target: Option<Entity>,
+}
+
+/// The [`FetchState`] of [`RelatedTo`].
+pub struct RelatedToState<R: Send + Sync + 'static> {
+    relation_id: ComponentId,
+    state: ReadState<Relation<R>>,
+    target: Mutex<Option<Entity>>,
+}
+
+// SAFETY: component access and archetype component access are those of `&Relation<R>`, which is
+// the only component read
+unsafe impl<R: Send + Sync + 'static> FetchState for RelatedToState<R> {
+    fn init(world: &mut World) -> Self {
+        Self {
+            relation_id: world.init_relation::<R>(),
+            state: ReadState::init(world),
+            target: Mutex::new(None),
+        }
+    }
+
+    #[inline]
+    fn update_component_access(&self, access: &mut FilteredAccess<ComponentId>) {
+        self.state.update_component_access(access);
+    }
+
+    #[inline]
+    fn update_archetype_component_access(
+        &self,
+        archetype: &Archetype,
+        access: &mut Access<ArchetypeComponentId>,
+    ) {
+        self.state
+            .update_archetype_component_access(archetype, access);
+    }
+
+    fn matches_archetype(&self, archetype: &Archetype) -> bool {
+        self.state.matches_archetype(archetype)
+    }
+
+    fn matches_table(&self, table: &Table) -> bool {
+        self.state.matches_table(table)
+    }
+
+    fn set_relation_target(&self, relation_id: ComponentId, target: Entity) {
+        if relation_id == self.relation_id {
+            *self.target.lock().unwrap() = Some(target);
+        }
+    }
+}
+
+impl<'w, 's, R: Send + Sync + 'static> Fetch<'w, 's> for RelatedToFetch<R> {
+    type Item = bool;
+    type State = RelatedToState<R>;
+
+    unsafe fn init(
+        world: &World,
+        state: &Self::State,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        Self {
+            fetch: ReadFetch::init(world, &state.state, last_change_tick, change_tick),
+            target: *state.target.lock().unwrap(),
+        }
+    }
+
+    const IS_DENSE: bool = false;
+
+    #[inline]
+    unsafe fn set_table(&mut self, _state: &Self::State, _table: &Table) {}
+
+    #[inline]
+    unsafe fn set_archetype(
+        &mut self,
+        state: &Self::State,
+        archetype: &Archetype,
+        tables: &Tables,
+    ) {
+        self.fetch.set_archetype(&state.state, archetype, tables);
+    }
+
+    #[inline]
+    unsafe fn archetype_fetch(&mut self, archetype_index: usize) -> Self::Item {
+        match self.target {
+            Some(target) => self.fetch.archetype_fetch(archetype_index).contains(target),
+            None => false,
+        }
+    }
+
+    #[inline]
+    unsafe fn table_fetch(&mut self, _table_row: usize) -> bool {
+        // `Relation<R>` is stored in a sparse set, so the query always iterates archetypes
+        unreachable!()
+    }
+}
+
+// SAFETY: only reads `Relation<R>`
+unsafe impl<R: Send + Sync + 'static> ReadOnlyFetch for RelatedToFetch<R> {}
+
 /// A filter that tests if any of the given filters apply.
 ///
 /// This is useful for example if a system with multiple components in a query only wants to run
@@ -439,6 +699,11 @@ macro_rules! impl_query_filter_tuple {
                 let ($($filter,)*) = &self.0;
                 false $(|| $filter.matches_table(table))*
             }
+
+            fn set_relation_target(&self, relation_id: ComponentId, target: Entity) {
+                let ($($filter,)*) = &self.0;
+                $($filter.set_relation_target(relation_id, target);)*
+            }
         }
     };
 }
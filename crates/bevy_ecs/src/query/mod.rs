@@ -1,19 +1,27 @@
 mod access;
+mod dynamic;
 mod fetch;
 mod filter;
 mod iter;
+mod par_iter;
 mod state;
 
 pub use access::*;
+pub use dynamic::*;
 pub use fetch::*;
 pub use filter::*;
 pub use iter::*;
+pub use par_iter::*;
 pub use state::*;
 
 #[cfg(test)]
 mod tests {
-    use super::AnyOf;
-    use crate::{self as bevy_ecs, component::Component, world::World};
+    use super::{AllowDisabled, AnyOf, DynamicQuery, QueryEntityError, With, Without};
+    use crate::{
+        self as bevy_ecs,
+        component::{Component, Disabled},
+        world::World,
+    };
 
     #[derive(Component, Debug, Eq, PartialEq)]
     struct A(usize);
@@ -167,6 +175,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_many() {
+        let mut world = World::new();
+        let a = world.spawn().insert_bundle((A(1), Sparse(1))).id();
+        let b = world.spawn().insert(A(2)).id();
+        let c = world.spawn().insert(B(3)).id();
+
+        let mut query = world.query::<&A>();
+        assert_eq!(
+            query.get_many(&world, [a, b, a]).unwrap(),
+            [&A(1), &A(2), &A(1)]
+        );
+        assert!(matches!(
+            query.get_many(&world, [a, c]),
+            Err(QueryEntityError::QueryDoesNotMatch)
+        ));
+
+        let mut query = world.query::<&mut A>();
+        {
+            let [mut x, mut y] = query.get_many_mut(&mut world, [a, b]).unwrap();
+            std::mem::swap(&mut x.0, &mut y.0);
+        }
+        assert!(matches!(
+            query.get_many_mut(&mut world, [a, b, a]),
+            Err(QueryEntityError::AliasedMutability(entity)) if entity == a
+        ));
+        world.despawn(b);
+        assert!(matches!(
+            query.get_many_mut(&mut world, [a, b]),
+            Err(QueryEntityError::NoSuchEntity)
+        ));
+        assert_eq!(world.get::<A>(a), Some(&A(2)));
+    }
+
     #[test]
     fn multi_storage_query() {
         let mut world = World::new();
@@ -188,6 +230,42 @@ mod tests {
         assert_eq!(values, vec![&B(3)]);
     }
 
+    #[test]
+    fn derived_world_query() {
+        use crate::{entity::Entity, query::WorldQuery};
+
+        #[derive(WorldQuery)]
+        #[world_query(mutable, derive(Debug))]
+        struct AQuery<'w> {
+            entity: Entity,
+            a: &'w mut A,
+            b: Option<&'w B>,
+        }
+
+        #[derive(WorldQuery)]
+        #[world_query(filter)]
+        struct AFilter {
+            _a: With<A>,
+            _c: Without<C>,
+        }
+
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), B(10))).id();
+        let e2 = world.spawn().insert(A(2)).id();
+        world.spawn().insert_bundle((A(3), C(3)));
+
+        let mut query = world.query_filtered::<AQuery, AFilter>();
+        for mut item in query.iter_mut(&mut world) {
+            item.a.0 += item.b.map_or(0, |b| b.0);
+        }
+        let mut values = query
+            .iter(&world)
+            .map(|item| (item.entity, item.a.0, item.b.is_some()))
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(_, a, _)| *a);
+        assert_eq!(values, vec![(e2, 2, false), (e1, 11, true)]);
+    }
+
     #[test]
     fn any_query() {
         let mut world = World::new();
@@ -204,4 +282,65 @@ mod tests {
             vec![(Some(&A(1)), Some(&B(2))), (Some(&A(2)), None),]
         );
     }
+
+    #[test]
+    fn disabled_entities() {
+        let mut world = World::new();
+        let enabled = world.spawn().insert(A(1)).id();
+        let disabled = world.spawn().insert_bundle((A(2), Disabled)).id();
+        let disabled_sparse = world
+            .spawn()
+            .insert_bundle((A(3), Sparse(3), Disabled))
+            .id();
+
+        let mut query = world.query::<&A>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![&A(1)]);
+        assert!(query.get(&world, enabled).is_ok());
+        assert!(query.get(&world, disabled).is_err());
+        assert_eq!(world.query::<&Sparse>().iter(&world).count(), 0);
+
+        let mut values = world
+            .query_filtered::<&A, AllowDisabled>()
+            .iter(&world)
+            .map(|a| a.0)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, vec![1, 2, 3]);
+        assert_eq!(
+            world
+                .query_filtered::<&A, With<Disabled>>()
+                .iter(&world)
+                .count(),
+            2
+        );
+        assert_eq!(
+            world
+                .query_filtered::<&A, Without<Disabled>>()
+                .iter(&world)
+                .collect::<Vec<_>>(),
+            vec![&A(1)]
+        );
+
+        world.entity_mut(disabled_sparse).remove::<Disabled>();
+        assert_eq!(query.iter(&world).count(), 2);
+        assert_eq!(world.query::<&Sparse>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn disabled_entities_dynamic_query() {
+        let mut world = World::new();
+        world.spawn().insert(A(1));
+        world.spawn().insert_bundle((A(2), Disabled));
+        let a = world.init_component::<A>();
+        let disabled = world.init_component::<Disabled>();
+
+        let mut query = DynamicQuery::builder().read(a).build(&world).unwrap();
+        assert_eq!(query.iter(&world).count(), 1);
+        let mut query = DynamicQuery::builder()
+            .read(a)
+            .optional_read(disabled)
+            .build(&world)
+            .unwrap();
+        assert_eq!(query.iter(&world).count(), 2);
+    }
 }
@@ -0,0 +1,222 @@
+use crate::{
+    query::{Fetch, FilterFetch, QueryState, WorldQuery},
+    world::World,
+};
+use bevy_tasks::ParallelIterator;
+use std::{marker::PhantomData, ops::Range};
+
+/// The number of batches each thread gets on average when the batch size of a [`QueryParIter`]
+/// is picked automatically. More batches than threads even out the load when the items take
+/// different amounts of time to process.
+const BATCHES_PER_THREAD: usize = 4;
+
+/// A [`ParallelIterator`] over query results of a [`Query`](crate::system::Query).
+///
+/// This struct is created by the [`Query::par_iter`](crate::system::Query::par_iter) and
+/// [`Query::par_iter_mut`](crate::system::Query::par_iter_mut) methods.
+///
+/// The query results are split into [`QueryBatch`]es that each cover a range of a single table,
+/// or of a single archetype if the query fetches sparse set components. Like with
+/// [`QueryIter`](crate::query::QueryIter), change detection filters such as
+/// [`Changed`](crate::query::Changed) use the change ticks of the system running the query.
+pub struct QueryParIter<'w, 's, Q: WorldQuery, QF: Fetch<'w, 's, State = Q::State>, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    world: &'w World,
+    query_state: &'s QueryState<Q, F>,
+    last_change_tick: u32,
+    change_tick: u32,
+    batch_size: Option<usize>,
+    /// The index of the current table or archetype in the matched ids of `query_state`.
+    current_id: usize,
+    current_offset: usize,
+    marker: PhantomData<fn() -> QF>,
+}
+
+impl<'w, 's, Q: WorldQuery, QF, F: WorldQuery> QueryParIter<'w, 's, Q, QF, F>
+where
+    F::Fetch: FilterFetch,
+    QF: Fetch<'w, 's, State = Q::State>,
+{
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `query_state.world_id`. Calling this on a `world`
+    /// with a mismatched [`WorldId`](crate::world::WorldId) is unsound.
+    pub(crate) unsafe fn new(
+        world: &'w World,
+        query_state: &'s QueryState<Q, F>,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        QueryParIter {
+            world,
+            query_state,
+            last_change_tick,
+            change_tick,
+            batch_size: None,
+            current_id: 0,
+            current_offset: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets the maximum number of query results in each [`QueryBatch`].
+    ///
+    /// By default, the batch size is picked from the number of entities in the matched tables or
+    /// archetypes, so that each thread gets a few batches. A batch never spans several tables or
+    /// archetypes, so batches can be smaller than `batch_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is 0.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "The batch size must be at least 1.");
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    #[inline]
+    fn is_dense() -> bool {
+        QF::IS_DENSE && <F::Fetch as Fetch>::IS_DENSE
+    }
+
+    /// Returns the number of entities in the matched tables or archetypes, including the ones
+    /// that are filtered out.
+    fn matched_len(&self) -> usize {
+        if Self::is_dense() {
+            let tables = &self.world.storages().tables;
+            self.query_state
+                .matched_table_ids
+                .iter()
+                .map(|id| tables[*id].len())
+                .sum()
+        } else {
+            let archetypes = &self.world.archetypes;
+            self.query_state
+                .matched_archetype_ids
+                .iter()
+                .map(|id| archetypes[*id].len())
+                .sum()
+        }
+    }
+}
+
+impl<'w, 's, Q: WorldQuery, QF, F: WorldQuery> ParallelIterator<QueryBatch<'w, 's, Q, QF, F>>
+    for QueryParIter<'w, 's, Q, QF, F>
+where
+    F::Fetch: FilterFetch,
+    QF: Fetch<'w, 's, State = Q::State>,
+{
+    // NOTE: If you are changing query iteration code, remember to update the following places, where relevant:
+    // QueryIter, QueryIterationCursor, QueryState::for_each_unchecked_manual, QueryState::par_for_each_unchecked_manual
+    fn next_batch(&mut self) -> Option<QueryBatch<'w, 's, Q, QF, F>> {
+        let batch_size = match self.batch_size {
+            Some(batch_size) => batch_size,
+            None => {
+                let threads = bevy_tasks::logical_core_count().max(1) * BATCHES_PER_THREAD;
+                let batch_size = (self.matched_len() / threads).max(1);
+                *self.batch_size.insert(batch_size)
+            }
+        };
+        let world = self.world;
+        let query_state = self.query_state;
+        // SAFE: the batches cover disjoint ranges of entities, and the caller of `new` made sure
+        // that the query may access the world
+        unsafe {
+            let mut fetch = QF::init(
+                world,
+                &query_state.fetch_state,
+                self.last_change_tick,
+                self.change_tick,
+            );
+            let mut filter = <F::Fetch as Fetch>::init(
+                world,
+                &query_state.filter_state,
+                self.last_change_tick,
+                self.change_tick,
+            );
+            let tables = &world.storages().tables;
+            let len = loop {
+                if Self::is_dense() {
+                    let table = &tables[*query_state.matched_table_ids.get(self.current_id)?];
+                    if self.current_offset < table.len() {
+                        fetch.set_table(&query_state.fetch_state, table);
+                        filter.set_table(&query_state.filter_state, table);
+                        break table.len();
+                    }
+                } else {
+                    let archetype = &world.archetypes
+                        [*query_state.matched_archetype_ids.get(self.current_id)?];
+                    if self.current_offset < archetype.len() {
+                        fetch.set_archetype(&query_state.fetch_state, archetype, tables);
+                        filter.set_archetype(&query_state.filter_state, archetype, tables);
+                        break archetype.len();
+                    }
+                }
+                self.current_id += 1;
+                self.current_offset = 0;
+            };
+            let start = self.current_offset;
+            self.current_offset = len.min(start + batch_size);
+            Some(QueryBatch {
+                fetch,
+                filter,
+                dense: Self::is_dense(),
+                indices: start..self.current_offset,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+/// A batch of query results of a [`QueryParIter`], processed by a single task.
+pub struct QueryBatch<'w, 's, Q: WorldQuery, QF: Fetch<'w, 's, State = Q::State>, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    fetch: QF,
+    filter: F::Fetch,
+    dense: bool,
+    indices: Range<usize>,
+    marker: PhantomData<(&'w (), &'s QueryState<Q, F>)>,
+}
+
+// SAFE: the fetches of a batch only access the entities in its own range, in a table or archetype
+// that is not modified while the query is borrowed.
+unsafe impl<'w, 's, Q: WorldQuery, QF, F: WorldQuery> Send for QueryBatch<'w, 's, Q, QF, F>
+where
+    F::Fetch: FilterFetch,
+    QF: Fetch<'w, 's, State = Q::State>,
+{
+}
+
+impl<'w, 's, Q: WorldQuery, QF, F: WorldQuery> Iterator for QueryBatch<'w, 's, Q, QF, F>
+where
+    F::Fetch: FilterFetch,
+    QF: Fetch<'w, 's, State = Q::State>,
+{
+    type Item = QF::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFE: the fetches were set to the table or archetype the indices are in
+        unsafe {
+            for index in &mut self.indices {
+                if self.dense {
+                    if self.filter.table_filter_fetch(index) {
+                        return Some(self.fetch.table_fetch(index));
+                    }
+                } else if self.filter.archetype_filter_fetch(index) {
+                    return Some(self.fetch.archetype_fetch(index));
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.indices.len()))
+    }
+}
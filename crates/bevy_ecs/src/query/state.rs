@@ -1,16 +1,18 @@
 use crate::{
     archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
-    component::ComponentId,
+    component::{ComponentId, Disabled},
     entity::Entity,
     query::{
         Access, Fetch, FetchState, FilterFetch, FilteredAccess, NopFetch, QueryCombinationIter,
-        QueryIter, WorldQuery,
+        QueryIter, QueryParIter, WorldQuery,
     },
+    relation::Relation,
     storage::TableId,
     world::{World, WorldId},
 };
 use bevy_tasks::TaskPool;
 use fixedbitset::FixedBitSet;
+use std::any::TypeId;
 use thiserror::Error;
 
 /// Provides scoped access to a [`World`] state according to a given [`WorldQuery`] and query filter.
@@ -30,6 +32,9 @@ where
     pub(crate) matched_archetype_ids: Vec<ArchetypeId>,
     pub(crate) fetch_state: Q::State,
     pub(crate) filter_state: F::State,
+    /// Whether the query matches [`Disabled`] entities, which it does if it refers to the
+    /// [`Disabled`] component.
+    allows_disabled: bool,
 }
 
 impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F>
@@ -53,6 +58,7 @@ where
         // Merge the temporary filter access with the main access. This ensures that filter access is
         // properly considered in a global "cross-query" context (both within systems and across systems).
         component_access.extend(&filter_component_access);
+        let allows_disabled = component_access.contains(world.init_component::<Disabled>());
 
         let mut state = Self {
             world_id: world.id(),
@@ -65,6 +71,7 @@ where
             matched_tables: Default::default(),
             matched_archetypes: Default::default(),
             archetype_component_access: Default::default(),
+            allows_disabled,
         };
         state.update_archetypes(world);
         state
@@ -108,9 +115,32 @@ where
         );
     }
 
+    /// Sets the target of the [`RelatedTo<R>`](crate::query::RelatedTo) filters of this query.
+    /// It is used by the iterations that start after this call.
+    #[inline]
+    pub fn set_relation_target<R: Send + Sync + 'static>(&mut self, world: &World, target: Entity) {
+        self.validate_world(world);
+        self.set_relation_target_manual::<R>(world, target);
+    }
+
+    /// Sets the target of the [`RelatedTo<R>`](crate::query::RelatedTo) filters of this query,
+    /// without validating `world`.
+    pub(crate) fn set_relation_target_manual<R: Send + Sync + 'static>(
+        &self,
+        world: &World,
+        target: Entity,
+    ) {
+        // if `Relation<R>` isn't registered, this query has no `RelatedTo<R>` filter
+        if let Some(relation_id) = world.components().get_id(TypeId::of::<Relation<R>>()) {
+            self.fetch_state.set_relation_target(relation_id, target);
+            self.filter_state.set_relation_target(relation_id, target);
+        }
+    }
+
     /// Creates a new [`Archetype`].
     pub fn new_archetype(&mut self, archetype: &Archetype) {
-        if self.fetch_state.matches_archetype(archetype)
+        if (self.allows_disabled || !archetype.is_disabled())
+            && self.fetch_state.matches_archetype(archetype)
             && self.filter_state.matches_archetype(archetype)
         {
             self.fetch_state
@@ -172,6 +202,51 @@ where
         }
     }
 
+    /// Gets the read-only query results for the given [`World`] and array of [`Entity`]s.
+    ///
+    /// An error is returned if any of the entities doesn't match the query. The same entity can
+    /// be requested more than once.
+    #[inline]
+    pub fn get_many<'w, 's, const N: usize>(
+        &'s mut self,
+        world: &'w World,
+        entities: [Entity; N],
+    ) -> Result<[<Q::ReadOnlyFetch as Fetch<'w, 's>>::Item; N], QueryEntityError> {
+        self.update_archetypes(world);
+        // SAFETY: query is read only
+        unsafe {
+            self.get_many_unchecked_manual::<Q::ReadOnlyFetch, N>(
+                world,
+                entities,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Gets the query results for the given [`World`] and array of [`Entity`]s.
+    ///
+    /// An error is returned if any of the entities doesn't match the query, or if the same entity
+    /// is requested more than once.
+    #[inline]
+    pub fn get_many_mut<'w, 's, const N: usize>(
+        &'s mut self,
+        world: &'w mut World,
+        entities: [Entity; N],
+    ) -> Result<[<Q::Fetch as Fetch<'w, 's>>::Item; N], QueryEntityError> {
+        self.update_archetypes(world);
+        verify_entities_unique(&entities)?;
+        // SAFETY: query has unique world access, and the entities are unique
+        unsafe {
+            self.get_many_unchecked_manual::<Q::Fetch, N>(
+                world,
+                entities,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
     #[inline]
     pub fn get_manual<'w, 's>(
         &'s self,
@@ -249,6 +324,37 @@ where
         }
     }
 
+    /// Gets the query results for the given [`World`] and array of [`Entity`]s, where the last
+    /// change and the current change tick are given.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query, and that `entities` are unique if the
+    /// query is mutable.
+    pub(crate) unsafe fn get_many_unchecked_manual<
+        'w,
+        's,
+        QF: Fetch<'w, 's, State = Q::State>,
+        const N: usize,
+    >(
+        &'s self,
+        world: &'w World,
+        entities: [Entity; N],
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Result<[QF::Item; N], QueryEntityError> {
+        // check every entity before fetching any item, so that the items can be collected into an
+        // array without unwinding
+        for entity in entities {
+            self.get_unchecked_manual::<QF>(world, entity, last_change_tick, change_tick)?;
+        }
+        Ok(entities.map(|entity| {
+            self.get_unchecked_manual::<QF>(world, entity, last_change_tick, change_tick)
+                .unwrap()
+        }))
+    }
+
     /// Returns an [`Iterator`] over the query results for the given [`World`].
     ///
     /// This can only be called for read-only queries, see [`Self::iter_mut`] for write-queries.
@@ -545,6 +651,64 @@ where
         }
     }
 
+    /// Returns a [`ParallelIterator`](bevy_tasks::ParallelIterator) over the query results for
+    /// the given [`World`].
+    ///
+    /// This can only be called for read-only queries, see [`Self::par_iter_mut`] for
+    /// write-queries.
+    #[inline]
+    pub fn par_iter<'w, 's>(
+        &'s mut self,
+        world: &'w World,
+    ) -> QueryParIter<'w, 's, Q, Q::ReadOnlyFetch, F> {
+        // SAFETY: query is read only
+        unsafe {
+            self.update_archetypes(world);
+            self.par_iter_unchecked_manual(
+                world,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns a [`ParallelIterator`](bevy_tasks::ParallelIterator) over the query results for
+    /// the given [`World`].
+    #[inline]
+    pub fn par_iter_mut<'w, 's>(
+        &'s mut self,
+        world: &'w mut World,
+    ) -> QueryParIter<'w, 's, Q, Q::Fetch, F> {
+        // SAFETY: query has unique world access
+        unsafe {
+            self.update_archetypes(world);
+            self.par_iter_unchecked_manual(
+                world,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns a [`ParallelIterator`](bevy_tasks::ParallelIterator) over the query results for
+    /// the given [`World`], where the last change and the current change tick are given.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched [`WorldId`] is unsound.
+    #[inline]
+    pub(crate) unsafe fn par_iter_unchecked_manual<'w, 's, QF: Fetch<'w, 's, State = Q::State>>(
+        &'s self,
+        world: &'w World,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> QueryParIter<'w, 's, Q, QF, F> {
+        QueryParIter::new(world, self, last_change_tick, change_tick)
+    }
+
     /// Runs `func` on each query result in parallel using the given `task_pool`.
     ///
     /// This can only be called for read-only queries.
@@ -732,6 +896,17 @@ where
     }
 }
 
+/// Returns [`QueryEntityError::AliasedMutability`] if an entity appears more than once in
+/// `entities`.
+pub(crate) fn verify_entities_unique(entities: &[Entity]) -> Result<(), QueryEntityError> {
+    for (index, entity) in entities.iter().enumerate() {
+        if entities[..index].contains(entity) {
+            return Err(QueryEntityError::AliasedMutability(*entity));
+        }
+    }
+    Ok(())
+}
+
 /// An error that occurs when retrieving a specific [`Entity`]'s query result.
 #[derive(Error, Debug)]
 pub enum QueryEntityError {
@@ -739,4 +914,6 @@ pub enum QueryEntityError {
     QueryDoesNotMatch,
     #[error("The requested entity does not exist.")]
     NoSuchEntity,
+    #[error("The entity {0:?} was requested mutably more than once.")]
+    AliasedMutability(Entity),
 }
@@ -0,0 +1,1053 @@
+//! Types for recording the commands applied to a [`World`] and replaying them later, e.g. to
+//! reproduce a bug, and for checking that two runs of the same systems produce the same state.
+//!
+//! While a [`CommandRecorder`] resource is in the world, every command applied by a
+//! [`CommandQueue`](crate::system::CommandQueue) whose type has
+//! [`ReflectCommand`](crate::reflect::ReflectCommand) type data is recorded into a
+//! [`CommandLog`], along with the values of selected input resources at the start of each frame.
+//! The log can be serialized with a [`CommandLogSerializer`] and replayed against another world.
+//! A [`WorldStateHash`] hashes the reflected components of a world, so that the state of two runs
+//! can be compared after each frame.
+
+use crate::{
+    entity::Entity,
+    reflect::{ReflectCommand, ReflectComponent, ReflectResource},
+    system::Resource,
+    world::{FromWorld, Mut, World},
+};
+use bevy_reflect::{
+    serde::{ReflectDeserializer, ReflectSerializer},
+    Reflect, TypeRegistry, TypeRegistryArc,
+};
+use bevy_utils::HashSet;
+use serde::{
+    de::{DeserializeSeed, Error as _, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeSeq, SerializeStruct},
+    Deserialize, Serialize, Serializer,
+};
+use std::{
+    any::TypeId,
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+};
+use thiserror::Error;
+
+/// An error that occurs when replaying a [`RecordedFrame`].
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("command type {type_name} is not registered with ReflectCommand type data")]
+    UnregisteredCommand { type_name: String },
+    #[error("resource type {type_name} is not registered with ReflectResource type data")]
+    UnregisteredResource { type_name: String },
+}
+
+/// The inputs and the commands recorded during one frame.
+#[derive(Default)]
+pub struct RecordedFrame {
+    /// The values of the input resources at the start of the frame.
+    pub inputs: Vec<Box<dyn Reflect>>,
+    /// The reflectable commands applied during the frame, in the order they were applied.
+    pub commands: Vec<Box<dyn Reflect>>,
+}
+
+impl RecordedFrame {
+    /// Inserts the recorded input resources into `world`.
+    pub fn apply_inputs(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+    ) -> Result<(), ReplayError> {
+        for input in self.inputs.iter() {
+            let reflect_resource = type_registry
+                .get_with_name(input.type_name())
+                .and_then(|registration| registration.data::<ReflectResource>())
+                .ok_or_else(|| ReplayError::UnregisteredResource {
+                    type_name: input.type_name().to_string(),
+                })?;
+            reflect_resource.insert_resource(world, &**input);
+        }
+        Ok(())
+    }
+
+    /// Writes the recorded commands to `world`.
+    pub fn apply_commands(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+    ) -> Result<(), ReplayError> {
+        for command in self.commands.iter() {
+            let reflect_command = type_registry
+                .get_with_name(command.type_name())
+                .and_then(|registration| registration.data::<ReflectCommand>())
+                .ok_or_else(|| ReplayError::UnregisteredCommand {
+                    type_name: command.type_name().to_string(),
+                })?;
+            reflect_command.apply_command(world, &**command);
+        }
+        world.flush();
+        Ok(())
+    }
+
+    /// Inserts the recorded inputs into `world`, then writes the recorded commands to it.
+    pub fn replay(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+    ) -> Result<(), ReplayError> {
+        self.apply_inputs(world, type_registry)?;
+        self.apply_commands(world, type_registry)
+    }
+}
+
+/// The frames recorded by a [`CommandRecorder`].
+#[derive(Default)]
+pub struct CommandLog {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl CommandLog {
+    /// Replays every frame of the log against `world`, in order.
+    ///
+    /// The commands don't spawn entities with the ids they had when they were recorded, so
+    /// commands referring to entities only reproduce the recorded run if `world` spawns its
+    /// entities in the same order.
+    pub fn replay(
+        &self,
+        world: &mut World,
+        type_registry: &TypeRegistry,
+    ) -> Result<(), ReplayError> {
+        for frame in self.frames.iter() {
+            frame.replay(world, type_registry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the commands applied to the world it is inserted in, and the values of its input
+/// resources, into a [`CommandLog`].
+///
+/// A new frame is started by [`start_frame`](Self::start_frame) or by the exclusive system
+/// [`start_frame_system`](Self::start_frame_system). Commands whose type isn't registered with
+/// [`ReflectCommand`] type data can't be recorded; their type names are listed by
+/// [`unrecorded_commands`](Self::unrecorded_commands).
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{
+///     prelude::*, recording::CommandRecorder, reflect::ReflectCommand, system::Command,
+/// };
+/// use bevy_reflect::{Reflect, TypeRegistryArc};
+///
+/// struct Score(u32);
+///
+/// #[derive(Reflect, Default)]
+/// #[reflect(Command)]
+/// struct AddScore(u32);
+///
+/// impl Command for AddScore {
+///     fn write(self, world: &mut World) {
+///         world.get_resource_or_insert_with(|| Score(0)).0 += self.0;
+///     }
+/// }
+///
+/// let type_registry = TypeRegistryArc::default();
+/// type_registry.write().register::<AddScore>();
+///
+/// let mut world = World::new();
+/// world.insert_resource(type_registry.clone());
+/// world.init_resource::<CommandRecorder>();
+///
+/// let mut stage = SystemStage::single_threaded()
+///     .with_system(|mut commands: Commands| commands.add(AddScore(5)));
+/// CommandRecorder::start_frame_system(&mut world);
+/// stage.run(&mut world);
+///
+/// let log = world.resource_mut::<CommandRecorder>().take_log();
+/// let mut replay_world = World::new();
+/// log.replay(&mut replay_world, &type_registry.read()).unwrap();
+/// assert_eq!(replay_world.resource::<Score>().0, 5);
+/// ```
+pub struct CommandRecorder {
+    type_registry: TypeRegistryArc,
+    inputs: Vec<TypeId>,
+    log: CommandLog,
+    unrecorded_commands: HashSet<&'static str>,
+}
+
+impl FromWorld for CommandRecorder {
+    fn from_world(world: &mut World) -> Self {
+        CommandRecorder::new(
+            world
+                .get_resource_or_insert_with(TypeRegistryArc::default)
+                .clone(),
+        )
+    }
+}
+
+impl CommandRecorder {
+    pub fn new(type_registry: TypeRegistryArc) -> Self {
+        Self {
+            type_registry,
+            inputs: Vec::new(),
+            log: CommandLog::default(),
+            unrecorded_commands: HashSet::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_input<R: Resource>(mut self) -> Self {
+        self.add_input::<R>();
+        self
+    }
+
+    /// Records the value of the resource `R` at the start of each frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `R` isn't registered with [`ReflectResource`] type data.
+    pub fn add_input<R: Resource>(&mut self) -> &mut Self {
+        if self
+            .type_registry
+            .read()
+            .get_type_data::<ReflectResource>(TypeId::of::<R>())
+            .is_none()
+        {
+            panic!(
+                "The input {} must be registered with ReflectResource type data.",
+                std::any::type_name::<R>()
+            );
+        }
+        self.inputs.push(TypeId::of::<R>());
+        self
+    }
+
+    /// Starts a new frame, and records the current values of the input resources of `world`.
+    pub fn start_frame(&mut self, world: &World) {
+        let type_registry = self.type_registry.read();
+        let inputs = self
+            .inputs
+            .iter()
+            .filter_map(|type_id| {
+                type_registry
+                    .get_type_data::<ReflectResource>(*type_id)?
+                    .reflect_resource(world)
+            })
+            .map(|input| input.clone_value())
+            .collect();
+        self.log.frames.push(RecordedFrame {
+            inputs,
+            commands: Vec::new(),
+        });
+    }
+
+    /// Starts a new frame in the [`CommandRecorder`] of `world`.
+    pub fn start_frame_system(world: &mut World) {
+        world.resource_scope(|world, mut recorder: Mut<CommandRecorder>| {
+            recorder.start_frame(world);
+        });
+    }
+
+    pub fn log(&self) -> &CommandLog {
+        &self.log
+    }
+
+    /// Returns the recorded frames, and starts a new log.
+    pub fn take_log(&mut self) -> CommandLog {
+        std::mem::take(&mut self.log)
+    }
+
+    /// Returns the type names of the commands that were applied but not recorded, because they
+    /// aren't registered with [`ReflectCommand`] type data.
+    pub fn unrecorded_commands(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.unrecorded_commands.iter().copied()
+    }
+
+    /// Records the command pointed to by `command`.
+    ///
+    /// # Safety
+    /// `command` must point to a valid value of the type `type_id`.
+    pub(crate) unsafe fn record(
+        &mut self,
+        type_id: TypeId,
+        type_name: &'static str,
+        command: *const u8,
+    ) {
+        let type_registry = self.type_registry.read();
+        match type_registry.get_type_data::<ReflectCommand>(type_id) {
+            Some(reflect_command) => {
+                if self.log.frames.is_empty() {
+                    self.log.frames.push(RecordedFrame::default());
+                }
+                let frame = self.log.frames.last_mut().unwrap();
+                frame
+                    .commands
+                    .push(reflect_command.reflect_ptr(command).clone_value());
+            }
+            None => {
+                self.unrecorded_commands.insert(type_name);
+            }
+        }
+    }
+}
+
+/// Serializes a [`CommandLog`] as a list of frames.
+pub struct CommandLogSerializer<'a> {
+    pub log: &'a CommandLog,
+    pub registry: &'a TypeRegistry,
+}
+
+impl<'a> CommandLogSerializer<'a> {
+    pub fn new(log: &'a CommandLog, registry: &'a TypeRegistry) -> Self {
+        CommandLogSerializer { log, registry }
+    }
+}
+
+impl<'a> Serialize for CommandLogSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.log.frames.len()))?;
+        for frame in self.log.frames.iter() {
+            state.serialize_element(&FrameSerializer {
+                frame,
+                registry: self.registry,
+            })?;
+        }
+        state.end()
+    }
+}
+
+const FRAME_STRUCT: &str = "Frame";
+const FRAME_FIELD_INPUTS: &str = "inputs";
+const FRAME_FIELD_COMMANDS: &str = "commands";
+
+struct FrameSerializer<'a> {
+    frame: &'a RecordedFrame,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for FrameSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct(FRAME_STRUCT, 2)?;
+        state.serialize_field(
+            FRAME_FIELD_INPUTS,
+            &ValuesSerializer {
+                values: &self.frame.inputs,
+                registry: self.registry,
+            },
+        )?;
+        state.serialize_field(
+            FRAME_FIELD_COMMANDS,
+            &ValuesSerializer {
+                values: &self.frame.commands,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+struct ValuesSerializer<'a> {
+    values: &'a [Box<dyn Reflect>],
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for ValuesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.values.len()))?;
+        for value in self.values.iter() {
+            state.serialize_element(&ReflectSerializer::new(&**value, self.registry))?;
+        }
+        state.end()
+    }
+}
+
+/// Deserializes a [`CommandLog`] serialized by a [`CommandLogSerializer`].
+pub struct CommandLogDeserializer<'a> {
+    pub registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for CommandLogDeserializer<'a> {
+    type Value = CommandLog;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(CommandLog {
+            frames: deserializer.deserialize_seq(FrameSeqVisitor {
+                registry: self.registry,
+            })?,
+        })
+    }
+}
+
+struct FrameSeqVisitor<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for FrameSeqVisitor<'a> {
+    type Value = Vec<RecordedFrame>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("list of frames")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut frames = Vec::new();
+        while let Some(frame) = seq.next_element_seed(FrameDeserializer {
+            registry: self.registry,
+        })? {
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+}
+
+struct FrameDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for FrameDeserializer<'a> {
+    type Value = RecordedFrame;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            FRAME_STRUCT,
+            &[FRAME_FIELD_INPUTS, FRAME_FIELD_COMMANDS],
+            FrameVisitor {
+                registry: self.registry,
+            },
+        )
+    }
+}
+
+enum FrameField {
+    Inputs,
+    Commands,
+}
+
+impl<'de> Deserialize<'de> for FrameField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct FrameFieldVisitor;
+
+        impl<'de> Visitor<'de> for FrameFieldVisitor {
+            type Value = FrameField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("`inputs` or `commands`")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    FRAME_FIELD_INPUTS => Ok(FrameField::Inputs),
+                    FRAME_FIELD_COMMANDS => Ok(FrameField::Commands),
+                    _ => Err(E::unknown_field(
+                        value,
+                        &[FRAME_FIELD_INPUTS, FRAME_FIELD_COMMANDS],
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FrameFieldVisitor)
+    }
+}
+
+struct FrameVisitor<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for FrameVisitor<'a> {
+    type Value = RecordedFrame;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("frame")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut inputs = None;
+        let mut commands = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                FrameField::Inputs => {
+                    if inputs.is_some() {
+                        return Err(A::Error::duplicate_field(FRAME_FIELD_INPUTS));
+                    }
+                    inputs = Some(map.next_value_seed(ValuesDeserializer {
+                        registry: self.registry,
+                    })?);
+                }
+                FrameField::Commands => {
+                    if commands.is_some() {
+                        return Err(A::Error::duplicate_field(FRAME_FIELD_COMMANDS));
+                    }
+                    commands = Some(map.next_value_seed(ValuesDeserializer {
+                        registry: self.registry,
+                    })?);
+                }
+            }
+        }
+        Ok(RecordedFrame {
+            inputs: inputs.ok_or_else(|| A::Error::missing_field(FRAME_FIELD_INPUTS))?,
+            commands: commands.ok_or_else(|| A::Error::missing_field(FRAME_FIELD_COMMANDS))?,
+        })
+    }
+}
+
+struct ValuesDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for ValuesDeserializer<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for ValuesDeserializer<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("list of reflected values")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element_seed(ReflectDeserializer::new(self.registry))? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+/// A hash of the components of a [`World`], one per component type.
+///
+/// Only the components registered with [`ReflectComponent`] type data are hashed, from their
+/// [`ReflectSerializer`] output. The hash of a component type covers the entities that have it
+/// and their values; values that can't be serialized only contribute the part serialized before
+/// the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldStateHash {
+    components: Vec<(String, u64)>,
+}
+
+impl WorldStateHash {
+    pub fn new(world: &World, type_registry: &TypeRegistry) -> Self {
+        let mut components = Vec::new();
+        for registration in type_registry.iter() {
+            let reflect_component = match registration.data::<ReflectComponent>() {
+                Some(reflect_component) => reflect_component,
+                None => continue,
+            };
+            let component_id = match world.components().get_id(registration.type_id()) {
+                Some(component_id) => component_id,
+                None => continue,
+            };
+            let mut entities = world
+                .archetypes()
+                .iter()
+                .filter(|archetype| archetype.contains(component_id))
+                .flat_map(|archetype| archetype.entities().iter().copied())
+                .collect::<Vec<Entity>>();
+            if entities.is_empty() {
+                continue;
+            }
+            entities.sort_unstable();
+
+            let mut hasher = StateHasher::default();
+            for entity in entities {
+                entity.hash(&mut hasher.0);
+                if let Some(component) = reflect_component.reflect_component(world, entity) {
+                    // the error only means that the rest of the component isn't hashed
+                    let _ = ReflectSerializer::new(component, type_registry).serialize(&mut hasher);
+                }
+            }
+            components.push((registration.name().to_string(), hasher.0.finish()));
+        }
+        components.sort_unstable();
+        WorldStateHash { components }
+    }
+
+    /// Returns the hash of the component with the given type name, or `None` if no entity has it.
+    pub fn get(&self, component_name: &str) -> Option<u64> {
+        self.components
+            .iter()
+            .find(|(name, _)| name == component_name)
+            .map(|(_, hash)| *hash)
+    }
+
+    /// Returns the type names and hashes of the hashed components.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.components
+            .iter()
+            .map(|(name, hash)| (name.as_str(), *hash))
+    }
+
+    /// Returns the type names of the components whose hashes differ between `self` and `other`.
+    pub fn diff<'a>(&'a self, other: &'a WorldStateHash) -> Vec<&'a str> {
+        let mut names = self
+            .iter()
+            .filter(|(name, hash)| other.get(name) != Some(*hash))
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        names.extend(
+            other
+                .iter()
+                .filter(|(name, _)| self.get(name).is_none())
+                .map(|(name, _)| name),
+        );
+        names
+    }
+}
+
+/// A [`Serializer`] that hashes the serialized values.
+///
+/// The entries of maps are combined independently of their order, as the iteration order of a
+/// `HashMap` isn't the same in two runs.
+#[derive(Default)]
+struct StateHasher(DefaultHasher);
+
+#[derive(Debug)]
+struct HashError(String);
+
+impl fmt::Display for HashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for HashError {}
+
+impl ser::Error for HashError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        HashError(msg.to_string())
+    }
+}
+
+macro_rules! hash_value {
+    ($($method: ident: $ty: ty),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<(), HashError> {
+                value.hash(&mut self.0);
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<'a> Serializer for &'a mut StateHasher {
+    type Ok = ();
+    type Error = HashError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = MapHasher<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    hash_value!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8]
+    );
+
+    fn serialize_f32(self, value: f32) -> Result<(), HashError> {
+        value.to_bits().hash(&mut self.0);
+        Ok(())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), HashError> {
+        value.to_bits().hash(&mut self.0);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), HashError> {
+        0u8.hash(&mut self.0);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), HashError> {
+        1u8.hash(&mut self.0);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), HashError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), HashError> {
+        name.hash(&mut self.0);
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), HashError> {
+        variant.hash(&mut self.0);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), HashError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), HashError> {
+        variant.hash(&mut self.0);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, HashError> {
+        len.hash(&mut self.0);
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, HashError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, HashError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, HashError> {
+        variant.hash(&mut self.0);
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapHasher<'a>, HashError> {
+        Ok(MapHasher {
+            hasher: self,
+            entries: 0,
+            sum: 0,
+            entry: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, HashError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, HashError> {
+        variant.hash(&mut self.0);
+        Ok(self)
+    }
+}
+
+macro_rules! hash_elements {
+    ($($trait: ident::$method: ident),*) => {
+        $(
+            impl<'a> ser::$trait for &'a mut StateHasher {
+                type Ok = ();
+                type Error = HashError;
+
+                fn $method<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), HashError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+hash_elements!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+macro_rules! hash_fields {
+    ($($trait: ident),*) => {
+        $(
+            impl<'a> ser::$trait for &'a mut StateHasher {
+                type Ok = ();
+                type Error = HashError;
+
+                fn serialize_field<T: ?Sized + Serialize>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), HashError> {
+                    key.hash(&mut self.0);
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), HashError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+hash_fields!(SerializeStruct, SerializeStructVariant);
+
+struct MapHasher<'a> {
+    hasher: &'a mut StateHasher,
+    entries: usize,
+    sum: u64,
+    entry: Option<StateHasher>,
+}
+
+impl<'a> ser::SerializeMap for MapHasher<'a> {
+    type Ok = ();
+    type Error = HashError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), HashError> {
+        let mut entry = StateHasher::default();
+        key.serialize(&mut entry)?;
+        self.entry = Some(entry);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), HashError> {
+        let mut entry = self.entry.take().unwrap_or_default();
+        value.serialize(&mut entry)?;
+        self.entries += 1;
+        self.sum = self.sum.wrapping_add(entry.0.finish());
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), HashError> {
+        self.entries.hash(&mut self.hasher.0);
+        self.sum.hash(&mut self.hasher.0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandLogDeserializer, CommandLogSerializer, CommandRecorder, WorldStateHash};
+    use crate::{
+        self as bevy_ecs,
+        component::Component,
+        reflect::{ReflectCommand, ReflectComponent, ReflectResource},
+        schedule::{Stage, SystemStage},
+        system::{Command, Commands, Res},
+        world::World,
+    };
+    use bevy_reflect::{Reflect, TypeRegistryArc};
+    use bevy_utils::HashMap;
+    use serde::de::DeserializeSeed;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Counter {
+        value: u32,
+        weights: HashMap<String, f32>,
+    }
+
+    #[derive(Reflect, Default)]
+    #[reflect(Command)]
+    struct SpawnCounter {
+        value: u32,
+    }
+
+    impl Command for SpawnCounter {
+        fn write(self, world: &mut World) {
+            let mut weights = HashMap::default();
+            weights.insert("a".to_string(), 0.5);
+            weights.insert("b".to_string(), self.value as f32);
+            world.spawn().insert(Counter {
+                value: self.value,
+                weights,
+            });
+        }
+    }
+
+    #[derive(Reflect, Default)]
+    #[reflect(Resource)]
+    struct PlayerInput(u32);
+
+    struct Unrecorded;
+
+    fn type_registry() -> TypeRegistryArc {
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<u32>();
+            type_registry.register::<f32>();
+            type_registry.register::<String>();
+            type_registry.register::<HashMap<String, f32>>();
+            type_registry.register::<Counter>();
+            type_registry.register::<SpawnCounter>();
+            type_registry.register::<PlayerInput>();
+        }
+        type_registry
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let type_registry = type_registry();
+        let mut world = World::new();
+        world.insert_resource(type_registry.clone());
+        world.insert_resource(PlayerInput(0));
+        world.insert_resource(
+            CommandRecorder::new(type_registry.clone()).with_input::<PlayerInput>(),
+        );
+
+        let mut stage = SystemStage::single_threaded().with_system(
+            |mut commands: Commands, input: Res<PlayerInput>| {
+                commands.add(SpawnCounter { value: input.0 });
+                commands.insert_resource(Unrecorded);
+            },
+        );
+        for input in 1..=3 {
+            world.resource_mut::<PlayerInput>().0 = input;
+            CommandRecorder::start_frame_system(&mut world);
+            stage.run(&mut world);
+        }
+
+        let mut recorder = world.resource_mut::<CommandRecorder>();
+        assert_eq!(
+            recorder.unrecorded_commands().collect::<Vec<_>>(),
+            vec![std::any::type_name::<
+                crate::system::InsertResource<Unrecorded>,
+            >()]
+        );
+        let log = recorder.take_log();
+        assert_eq!(log.frames.len(), 3);
+        assert!(log
+            .frames
+            .iter()
+            .all(|frame| frame.inputs.len() == 1 && frame.commands.len() == 1));
+
+        let type_registry = type_registry.read();
+        let serialized = ron::to_string(&CommandLogSerializer::new(&log, &type_registry)).unwrap();
+        let mut deserializer = ron::de::Deserializer::from_str(&serialized).unwrap();
+        let log = CommandLogDeserializer {
+            registry: &type_registry,
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+
+        let mut replay_world = World::new();
+        log.replay(&mut replay_world, &type_registry).unwrap();
+        assert_eq!(replay_world.resource::<PlayerInput>().0, 3);
+        assert_eq!(
+            WorldStateHash::new(&replay_world, &type_registry),
+            WorldStateHash::new(&world, &type_registry)
+        );
+    }
+
+    #[test]
+    fn record_nested_commands() {
+        let type_registry = type_registry();
+        let mut world = World::new();
+        world.insert_resource(CommandRecorder::new(type_registry));
+
+        let spawn_counter = world.register_system(|mut commands: Commands| {
+            commands.add(SpawnCounter { value: 1 });
+        });
+        let mut stage =
+            SystemStage::single_threaded().with_system(move |mut commands: Commands| {
+                commands.run_system(spawn_counter);
+            });
+        stage.run(&mut world);
+
+        // the commands of the registered system are applied while the command that runs it is
+        let recorder = world.resource::<CommandRecorder>();
+        assert_eq!(
+            recorder.unrecorded_commands().collect::<Vec<_>>(),
+            vec![std::any::type_name::<crate::system::RunRegisteredSystem>()]
+        );
+        assert_eq!(recorder.log().frames.len(), 1);
+        assert_eq!(recorder.log().frames[0].commands.len(), 1);
+        assert_eq!(world.query::<&Counter>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn world_state_hash() {
+        let type_registry = type_registry();
+        let type_registry = type_registry.read();
+        let mut world = World::new();
+        SpawnCounter { value: 1 }.write(&mut world);
+        let entity = world.spawn().id();
+        SpawnCounter { value: 2 }.write(&mut world);
+        let hash = WorldStateHash::new(&world, &type_registry);
+        let name = std::any::type_name::<Counter>();
+        assert!(hash.get(name).is_some());
+        assert_eq!(hash, WorldStateHash::new(&world, &type_registry));
+
+        world.despawn(entity);
+        assert_eq!(hash, WorldStateHash::new(&world, &type_registry));
+
+        for mut counter in world.query::<&mut Counter>().iter_mut(&mut world) {
+            counter.weights.insert("a".to_string(), 0.25);
+        }
+        let changed = WorldStateHash::new(&world, &type_registry);
+        assert_eq!(changed.diff(&hash), vec![name]);
+        assert!(changed.diff(&WorldStateHash::new(&World::new(), &type_registry)) == vec![name]);
+    }
+}
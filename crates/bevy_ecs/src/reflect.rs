@@ -4,6 +4,8 @@ pub use crate::change_detection::ReflectMut;
 use crate::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    snapshot::SnapshotRegistry,
+    system::{Command, Resource},
     world::{FromWorld, World},
 };
 use bevy_reflect::{
@@ -18,6 +20,8 @@ pub struct ReflectComponent {
     reflect_component: fn(&World, Entity) -> Option<&dyn Reflect>,
     reflect_component_mut: unsafe fn(&World, Entity) -> Option<ReflectMut>,
     copy_component: fn(&World, &mut World, Entity, Entity),
+    reflect_ptr: unsafe fn(*const u8) -> *const dyn Reflect,
+    reflect_ptr_mut: unsafe fn(*mut u8) -> *mut dyn Reflect,
 }
 
 impl ReflectComponent {
@@ -64,6 +68,26 @@ impl ReflectComponent {
         (self.reflect_component_mut)(world, entity)
     }
 
+    /// Gets the component pointed to by `ptr` as a reflected value.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid value of the component type this [`ReflectComponent`] was
+    /// created for, such as the pointer of a [`DynamicComponent`](crate::query::DynamicComponent),
+    /// and the value must outlive `'a`.
+    pub unsafe fn reflect_ptr<'a>(&self, ptr: *const u8) -> &'a dyn Reflect {
+        &*(self.reflect_ptr)(ptr)
+    }
+
+    /// Gets the component pointed to by `ptr` as a mutable reflected value.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid value of the component type this [`ReflectComponent`] was
+    /// created for, the value must outlive `'a`, and no other reference to it may exist while the
+    /// returned reference is alive.
+    pub unsafe fn reflect_ptr_mut<'a>(&self, ptr: *mut u8) -> &'a mut dyn Reflect {
+        &mut *(self.reflect_ptr_mut)(ptr)
+    }
+
     pub fn copy_component(
         &self,
         source_world: &World,
@@ -118,6 +142,101 @@ impl<C: Component + Reflect + FromWorld> FromType<C> for ReflectComponent {
                         ticks: c.ticks,
                     })
             },
+            reflect_ptr: |ptr| ptr as *const C as *const dyn Reflect,
+            reflect_ptr_mut: |ptr| ptr as *mut C as *mut dyn Reflect,
+        }
+    }
+}
+
+/// Type data that adds a component to the [`SnapshotRegistry`] created by
+/// [`SnapshotRegistry::from_type_registry`].
+#[derive(Clone)]
+pub struct ReflectSnapshot {
+    register: fn(&mut SnapshotRegistry),
+}
+
+impl ReflectSnapshot {
+    pub fn register(&self, registry: &mut SnapshotRegistry) {
+        (self.register)(registry);
+    }
+}
+
+impl<C: Component + Clone + PartialEq> FromType<C> for ReflectSnapshot {
+    fn from_type() -> Self {
+        ReflectSnapshot {
+            register: |registry| {
+                registry.register::<C>();
+            },
+        }
+    }
+}
+
+/// Type data that lets a [`Command`] be recorded by a
+/// [`CommandRecorder`](crate::recording::CommandRecorder) and replayed from a
+/// [`CommandLog`](crate::recording::CommandLog).
+#[derive(Clone)]
+pub struct ReflectCommand {
+    apply_command: fn(&mut World, &dyn Reflect),
+    reflect_ptr: unsafe fn(*const u8) -> *const dyn Reflect,
+}
+
+impl ReflectCommand {
+    /// Writes a command built from the reflected `command` to the world.
+    pub fn apply_command(&self, world: &mut World, command: &dyn Reflect) {
+        (self.apply_command)(world, command);
+    }
+
+    /// Gets the command pointed to by `ptr` as a reflected value.
+    ///
+    /// # Safety
+    /// `ptr` must point to a valid value of the command type this [`ReflectCommand`] was created
+    /// for, and the value must outlive `'a`.
+    pub unsafe fn reflect_ptr<'a>(&self, ptr: *const u8) -> &'a dyn Reflect {
+        &*(self.reflect_ptr)(ptr)
+    }
+}
+
+impl<C: Command + Reflect + FromWorld> FromType<C> for ReflectCommand {
+    fn from_type() -> Self {
+        ReflectCommand {
+            apply_command: |world, reflected_command| {
+                let mut command = C::from_world(world);
+                command.apply(reflected_command);
+                command.write(world);
+            },
+            reflect_ptr: |ptr| ptr as *const C as *const dyn Reflect,
+        }
+    }
+}
+
+/// Type data that lets a resource be read and inserted through reflection, e.g. to record it as
+/// an input of a [`CommandRecorder`](crate::recording::CommandRecorder).
+#[derive(Clone)]
+pub struct ReflectResource {
+    insert_resource: fn(&mut World, &dyn Reflect),
+    reflect_resource: fn(&World) -> Option<&dyn Reflect>,
+}
+
+impl ReflectResource {
+    /// Inserts a resource built from the reflected `resource`, replacing the current value.
+    pub fn insert_resource(&self, world: &mut World, resource: &dyn Reflect) {
+        (self.insert_resource)(world, resource);
+    }
+
+    pub fn reflect_resource<'a>(&self, world: &'a World) -> Option<&'a dyn Reflect> {
+        (self.reflect_resource)(world)
+    }
+}
+
+impl<R: Resource + Reflect + FromWorld> FromType<R> for ReflectResource {
+    fn from_type() -> Self {
+        ReflectResource {
+            insert_resource: |world, reflected_resource| {
+                let mut resource = R::from_world(world);
+                resource.apply(reflected_resource);
+                world.insert_resource(resource);
+            },
+            reflect_resource: |world| world.get_resource::<R>().map(|r| r as &dyn Reflect),
         }
     }
 }
@@ -128,6 +247,7 @@ impl_from_reflect_value!(Entity);
 #[derive(Clone)]
 pub struct ReflectMapEntities {
     map_entities: fn(&mut World, &EntityMap) -> Result<(), MapEntitiesError>,
+    map_entity: fn(&mut World, Entity, &EntityMap) -> Result<(), MapEntitiesError>,
 }
 
 impl ReflectMapEntities {
@@ -138,6 +258,17 @@ impl ReflectMapEntities {
     ) -> Result<(), MapEntitiesError> {
         (self.map_entities)(world, entity_map)
     }
+
+    /// Maps the entities referenced by the component of `entity` only. Does nothing if `entity`
+    /// doesn't have the component.
+    pub fn map_entity(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        entity_map: &EntityMap,
+    ) -> Result<(), MapEntitiesError> {
+        (self.map_entity)(world, entity, entity_map)
+    }
 }
 
 impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
@@ -151,6 +282,27 @@ impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
                 }
                 Ok(())
             },
+            map_entity: |world, entity, entity_map| {
+                if let Some(mut component) = world.get_mut::<C>(entity) {
+                    component.map_entities(entity_map)?;
+                }
+                Ok(())
+            },
         }
     }
 }
+
+/// Type data for components whose entity references are only meaningful within a set of cloned
+/// entities, such as the children of an entity.
+///
+/// When cloning entities, e.g. with [`World::clone_entities`], references to entities outside of
+/// the cloned set are usually left as they are. A component registered with this type data is
+/// instead not copied to a clone that would refer to such an entity.
+#[derive(Clone)]
+pub struct ReflectCloneMapped;
+
+impl<C: Component + MapEntities> FromType<C> for ReflectCloneMapped {
+    fn from_type() -> Self {
+        ReflectCloneMapped
+    }
+}
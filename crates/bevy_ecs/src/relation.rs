@@ -0,0 +1,419 @@
+//! Types for declaring relations between entities.
+//!
+//! A relation is a directed edge from a *source* entity to one or more *target* entities, tagged
+//! with a relation kind `R`. Relations are stored on the source as a [`Relation<R>`] component and
+//! mirrored in a reverse index owned by the [`World`], so both directions can be looked up cheaply.
+
+use crate::{
+    component::{Component, ComponentId, SparseStorage},
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    storage::SparseSet,
+    world::World,
+};
+use bevy_utils::{HashMap, HashSet};
+use std::{collections::VecDeque, marker::PhantomData};
+
+/// A component that relates its entity to one or more target entities through the relation kind
+/// `R`.
+///
+/// `R` is usually a unit struct that names the relation, e.g. `Relation<Owns>` or
+/// `Relation<Targets>`. A single source can relate to many targets and many sources can relate to
+/// the same target, making relations many-to-many.
+///
+/// Relations are created and removed with [`EntityMut::relate`](crate::world::EntityMut::relate)
+/// and [`EntityMut::unrelate`](crate::world::EntityMut::unrelate) (or their
+/// [`EntityCommands`](crate::system::EntityCommands) equivalents), which keep the reverse index
+/// used by [`World::related_to`] up to date. When a target is despawned it is automatically removed
+/// from every relation pointing at it.
+/// In systems, the sources of a relation to a given target are matched with the
+/// [`RelatedTo`](crate::query::RelatedTo) query filter.
+///
+/// Relations are stored in sparse sets, so adding and removing them is cheap.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{prelude::*, relation::Relation};
+///
+/// struct Owns;
+///
+/// let mut world = World::new();
+/// let sword = world.spawn().id();
+/// let player = world.spawn().relate::<Owns>(sword).id();
+///
+/// let owned = world.get::<Relation<Owns>>(player).unwrap();
+/// assert!(owned.contains(sword));
+/// assert_eq!(world.related_to::<Owns>(sword).collect::<Vec<_>>(), vec![player]);
+///
+/// world.despawn(sword);
+/// assert!(world.get::<Relation<Owns>>(player).is_none());
+/// ```
+pub struct Relation<R> {
+    pub(crate) targets: Vec<Entity>,
+    marker: PhantomData<R>,
+}
+
+impl<R: Send + Sync + 'static> Component for Relation<R> {
+    type Storage = SparseStorage;
+}
+
+impl<R> Relation<R> {
+    pub(crate) fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the targets of this relation, in the order they were added.
+    #[inline]
+    pub fn targets(&self) -> &[Entity] {
+        &self.targets
+    }
+
+    /// Returns `true` if this relation points at `target`.
+    #[inline]
+    pub fn contains(&self, target: Entity) -> bool {
+        self.targets.contains(&target)
+    }
+
+    /// Returns the number of targets of this relation.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns `true` if this relation has no targets.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+}
+
+impl<R> MapEntities for Relation<R> {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for target in &mut self.targets {
+            *target = entity_map.get(*target)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R> std::fmt::Debug for Relation<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Relation")
+            .field("kind", &std::any::type_name::<R>())
+            .field("targets", &self.targets)
+            .finish()
+    }
+}
+
+/// Type-erased operations for a registered relation kind.
+pub(crate) struct RelationInfo {
+    pub(crate) targets: fn(&World, Entity) -> Vec<Entity>,
+    pub(crate) remove_target: fn(&mut World, Entity, Entity),
+}
+
+/// Bookkeeping for every relation in a [`World`].
+///
+/// This stores a reverse index from each target to the `(relation, source)` pairs pointing at it.
+/// The index may contain stale entries if a [`Relation`] component is removed directly; those are
+/// filtered out on lookup and purged when the target is despawned.
+#[derive(Default)]
+pub struct Relations {
+    pub(crate) infos: SparseSet<ComponentId, RelationInfo>,
+    pub(crate) sources: HashMap<Entity, Vec<(ComponentId, Entity)>>,
+}
+
+impl Relations {
+    /// Returns `true` if `component_id` belongs to a [`Relation`] component.
+    #[inline]
+    pub fn is_relation(&self, component_id: ComponentId) -> bool {
+        self.infos.contains(component_id)
+    }
+
+    /// Returns the number of relation kinds registered in this world.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.infos.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.infos.is_empty()
+    }
+
+    pub(crate) fn register<R: Send + Sync + 'static>(&mut self, component_id: ComponentId) {
+        self.infos
+            .get_or_insert_with(component_id, || RelationInfo {
+                targets: |world, source| {
+                    world
+                        .get::<Relation<R>>(source)
+                        .map(|relation| relation.targets.clone())
+                        .unwrap_or_default()
+                },
+                remove_target: |world, source, target| {
+                    if let Some(mut entity) = world.get_entity_mut(source) {
+                        entity.unrelate::<R>(target);
+                    }
+                },
+            });
+    }
+
+    pub(crate) fn add_source(&mut self, component_id: ComponentId, source: Entity, target: Entity) {
+        let sources = self.sources.entry(target).or_default();
+        // the entry may be left over from removing `Relation<R>` directly
+        if !sources.contains(&(component_id, source)) {
+            sources.push((component_id, source));
+        }
+    }
+
+    pub(crate) fn remove_source(
+        &mut self,
+        component_id: ComponentId,
+        source: Entity,
+        target: Entity,
+    ) {
+        if let Some(sources) = self.sources.get_mut(&target) {
+            sources.retain(|pair| *pair != (component_id, source));
+            if sources.is_empty() {
+                self.sources.remove(&target);
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.sources.clear();
+    }
+}
+
+/// A breadth-first, cycle-safe walk over a relation graph.
+///
+/// Created by [`World::traverse_relation`] and [`World::traverse_relation_sources`].
+pub struct RelationTraversal<'w, R> {
+    world: &'w World,
+    queue: VecDeque<Entity>,
+    visited: HashSet<Entity>,
+    next: fn(&World, Entity) -> Vec<Entity>,
+    marker: PhantomData<R>,
+}
+
+impl<'w, R> RelationTraversal<'w, R> {
+    pub(crate) fn new(
+        world: &'w World,
+        start: Entity,
+        next: fn(&World, Entity) -> Vec<Entity>,
+    ) -> Self {
+        let mut visited = HashSet::default();
+        let mut queue = VecDeque::new();
+        for entity in next(world, start) {
+            if visited.insert(entity) {
+                queue.push_back(entity);
+            }
+        }
+        Self {
+            world,
+            queue,
+            visited,
+            next,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'w, R> Iterator for RelationTraversal<'w, R> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entity = self.queue.pop_front()?;
+        for next in (self.next)(self.world, entity) {
+            if self.visited.insert(next) {
+                self.queue.push_back(next);
+            }
+        }
+        Some(entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Relation;
+    use crate as bevy_ecs;
+    use crate::{
+        component::Component,
+        entity::Entity,
+        query::{QueryState, RelatedTo, With},
+        schedule::{Stage, SystemStage},
+        system::{CommandQueue, Commands, Query, ResMut},
+        world::World,
+    };
+
+    struct Owns;
+    struct Likes;
+
+    #[test]
+    fn relate_and_unrelate() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        let c = world.spawn().id();
+
+        world.entity_mut(a).relate::<Owns>(b).relate::<Owns>(c);
+        world.entity_mut(a).relate::<Owns>(b);
+        world.entity_mut(c).relate::<Owns>(b);
+        world.entity_mut(a).relate::<Likes>(b);
+
+        assert_eq!(world.get::<Relation<Owns>>(a).unwrap().targets(), &[b, c]);
+        let mut owners = world.related_to::<Owns>(b).collect::<Vec<_>>();
+        owners.sort();
+        assert_eq!(owners, vec![a, c]);
+        assert_eq!(world.related_to::<Likes>(b).collect::<Vec<_>>(), vec![a]);
+
+        world.entity_mut(a).unrelate::<Owns>(b).unrelate::<Owns>(c);
+        assert!(world.get::<Relation<Owns>>(a).is_none());
+        assert_eq!(world.related_to::<Owns>(b).collect::<Vec<_>>(), vec![c]);
+        assert_eq!(world.related_to::<Likes>(b).collect::<Vec<_>>(), vec![a]);
+    }
+
+    #[test]
+    fn remove_then_relate() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+
+        world.entity_mut(a).relate::<Owns>(b);
+        world.entity_mut(a).remove::<Relation<Owns>>();
+        assert_eq!(world.related_to::<Owns>(b).count(), 0);
+
+        world.entity_mut(a).relate::<Owns>(b);
+        assert_eq!(world.related_to::<Owns>(b).collect::<Vec<_>>(), vec![a]);
+    }
+
+    #[test]
+    fn despawn_cleans_up_relations() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        let c = world.spawn().id();
+        world.entity_mut(a).relate::<Owns>(b).relate::<Owns>(c);
+        world.entity_mut(b).relate::<Owns>(c);
+
+        world.despawn(b);
+        assert_eq!(world.get::<Relation<Owns>>(a).unwrap().targets(), &[c]);
+        assert_eq!(world.related_to::<Owns>(c).collect::<Vec<_>>(), vec![a]);
+
+        world.despawn(a);
+        assert_eq!(world.related_to::<Owns>(c).count(), 0);
+        assert!(world.relations.sources.is_empty());
+    }
+
+    #[test]
+    fn removed_relation_component_is_not_reported() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        world.entity_mut(a).relate::<Owns>(b);
+        world.entity_mut(a).remove::<Relation<Owns>>();
+        assert_eq!(world.related_to::<Owns>(b).count(), 0);
+        world.despawn(b);
+    }
+
+    #[test]
+    fn traversal_handles_cycles() {
+        let mut world = World::new();
+        let entities = (0..4).map(|_| world.spawn().id()).collect::<Vec<Entity>>();
+        for pair in entities.windows(2) {
+            world.entity_mut(pair[0]).relate::<Owns>(pair[1]);
+        }
+        world.entity_mut(entities[3]).relate::<Owns>(entities[0]);
+
+        let visited = world
+            .traverse_relation::<Owns>(entities[0])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            visited,
+            vec![entities[1], entities[2], entities[3], entities[0]]
+        );
+
+        let visited = world
+            .traverse_relation_sources::<Owns>(entities[0])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            visited,
+            vec![entities[3], entities[2], entities[1], entities[0]]
+        );
+    }
+
+    #[test]
+    fn relation_commands() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let target = world.spawn().id();
+        let source = {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.spawn().relate::<Owns>(target).id()
+        };
+        queue.apply(&mut world);
+        assert!(world
+            .get::<Relation<Owns>>(source)
+            .unwrap()
+            .contains(target));
+
+        Commands::new(&mut queue, &world)
+            .entity(source)
+            .unrelate::<Owns>(target);
+        queue.apply(&mut world);
+        assert!(world.get::<Relation<Owns>>(source).is_none());
+    }
+
+    #[derive(Component)]
+    struct Item;
+
+    #[derive(Component)]
+    struct Player;
+
+    #[test]
+    fn related_to_filter() {
+        let mut world = World::new();
+        let sword = world.spawn().insert(Item).id();
+        let shield = world.spawn().insert(Item).id();
+        let a = world.spawn().insert(Player).relate::<Owns>(sword).id();
+        let b = world
+            .spawn()
+            .insert(Player)
+            .relate::<Owns>(sword)
+            .relate::<Owns>(shield)
+            .id();
+        world.spawn().insert(Player).relate::<Likes>(shield);
+        world.insert_resource(Vec::<(Entity, Vec<Entity>)>::new());
+
+        fn owners(
+            items: Query<Entity, With<Item>>,
+            mut players: Query<Entity, (With<Player>, RelatedTo<Owns>)>,
+            mut owners: ResMut<Vec<(Entity, Vec<Entity>)>>,
+        ) {
+            for item in items.iter() {
+                players.set_relation_target::<Owns>(item);
+                let mut item_owners = players.iter().collect::<Vec<_>>();
+                item_owners.sort();
+                owners.push((item, item_owners));
+            }
+        }
+
+        let mut stage = SystemStage::single(owners);
+        stage.run(&mut world);
+        let mut owners = world
+            .get_resource::<Vec<(Entity, Vec<Entity>)>>()
+            .unwrap()
+            .clone();
+        owners.sort();
+        assert_eq!(owners, vec![(sword, vec![a, b]), (shield, vec![b])]);
+
+        // without a target, nothing matches
+        let mut query = QueryState::<Entity, RelatedTo<Owns>>::new(&mut world);
+        assert_eq!(query.iter(&world).count(), 0);
+        query.set_relation_target::<Owns>(&world, shield);
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![b]);
+        assert_eq!(query.get(&world, a).ok(), None);
+    }
+}
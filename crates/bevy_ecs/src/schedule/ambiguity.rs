@@ -0,0 +1,134 @@
+use crate::schedule::SystemKind;
+use std::{borrow::Cow, fmt};
+
+/// A pair of systems of the same stage that access the same data, at least one of them mutably,
+/// without an ordering constraint between them.
+///
+/// The ambiguities of a stage are returned by
+/// [`SystemStage::ambiguities`](super::SystemStage::ambiguities) and
+/// [`Schedule::ambiguities`](super::Schedule::ambiguities).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionOrderAmbiguity {
+    /// The label of the stage in its [`Schedule`](super::Schedule), or `None` if the ambiguity
+    /// was not found as part of a schedule.
+    pub stage: Option<String>,
+    /// The kind of both systems; systems of different kinds never run at the same time.
+    pub kind: SystemKind,
+    pub system_a: String,
+    pub system_b: String,
+    /// The names of the components and resources both systems access. This is empty for
+    /// exclusive systems, which can access anything.
+    pub conflicts: Vec<String>,
+}
+
+impl fmt::Display for ExecutionOrderAmbiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} and {:?}", self.system_a, self.system_b)?;
+        if !self.conflicts.is_empty() {
+            write!(f, ", conflicts: {:?}", self.conflicts)?;
+        }
+        Ok(())
+    }
+}
+
+/// When this resource is present in the `App`'s `Resources`, each `SystemStage` panics when it
+/// is first run, or when its systems have changed, if it contains systems with ambiguous
+/// execution order. This makes execution order ambiguities a hard error in tests.
+///
+/// Ambiguities between systems of external crates can be ignored with
+/// [`ignore_crate`](Self::ignore_crate), so that only the ambiguities involving systems of your
+/// own crates fail. See [`ReportExecutionOrderAmbiguities`](super::ReportExecutionOrderAmbiguities)
+/// for how to resolve the ambiguities.
+///
+/// # Example
+///
+/// ```should_panic
+/// use bevy_ecs::{prelude::*, schedule::DenyExecutionOrderAmbiguities};
+///
+/// struct Score(u32);
+///
+/// fn increment(mut score: ResMut<Score>) {
+///     score.0 += 1;
+/// }
+///
+/// fn reset(mut score: ResMut<Score>) {
+///     score.0 = 0;
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(Score(0));
+/// world.insert_resource(DenyExecutionOrderAmbiguities::default().ignore_crate("bevy_render"));
+///
+/// let mut stage = SystemStage::parallel()
+///     .with_system(increment)
+///     .with_system(reset);
+/// // panics, as `increment` and `reset` both write `Score` in an unspecified order
+/// stage.run(&mut world);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DenyExecutionOrderAmbiguities {
+    ignored_crates: Vec<Cow<'static, str>>,
+}
+
+impl DenyExecutionOrderAmbiguities {
+    /// Ignores the ambiguities between two systems that are both defined in the crate
+    /// `crate_name`, or in other ignored crates.
+    ///
+    /// The crate of a system is the first segment of its name, so a generic system such as
+    /// `bevy_ecs::event::Events<T>::update_system` belongs to `bevy_ecs`.
+    pub fn ignore_crate(mut self, crate_name: impl Into<Cow<'static, str>>) -> Self {
+        self.ignored_crates.push(crate_name.into());
+        self
+    }
+
+    /// Returns `true` if `ambiguity` is between two systems of ignored crates.
+    pub fn is_ignored(&self, ambiguity: &ExecutionOrderAmbiguity) -> bool {
+        self.is_ignored_system(&ambiguity.system_a) && self.is_ignored_system(&ambiguity.system_b)
+    }
+
+    fn is_ignored_system(&self, name: &str) -> bool {
+        let crate_name = name.split("::").next().unwrap_or(name);
+        self.ignored_crates
+            .iter()
+            .any(|ignored| ignored == crate_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DenyExecutionOrderAmbiguities, ExecutionOrderAmbiguity};
+    use crate::schedule::SystemKind;
+
+    fn ambiguity(system_a: &str, system_b: &str) -> ExecutionOrderAmbiguity {
+        ExecutionOrderAmbiguity {
+            stage: None,
+            kind: SystemKind::Parallel,
+            system_a: system_a.to_string(),
+            system_b: system_b.to_string(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ignored_crates() {
+        let deny = DenyExecutionOrderAmbiguities::default()
+            .ignore_crate("bevy_render")
+            .ignore_crate("bevy_ui");
+        assert!(deny.is_ignored(&ambiguity(
+            "bevy_render::camera::camera_system",
+            "bevy_render::view::visibility_system"
+        )));
+        assert!(deny.is_ignored(&ambiguity(
+            "bevy_render::camera::camera_system",
+            "bevy_ui::flex::flex_node_system"
+        )));
+        assert!(!deny.is_ignored(&ambiguity(
+            "bevy_render::camera::camera_system",
+            "game::camera::follow_player"
+        )));
+        assert!(!deny.is_ignored(&ambiguity(
+            "bevy_render_extra::camera_system",
+            "bevy_render::camera::camera_system"
+        )));
+    }
+}
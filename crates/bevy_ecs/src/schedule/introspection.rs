@@ -0,0 +1,389 @@
+//! A description of the resolved system graph of a [`Schedule`](super::Schedule), with an
+//! exporter to Graphviz DOT.
+//!
+//! The graph is built by [`Schedule::graph`](super::Schedule::graph) or
+//! [`SystemStage::graph`](super::SystemStage::graph). Its types implement [`Serialize`], so it can
+//! be exported to any format supported by `serde`, such as JSON or RON. Exporting it in CI and
+//! diffing it against a checked-in copy catches accidental changes to the order of systems.
+
+use crate::{
+    component::ComponentId,
+    query::Access,
+    schedule::{BoxedSystemLabel, RunCriteriaContainer, RunCriteriaInner, SystemContainer},
+    world::World,
+};
+use bevy_utils::label::DynHash;
+use serde::Serialize;
+use std::{borrow::Cow, fmt::Debug, fmt::Write};
+
+/// The stages of a [`Schedule`](super::Schedule), in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScheduleGraph {
+    pub stages: Vec<StageGraph>,
+}
+
+/// The systems and run criteria of a stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StageGraph {
+    /// The label of the stage in its [`Schedule`](super::Schedule), or `None` for a stage that
+    /// was not described as part of a schedule.
+    pub label: Option<String>,
+    /// The name of the run criteria of the whole stage, if it has one.
+    pub stage_run_criteria: Option<String>,
+    /// The run criteria of the systems, in the order they are evaluated.
+    pub run_criteria: Vec<RunCriteriaNode>,
+    /// The systems, in the order of [`SystemKind`] and then in topological order.
+    pub systems: Vec<SystemNode>,
+    /// The stages of the stage, if it is a nested [`Schedule`](super::Schedule).
+    pub schedule: Option<ScheduleGraph>,
+}
+
+/// The run criteria of one or more systems of a stage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunCriteriaNode {
+    pub name: String,
+    pub label: Option<String>,
+    /// The index in [`StageGraph::run_criteria`] of the run criteria piped into this one.
+    pub input: Option<usize>,
+}
+
+/// Where a system runs in its stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemKind {
+    ExclusiveAtStart,
+    Parallel,
+    ExclusiveBeforeCommands,
+    ExclusiveAtEnd,
+}
+
+/// A system of a stage and its ordering constraints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SystemNode {
+    pub name: String,
+    pub kind: SystemKind,
+    pub labels: Vec<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    /// The indices in [`StageGraph::systems`] of the systems that must run before this one, as
+    /// resolved from the `before` and `after` constraints of the systems of the same kind.
+    pub dependencies: Vec<usize>,
+    /// The index in [`StageGraph::run_criteria`] of the run criteria of this system.
+    pub run_criteria: Option<usize>,
+    /// The data accessed by the system, or `None` for exclusive systems, which access the whole
+    /// [`World`].
+    pub access: Option<SystemAccess>,
+}
+
+/// The components and resources a system accesses, by type name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SystemAccess {
+    pub components_read: Vec<String>,
+    pub components_written: Vec<String>,
+    pub resources_read: Vec<String>,
+    pub resources_written: Vec<String>,
+}
+
+impl SystemAccess {
+    fn new(access: &Access<ComponentId>, world: &World) -> Self {
+        let mut system_access = SystemAccess::default();
+        let resources = world.archetypes().resource();
+        let name = |id: ComponentId| {
+            world
+                .components()
+                .get_info(id)
+                .map_or_else(|| format!("{:?}", id), |info| info.name().to_string())
+        };
+        for id in access.reads() {
+            if resources.contains(id) {
+                system_access.resources_read.push(name(id));
+            } else {
+                system_access.components_read.push(name(id));
+            }
+        }
+        for id in access.writes() {
+            if resources.contains(id) {
+                system_access.resources_written.push(name(id));
+            } else {
+                system_access.components_written.push(name(id));
+            }
+        }
+        system_access.components_read.sort();
+        system_access.components_written.sort();
+        system_access.resources_read.sort();
+        system_access.resources_written.sort();
+        system_access
+    }
+}
+
+impl RunCriteriaNode {
+    pub(super) fn new(container: &RunCriteriaContainer) -> Self {
+        RunCriteriaNode {
+            name: container.name().to_string(),
+            label: container.label.as_ref().map(|label| label_name(&**label)),
+            input: match container.inner {
+                RunCriteriaInner::Single(_) => None,
+                RunCriteriaInner::Piped { input, .. } => Some(input),
+            },
+        }
+    }
+}
+
+impl SystemNode {
+    /// Describes `container`. `offset` is the index in [`StageGraph::systems`] of the first
+    /// system of the same kind.
+    pub(super) fn new(
+        container: &impl SystemContainer,
+        kind: SystemKind,
+        offset: usize,
+        world: &World,
+    ) -> Self {
+        let labels = |labels: &[BoxedSystemLabel]| {
+            labels
+                .iter()
+                .map(|label| label_name(&**label))
+                .collect::<Vec<_>>()
+        };
+        SystemNode {
+            name: container.name().to_string(),
+            kind,
+            labels: labels(container.labels()),
+            before: labels(container.before()),
+            after: labels(container.after()),
+            dependencies: container
+                .dependencies()
+                .iter()
+                .map(|index| offset + index)
+                .collect(),
+            run_criteria: container.run_criteria(),
+            access: container
+                .component_access()
+                .map(|access| SystemAccess::new(access, world)),
+        }
+    }
+}
+
+impl ScheduleGraph {
+    /// Exports the graph to the Graphviz DOT format.
+    ///
+    /// Each stage is a cluster, systems are boxes and run criteria are diamonds. Solid edges go
+    /// from a system to the systems that depend on it, and dashed edges from run criteria to the
+    /// systems or run criteria that use them.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph schedule {\n    rankdir=LR;\n    node [shape=box];\n");
+        write_dot_stages(&mut dot, &self.stages, "s", 1);
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The name of a label in the graph: the string itself for a string label, or else its `Debug`
+/// representation.
+pub(super) fn label_name<L: DynHash + Debug + ?Sized>(label: &L) -> String {
+    let any = label.as_dyn_eq().as_any();
+    if let Some(name) = any.downcast_ref::<&'static str>() {
+        name.to_string()
+    } else if let Some(name) = any.downcast_ref::<Cow<'static, str>>() {
+        name.to_string()
+    } else {
+        format!("{:?}", label)
+    }
+}
+
+fn write_dot_stages(dot: &mut String, stages: &[StageGraph], prefix: &str, depth: usize) {
+    let indent = "    ".repeat(depth);
+    for (stage_index, stage) in stages.iter().enumerate() {
+        let id = format!("{}{}", prefix, stage_index);
+        let mut label = stage.label.clone().unwrap_or_default();
+        if let Some(run_criteria) = &stage.stage_run_criteria {
+            let _ = write!(label, "\nrun criteria: {}", run_criteria);
+        }
+        let _ = writeln!(dot, "{}subgraph \"cluster_{}\" {{", indent, id);
+        let _ = writeln!(dot, "{}    label={};", indent, dot_string(&label));
+        for (index, run_criteria) in stage.run_criteria.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "{}    \"{}_rc{}\" [label={}, shape=diamond];",
+                indent,
+                id,
+                index,
+                dot_string(&run_criteria.name)
+            );
+            if let Some(input) = run_criteria.input {
+                let _ = writeln!(
+                    dot,
+                    "{}    \"{}_rc{}\" -> \"{}_rc{}\" [style=dashed];",
+                    indent, id, input, id, index
+                );
+            }
+        }
+        for (index, system) in stage.systems.iter().enumerate() {
+            // exclusive systems are drawn in bold
+            let style = if system.access.is_some() {
+                ""
+            } else {
+                ", style=bold"
+            };
+            let _ = writeln!(
+                dot,
+                "{}    \"{}_{}\" [label={}{}];",
+                indent,
+                id,
+                index,
+                dot_string(&system.name),
+                style
+            );
+            for dependency in &system.dependencies {
+                let _ = writeln!(
+                    dot,
+                    "{}    \"{}_{}\" -> \"{}_{}\";",
+                    indent, id, dependency, id, index
+                );
+            }
+            if let Some(run_criteria) = system.run_criteria {
+                let _ = writeln!(
+                    dot,
+                    "{}    \"{}_rc{}\" -> \"{}_{}\" [style=dashed];",
+                    indent, id, run_criteria, id, index
+                );
+            }
+        }
+        if let Some(schedule) = &stage.schedule {
+            write_dot_stages(dot, &schedule.stages, &format!("{}_", id), depth + 1);
+        }
+        let _ = writeln!(dot, "{}}}", indent);
+    }
+}
+
+fn dot_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SystemKind, SystemNode};
+    use crate::{
+        self as bevy_ecs,
+        component::Component,
+        schedule::{
+            ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
+            RunCriteriaDescriptorCoercion, Schedule, ShouldRun, SystemStage,
+        },
+        system::{IntoExclusiveSystem, Query, Res, ResMut},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct Position(f32);
+    #[derive(Component)]
+    struct Velocity(f32);
+
+    struct Gravity(f32);
+
+    fn gravity(gravity: Res<Gravity>, mut query: Query<&mut Velocity>) {
+        for mut velocity in query.iter_mut() {
+            velocity.0 += gravity.0;
+        }
+    }
+
+    fn movement(mut query: Query<(&mut Position, &Velocity)>) {
+        for (mut position, velocity) in query.iter_mut() {
+            position.0 += velocity.0;
+        }
+    }
+
+    fn reset(mut gravity: ResMut<Gravity>) {
+        gravity.0 = 0.0;
+    }
+
+    fn always() -> ShouldRun {
+        ShouldRun::Yes
+    }
+
+    fn schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.add_stage(
+            "update",
+            SystemStage::parallel()
+                .with_system_run_criteria(RunCriteriaDescriptorCoercion::label(always, "always"))
+                .with_system(movement.label("movement").after("gravity"))
+                .with_system(gravity.label("gravity").with_run_criteria("always"))
+                .with_system((|_: &mut World| {}).exclusive_system().at_end()),
+        );
+        schedule.add_stage_after(
+            "update",
+            "nested",
+            Schedule::default().with_stage("inner", SystemStage::single(reset)),
+        );
+        schedule
+    }
+
+    #[test]
+    fn schedule_graph() {
+        let mut world = World::new();
+        world.insert_resource(Gravity(-9.81));
+        let graph = schedule().graph(&mut world);
+
+        assert_eq!(graph.stages.len(), 2);
+        let update = &graph.stages[0];
+        assert_eq!(update.label.as_deref(), Some("update"));
+        assert_eq!(update.run_criteria.len(), 1);
+        assert_eq!(update.systems.len(), 3);
+
+        let gravity_index = update
+            .systems
+            .iter()
+            .position(|system| system.labels == ["gravity"])
+            .unwrap();
+        let gravity: &SystemNode = &update.systems[gravity_index];
+        assert_eq!(gravity.kind, SystemKind::Parallel);
+        assert_eq!(gravity.run_criteria, Some(0));
+        let access = gravity.access.as_ref().unwrap();
+        assert!(access.components_written[0].ends_with("Velocity"));
+        assert!(access.resources_read[0].ends_with("Gravity"));
+
+        let movement = update
+            .systems
+            .iter()
+            .find(|system| system.labels == ["movement"])
+            .unwrap();
+        assert_eq!(movement.dependencies, vec![gravity_index]);
+        assert_eq!(movement.after, vec!["gravity".to_string()]);
+
+        let exclusive = &update.systems[2];
+        assert_eq!(exclusive.kind, SystemKind::ExclusiveAtEnd);
+        assert!(exclusive.access.is_none());
+
+        let nested = graph.stages[1].schedule.as_ref().unwrap();
+        let reset = &nested.stages[0].systems[0];
+        assert!(reset.name.ends_with("reset"));
+        assert!(reset.access.as_ref().unwrap().resources_written[0].ends_with("Gravity"));
+
+        // building the graph again gives the same result
+        assert_eq!(schedule().graph(&mut world), graph);
+    }
+
+    #[test]
+    fn schedule_graph_export() {
+        let mut world = World::new();
+        world.insert_resource(Gravity(-9.81));
+        let graph = schedule().graph(&mut world);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph schedule {"));
+        assert!(dot.contains("subgraph \"cluster_s0\""));
+        assert!(dot.contains("subgraph \"cluster_s1_0\""));
+        assert!(dot.contains("label=\"update\";"));
+        assert!(dot.contains("shape=diamond"));
+
+        let ron = ron::to_string(&graph).unwrap();
+        assert!(ron.starts_with("(stages:[(label:Some(\"update\")"));
+        assert!(ron.contains("kind:exclusive_at_end"));
+    }
+}
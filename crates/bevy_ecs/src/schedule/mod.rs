@@ -1,28 +1,39 @@
 //! Tools for controlling system execution.
 //!
 //! When using Bevy ECS, systems are usually not run directly, but are inserted into a
-//!  [`Stage`], which then lives within a [`Schedule`].
+//!  [`Stage`], which then lives within a [`Schedule`]. A [`SystemGraph`] can be used instead of
+//!  a sequence of [`SystemStage`]s to order all systems in a single graph.
 
+mod ambiguity;
 mod executor;
 mod executor_parallel;
 pub mod graph_utils;
+mod introspection;
 mod label;
+mod run_condition;
 mod run_criteria;
 mod stage;
 mod state;
+mod state_transition;
 mod system_container;
 mod system_descriptor;
+mod system_graph;
 mod system_set;
 
+pub use ambiguity::*;
 pub use executor::*;
 pub use executor_parallel::*;
 pub use graph_utils::GraphNode;
+pub use introspection::*;
 pub use label::*;
+pub use run_condition::*;
 pub use run_criteria::*;
 pub use stage::*;
 pub use state::*;
+pub use state_transition::*;
 pub use system_container::*;
 pub use system_descriptor::*;
+pub use system_graph::*;
 pub use system_set::*;
 
 use std::fmt::Debug;
@@ -344,6 +355,68 @@ impl Schedule {
         }
     }
 
+    /// Returns the resolved graph of the systems of each stage, in execution order.
+    ///
+    /// The systems of each [`SystemStage`] are initialized and ordered first, see
+    /// [`SystemStage::graph`]. Nested schedules are described recursively, and other stages are
+    /// described by their label only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # fn my_system() {}
+    /// let mut world = World::new();
+    /// let mut schedule = Schedule::default();
+    /// schedule.add_stage("my_stage", SystemStage::single(my_system));
+    ///
+    /// let graph = schedule.graph(&mut world);
+    /// assert_eq!(graph.stages[0].systems.len(), 1);
+    /// println!("{}", graph.to_dot());
+    /// ```
+    pub fn graph(&mut self, world: &mut World) -> ScheduleGraph {
+        let mut stages = Vec::with_capacity(self.stage_order.len());
+        for label in &self.stage_order {
+            let stage = self.stages.get_mut(label).unwrap();
+            let mut graph = if let Some(stage) = stage.downcast_mut::<SystemStage>() {
+                stage.graph(world)
+            } else if let Some(schedule) = stage.downcast_mut::<Schedule>() {
+                StageGraph {
+                    schedule: Some(schedule.graph(world)),
+                    ..Default::default()
+                }
+            } else {
+                StageGraph::default()
+            };
+            graph.label = Some(introspection::label_name(&**label));
+            stages.push(graph);
+        }
+        ScheduleGraph { stages }
+    }
+
+    /// Returns the pairs of systems with ambiguous execution order of every [`SystemStage`] of
+    /// the schedule, including those of nested schedules, with the label of their stage. See
+    /// [`SystemStage::ambiguities`].
+    pub fn ambiguities(&mut self, world: &mut World) -> Vec<ExecutionOrderAmbiguity> {
+        let mut ambiguities = Vec::new();
+        for label in &self.stage_order {
+            let stage = self.stages.get_mut(label).unwrap();
+            if let Some(stage) = stage.downcast_mut::<SystemStage>() {
+                let label = format!("{:?}", label);
+                ambiguities.extend(stage.ambiguities(world).into_iter().map(|ambiguity| {
+                    ExecutionOrderAmbiguity {
+                        stage: Some(label.clone()),
+                        ..ambiguity
+                    }
+                }));
+            } else if let Some(schedule) = stage.downcast_mut::<Schedule>() {
+                ambiguities.extend(schedule.ambiguities(world));
+            }
+        }
+        ambiguities
+    }
+
     /// Iterates over all of schedule's stages and their labels, in execution order.
     pub fn iter_stages(&self) -> impl Iterator<Item = (&dyn StageLabel, &dyn Stage)> {
         self.stage_order
@@ -0,0 +1,273 @@
+use crate::{
+    archetype::{Archetype, ArchetypeComponentId},
+    component::ComponentId,
+    query::Access,
+    schedule::ShouldRun,
+    system::{BoxedSystem, In, IntoChainSystem, IntoSystem, System},
+    world::World,
+};
+use std::borrow::Cow;
+
+/// A system that decides whether another system should run, by returning a `bool`.
+///
+/// Unlike [`RunCriteria`](crate::schedule::RunCriteria), run conditions cannot ask to be checked
+/// again; they are evaluated once each time the system they guard could run. Any system with
+/// no input that returns a `bool` is a run condition, and conditions can be combined with
+/// [`and`](IntoRunCondition::and) and [`or`](IntoRunCondition::or).
+///
+/// Run conditions are attached to systems with
+/// [`ParallelSystemDescriptorCoercion::run_if`](crate::schedule::ParallelSystemDescriptorCoercion::run_if)
+/// or its exclusive equivalent.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::prelude::*;
+///
+/// struct Paused(bool);
+/// struct Score(u32);
+///
+/// fn not_paused(paused: Res<Paused>) -> bool {
+///     !paused.0
+/// }
+///
+/// fn below_max(score: Res<Score>) -> bool {
+///     score.0 < 3
+/// }
+///
+/// fn increment(mut score: ResMut<Score>) {
+///     score.0 += 1;
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(Paused(false));
+/// world.insert_resource(Score(0));
+///
+/// let mut stage = SystemStage::single(increment.run_if(not_paused.and(below_max)));
+/// for _ in 0..5 {
+///     stage.run(&mut world);
+/// }
+/// assert_eq!(world.get_resource::<Score>().unwrap().0, 3);
+/// ```
+pub trait IntoRunCondition<Params>: IntoSystem<(), bool, Params> + Sized {
+    /// Returns a run condition that is `true` if both `self` and `other` are `true`.
+    ///
+    /// `other` is not evaluated if `self` is `false`.
+    fn and<OtherParams, Other: IntoRunCondition<OtherParams>>(
+        self,
+        other: Other,
+    ) -> CombinedCondition<Self::System, Other::System> {
+        CombinedCondition::new(self, other, Combinator::And)
+    }
+
+    /// Returns a run condition that is `true` if either `self` or `other` is `true`.
+    ///
+    /// `other` is not evaluated if `self` is `true`.
+    fn or<OtherParams, Other: IntoRunCondition<OtherParams>>(
+        self,
+        other: Other,
+    ) -> CombinedCondition<Self::System, Other::System> {
+        CombinedCondition::new(self, other, Combinator::Or)
+    }
+}
+
+impl<Params, S> IntoRunCondition<Params> for S where S: IntoSystem<(), bool, Params> {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    And,
+    Or,
+}
+
+/// A run condition made of two other run conditions, created by [`IntoRunCondition::and`] or
+/// [`IntoRunCondition::or`].
+pub struct CombinedCondition<A, B> {
+    condition_a: A,
+    condition_b: B,
+    combinator: Combinator,
+    name: Cow<'static, str>,
+    component_access: Access<ComponentId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+}
+
+impl<A, B> CombinedCondition<A, B>
+where
+    A: System<In = (), Out = bool>,
+    B: System<In = (), Out = bool>,
+{
+    fn new<ParamsA, ParamsB>(
+        condition_a: impl IntoSystem<(), bool, ParamsA, System = A>,
+        condition_b: impl IntoSystem<(), bool, ParamsB, System = B>,
+        combinator: Combinator,
+    ) -> Self {
+        let condition_a = IntoSystem::into_system(condition_a);
+        let condition_b = IntoSystem::into_system(condition_b);
+        Self {
+            name: Cow::Owned(format!(
+                "{:?}({}, {})",
+                combinator,
+                condition_a.name(),
+                condition_b.name()
+            )),
+            condition_a,
+            condition_b,
+            combinator,
+            component_access: Default::default(),
+            archetype_component_access: Default::default(),
+        }
+    }
+}
+
+impl<A, B> System for CombinedCondition<A, B>
+where
+    A: System<In = (), Out = bool>,
+    B: System<In = (), Out = bool>,
+{
+    type In = ();
+    type Out = bool;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype) {
+        self.condition_a.new_archetype(archetype);
+        self.condition_b.new_archetype(archetype);
+
+        self.archetype_component_access
+            .extend(self.condition_a.archetype_component_access());
+        self.archetype_component_access
+            .extend(self.condition_b.archetype_component_access());
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        &self.component_access
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    fn is_send(&self) -> bool {
+        self.condition_a.is_send() && self.condition_b.is_send()
+    }
+
+    unsafe fn run_unsafe(&mut self, _input: (), world: &World) -> bool {
+        let a = self.condition_a.run_unsafe((), world);
+        match self.combinator {
+            Combinator::And => a && self.condition_b.run_unsafe((), world),
+            Combinator::Or => a || self.condition_b.run_unsafe((), world),
+        }
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.condition_a.apply_buffers(world);
+        self.condition_b.apply_buffers(world);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.condition_a.initialize(world);
+        self.condition_b.initialize(world);
+        self.component_access
+            .extend(self.condition_a.component_access());
+        self.component_access
+            .extend(self.condition_b.component_access());
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.condition_a.check_change_tick(change_tick);
+        self.condition_b.check_change_tick(change_tick);
+    }
+}
+
+fn should_run_if(In(condition): In<bool>) -> ShouldRun {
+    if condition {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+/// Turns a run condition into run criteria that can be evaluated by a
+/// [`SystemStage`](crate::schedule::SystemStage).
+pub(crate) fn run_condition_criteria<Params>(
+    condition: impl IntoRunCondition<Params>,
+) -> BoxedSystem<(), ShouldRun> {
+    Box::new(condition.chain(should_run_if))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schedule::{
+            ExclusiveSystemDescriptorCoercion, IntoRunCondition, ParallelSystemDescriptorCoercion,
+            Stage, SystemStage,
+        },
+        system::{IntoExclusiveSystem, Local, Res, ResMut},
+        world::World,
+    };
+
+    fn every_other_time(mut has_ran: Local<bool>) -> bool {
+        *has_ran = !*has_ran;
+        *has_ran
+    }
+
+    fn always() -> bool {
+        true
+    }
+
+    fn never() -> bool {
+        false
+    }
+
+    fn push(value: usize) -> impl FnMut(ResMut<Vec<usize>>) {
+        move |mut values: ResMut<Vec<usize>>| values.push(value)
+    }
+
+    #[test]
+    fn run_if() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut stage = SystemStage::parallel()
+            .with_system(push(0).run_if(every_other_time).label("0"))
+            .with_system(push(1).run_if(never).after("0"))
+            .with_system(
+                (|world: &mut World| world.get_resource_mut::<Vec<usize>>().unwrap().push(2))
+                    .exclusive_system()
+                    .run_if(always)
+                    .at_end(),
+            );
+        for _ in 0..4 {
+            stage.run(&mut world);
+        }
+        assert_eq!(
+            *world.get_resource::<Vec<usize>>().unwrap(),
+            vec![0, 2, 2, 0, 2, 2]
+        );
+    }
+
+    #[test]
+    fn combined_conditions() {
+        struct Flag(bool);
+
+        fn flag(flag: Res<Flag>) -> bool {
+            flag.0
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        world.insert_resource(Flag(false));
+        let mut stage = SystemStage::single_threaded()
+            .with_system(push(0).run_if(flag.and(always)).label("0"))
+            .with_system(push(1).run_if(flag.or(always)).label("1").after("0"))
+            .with_system(push(2).run_if(never.or(flag.and(never))).after("1"))
+            .with_system(push(3).run_if(always.and(never.or(always))).after("1"));
+        stage.run(&mut world);
+        world.get_resource_mut::<Flag>().unwrap().0 = true;
+        stage.run(&mut world);
+        assert_eq!(
+            *world.get_resource::<Vec<usize>>().unwrap(),
+            vec![1, 3, 0, 1, 3]
+        );
+    }
+}
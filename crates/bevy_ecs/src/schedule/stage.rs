@@ -3,11 +3,12 @@ use crate::{
     prelude::IntoSystem,
     schedule::{
         graph_utils::{self, DependencyGraphError},
-        BoxedRunCriteria, BoxedRunCriteriaLabel, BoxedSystemLabel, DuplicateLabelStrategy,
-        ExclusiveSystemContainer, GraphNode, InsertionPoint, ParallelExecutor,
-        ParallelSystemContainer, ParallelSystemExecutor, RunCriteriaContainer,
-        RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel, RunCriteriaInner, ShouldRun,
-        SingleThreadedExecutor, SystemContainer, SystemDescriptor, SystemSet,
+        BoxedRunCriteria, BoxedRunCriteriaLabel, BoxedSystemLabel, DenyExecutionOrderAmbiguities,
+        DuplicateLabelStrategy, ExclusiveSystemContainer, ExecutionOrderAmbiguity, GraphNode,
+        InsertionPoint, ParallelExecutor, ParallelSystemContainer, ParallelSystemExecutor,
+        RunCriteriaContainer, RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel,
+        RunCriteriaInner, RunCriteriaNode, ShouldRun, SingleThreadedExecutor, StageGraph,
+        SystemContainer, SystemDescriptor, SystemKind, SystemNode, SystemSet,
     },
     world::{World, WorldId},
 };
@@ -46,6 +47,9 @@ impl_downcast!(Stage);
 ///
 /// The checker may report a system more times than the amount of constraints it would actually need
 /// to have unambiguous order with regards to a group of already-constrained systems.
+///
+/// The ambiguities can also be inspected with [`SystemStage::ambiguities`], or turned into a panic
+/// with the [`DenyExecutionOrderAmbiguities`] resource.
 pub struct ReportExecutionOrderAmbiguities;
 
 /// Stores and executes systems. Execution order is not defined unless explicitly specified;
@@ -441,31 +445,6 @@ impl SystemStage {
                 && self.uninitialized_before_commands.is_empty()
                 && self.uninitialized_at_end.is_empty()
         );
-        fn unwrap_dependency_cycle_error<Node: GraphNode, Output, Labels: Debug>(
-            result: Result<Output, DependencyGraphError<Labels>>,
-            nodes: &[Node],
-            nodes_description: &'static str,
-        ) -> Output {
-            match result {
-                Ok(output) => output,
-                Err(DependencyGraphError::GraphCycles(cycle)) => {
-                    use std::fmt::Write;
-                    let mut message = format!("Found a dependency cycle in {}:", nodes_description);
-                    writeln!(message).unwrap();
-                    for (index, labels) in &cycle {
-                        writeln!(message, " - {}", nodes[*index].name()).unwrap();
-                        writeln!(
-                            message,
-                            "    wants to be after (because of labels: {:?})",
-                            labels,
-                        )
-                        .unwrap();
-                    }
-                    writeln!(message, " - {}", cycle[0].0).unwrap();
-                    panic!("{}", message);
-                }
-            }
-        }
         let run_criteria_labels = unwrap_dependency_cycle_error(
             self.process_run_criteria(),
             &self.run_criteria,
@@ -493,72 +472,92 @@ impl SystemStage {
         );
     }
 
-    /// Logs execution order ambiguities between systems. System orders must be fresh.
-    fn report_ambiguities(&self, world: &World) {
+    /// Returns the execution order ambiguities between systems. System orders must be fresh.
+    fn find_ambiguities(&self, world: &World) -> Vec<ExecutionOrderAmbiguity> {
         debug_assert!(!self.systems_modified);
-        use std::fmt::Write;
-        fn write_display_names_of_pairs(
-            string: &mut String,
+        fn describe(
+            ambiguities: &mut Vec<ExecutionOrderAmbiguity>,
             systems: &[impl SystemContainer],
-            mut ambiguities: Vec<(usize, usize, Vec<ComponentId>)>,
+            kind: SystemKind,
             world: &World,
         ) {
-            for (index_a, index_b, conflicts) in ambiguities.drain(..) {
-                writeln!(
-                    string,
-                    " -- {:?} and {:?}",
-                    systems[index_a].name(),
-                    systems[index_b].name()
-                )
-                .unwrap();
-                if !conflicts.is_empty() {
-                    let names = conflicts
+            for (index_a, index_b, conflicts) in find_ambiguities(systems) {
+                ambiguities.push(ExecutionOrderAmbiguity {
+                    stage: None,
+                    kind,
+                    system_a: systems[index_a].name().to_string(),
+                    system_b: systems[index_b].name().to_string(),
+                    conflicts: conflicts
                         .iter()
-                        .map(|id| world.components().get_info(*id).unwrap().name())
-                        .collect::<Vec<_>>();
-                    writeln!(string, "    conflicts: {:?}", names).unwrap();
-                }
+                        .map(|id| world.components().get_info(*id).unwrap().name().to_string())
+                        .collect(),
+                });
             }
         }
-        let parallel = find_ambiguities(&self.parallel);
-        let at_start = find_ambiguities(&self.exclusive_at_start);
-        let before_commands = find_ambiguities(&self.exclusive_before_commands);
-        let at_end = find_ambiguities(&self.exclusive_at_end);
-        if !(parallel.is_empty()
-            && at_start.is_empty()
-            && before_commands.is_empty()
-            && at_end.is_empty())
-        {
-            let mut string = "Execution order ambiguities detected, you might want to \
-                    add an explicit dependency relation between some of these systems:\n"
-                .to_owned();
-            if !parallel.is_empty() {
-                writeln!(string, " * Parallel systems:").unwrap();
-                write_display_names_of_pairs(&mut string, &self.parallel, parallel, world);
-            }
-            if !at_start.is_empty() {
-                writeln!(string, " * Exclusive systems at start of stage:").unwrap();
-                write_display_names_of_pairs(
-                    &mut string,
-                    &self.exclusive_at_start,
-                    at_start,
-                    world,
-                );
-            }
-            if !before_commands.is_empty() {
-                writeln!(string, " * Exclusive systems before commands of stage:").unwrap();
-                write_display_names_of_pairs(
-                    &mut string,
-                    &self.exclusive_before_commands,
-                    before_commands,
-                    world,
+        let mut ambiguities = Vec::new();
+        describe(
+            &mut ambiguities,
+            &self.parallel,
+            SystemKind::Parallel,
+            world,
+        );
+        describe(
+            &mut ambiguities,
+            &self.exclusive_at_start,
+            SystemKind::ExclusiveAtStart,
+            world,
+        );
+        describe(
+            &mut ambiguities,
+            &self.exclusive_before_commands,
+            SystemKind::ExclusiveBeforeCommands,
+            world,
+        );
+        describe(
+            &mut ambiguities,
+            &self.exclusive_at_end,
+            SystemKind::ExclusiveAtEnd,
+            world,
+        );
+        ambiguities
+    }
+
+    /// Logs the execution order ambiguities between systems if the
+    /// [`ReportExecutionOrderAmbiguities`] resource exists, and panics if the
+    /// [`DenyExecutionOrderAmbiguities`] resource exists and they are not all ignored. System
+    /// orders must be fresh.
+    fn check_ambiguities(&self, world: &World) {
+        let report = world.contains_resource::<ReportExecutionOrderAmbiguities>();
+        let deny = world.get_resource::<DenyExecutionOrderAmbiguities>();
+        if !report && deny.is_none() {
+            return;
+        }
+        let ambiguities = self.find_ambiguities(world);
+        if report && !ambiguities.is_empty() {
+            info!(
+                "{}",
+                describe_ambiguities(
+                    "Execution order ambiguities detected, you might want to \
+                    add an explicit dependency relation between some of these systems:",
+                    ambiguities.iter()
+                )
+            );
+        }
+        if let Some(deny) = deny {
+            let mut denied = ambiguities
+                .iter()
+                .filter(|ambiguity| !deny.is_ignored(ambiguity))
+                .peekable();
+            if denied.peek().is_some() {
+                panic!(
+                    "{}",
+                    describe_ambiguities(
+                        "Execution order ambiguities are denied by `DenyExecutionOrderAmbiguities`, \
+                        add an explicit dependency relation between these systems:",
+                        denied
+                    )
                 );
             }
-            if !at_end.is_empty() {
-                writeln!(string, " * Exclusive systems at end of stage:").unwrap();
-                write_display_names_of_pairs(&mut string, &self.exclusive_at_end, at_end, world);
-            }
-            info!("{}", string);
         }
     }
 
@@ -685,6 +684,67 @@ fn process_systems(
     Ok(())
 }
 
+/// Unwraps the result of sorting a dependency graph, panicking with a readable description of
+/// the cycle if one was found.
+pub(super) fn unwrap_dependency_cycle_error<Node: GraphNode, Output, Labels: Debug>(
+    result: Result<Output, DependencyGraphError<Labels>>,
+    nodes: &[Node],
+    nodes_description: &'static str,
+) -> Output {
+    match result {
+        Ok(output) => output,
+        Err(DependencyGraphError::GraphCycles(cycle)) => {
+            use std::fmt::Write;
+            let mut message = format!("Found a dependency cycle in {}:", nodes_description);
+            writeln!(message).unwrap();
+            for (index, labels) in &cycle {
+                writeln!(message, " - {}", nodes[*index].name()).unwrap();
+                writeln!(
+                    message,
+                    "    wants to be after (because of labels: {:?})",
+                    labels,
+                )
+                .unwrap();
+            }
+            writeln!(message, " - {}", cycle[0].0).unwrap();
+            panic!("{}", message);
+        }
+    }
+}
+
+/// Lists `ambiguities` under `header`, under a heading for each kind of system. The ambiguities
+/// must be sorted by kind.
+fn describe_ambiguities<'a>(
+    header: &str,
+    ambiguities: impl Iterator<Item = &'a ExecutionOrderAmbiguity>,
+) -> String {
+    use std::fmt::Write;
+    let mut string = format!("{}\n", header);
+    let mut kind = None;
+    for ambiguity in ambiguities {
+        if kind != Some(ambiguity.kind) {
+            kind = Some(ambiguity.kind);
+            let heading = match ambiguity.kind {
+                SystemKind::Parallel => "Parallel systems",
+                SystemKind::ExclusiveAtStart => "Exclusive systems at start of stage",
+                SystemKind::ExclusiveBeforeCommands => "Exclusive systems before commands of stage",
+                SystemKind::ExclusiveAtEnd => "Exclusive systems at end of stage",
+            };
+            writeln!(string, " * {}:", heading).unwrap();
+        }
+        writeln!(
+            string,
+            " -- {:?} and {:?}",
+            ambiguity.system_a, ambiguity.system_b
+        )
+        .unwrap();
+        if !ambiguity.conflicts.is_empty() {
+            writeln!(string, "    conflicts: {:?}", ambiguity.conflicts).unwrap();
+        }
+    }
+    string
+}
+
 /// Returns vector containing all pairs of indices of systems with ambiguous execution order,
 /// along with specific components that have triggered the warning.
 /// Systems must be topologically sorted beforehand.
@@ -761,8 +821,9 @@ fn find_ambiguities(systems: &[impl SystemContainer]) -> Vec<(usize, usize, Vec<
     ambiguities
 }
 
-impl Stage for SystemStage {
-    fn run(&mut self, world: &mut World) {
+impl SystemStage {
+    /// Initializes new systems and rebuilds the orders of the systems if they were modified.
+    fn prepare(&mut self, world: &mut World) {
         if let Some(world_id) = self.world_id {
             assert!(
                 world.id() == world_id,
@@ -778,13 +839,92 @@ impl Stage for SystemStage {
             self.systems_modified = false;
             self.executor.rebuild_cached_data(&self.parallel);
             self.executor_modified = false;
-            if world.contains_resource::<ReportExecutionOrderAmbiguities>() {
-                self.report_ambiguities(world);
-            }
+            self.check_ambiguities(world);
         } else if self.executor_modified {
             self.executor.rebuild_cached_data(&self.parallel);
             self.executor_modified = false;
         }
+    }
+
+    /// Returns the pairs of systems of this stage with ambiguous execution order, see
+    /// [`ReportExecutionOrderAmbiguities`].
+    ///
+    /// New systems are initialized and the orders of the systems are rebuilt first, like when
+    /// the stage runs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stage was already run on another [`World`], if there is a cycle in the
+    /// ordering constraints of its systems, or if the stage had to be rebuilt and `world`
+    /// contains a [`DenyExecutionOrderAmbiguities`] resource that denies the ambiguities.
+    pub fn ambiguities(&mut self, world: &mut World) -> Vec<ExecutionOrderAmbiguity> {
+        self.prepare(world);
+        self.find_ambiguities(world)
+    }
+
+    /// Returns the resolved graph of the systems of this stage, with the data they access in
+    /// `world`.
+    ///
+    /// New systems are initialized and the orders of the systems are rebuilt first, like when
+    /// the stage runs, so the graph is accurate even if the stage has not run yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stage was already run on another [`World`], or if there is a cycle in the
+    /// ordering constraints of its systems.
+    pub fn graph(&mut self, world: &mut World) -> StageGraph {
+        self.prepare(world);
+        let mut systems = Vec::with_capacity(
+            self.exclusive_at_start.len()
+                + self.parallel.len()
+                + self.exclusive_before_commands.len()
+                + self.exclusive_at_end.len(),
+        );
+        fn add_systems(
+            systems: &mut Vec<SystemNode>,
+            containers: &[impl SystemContainer],
+            kind: SystemKind,
+            world: &World,
+        ) {
+            let offset = systems.len();
+            systems.extend(
+                containers
+                    .iter()
+                    .map(|container| SystemNode::new(container, kind, offset, world)),
+            );
+        }
+        add_systems(
+            &mut systems,
+            &self.exclusive_at_start,
+            SystemKind::ExclusiveAtStart,
+            world,
+        );
+        add_systems(&mut systems, &self.parallel, SystemKind::Parallel, world);
+        add_systems(
+            &mut systems,
+            &self.exclusive_before_commands,
+            SystemKind::ExclusiveBeforeCommands,
+            world,
+        );
+        add_systems(
+            &mut systems,
+            &self.exclusive_at_end,
+            SystemKind::ExclusiveAtEnd,
+            world,
+        );
+        StageGraph {
+            label: None,
+            stage_run_criteria: self.stage_run_criteria.name().map(|name| name.to_string()),
+            run_criteria: self.run_criteria.iter().map(RunCriteriaNode::new).collect(),
+            systems,
+            schedule: None,
+        }
+    }
+}
+
+impl Stage for SystemStage {
+    fn run(&mut self, world: &mut World) {
+        self.prepare(world);
 
         let mut run_stage_loop = true;
         while run_stage_loop {
@@ -950,9 +1090,10 @@ mod tests {
         entity::Entity,
         query::{ChangeTrackers, Changed},
         schedule::{
-            BoxedSystemLabel, ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
-            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaPiping, ShouldRun,
-            SingleThreadedExecutor, Stage, SystemSet, SystemStage,
+            BoxedSystemLabel, DenyExecutionOrderAmbiguities, ExclusiveSystemDescriptorCoercion,
+            ParallelSystemDescriptorCoercion, RunCriteria, RunCriteriaDescriptorCoercion,
+            RunCriteriaPiping, ShouldRun, SingleThreadedExecutor, Stage, SystemKind, SystemSet,
+            SystemStage,
         },
         system::{In, IntoExclusiveSystem, IntoSystem, Local, Query, ResMut},
         world::World,
@@ -1956,6 +2097,70 @@ mod tests {
         assert_eq!(ambiguities.len(), 0);
     }
 
+    #[test]
+    fn structured_ambiguities() {
+        fn read(_: Query<&W<f32>>) {}
+        fn write(_: ResMut<usize>, _: Query<&mut W<f32>>) {}
+        fn empty() {}
+
+        let mut world = World::new();
+        world.insert_resource(0usize);
+        let mut stage = SystemStage::parallel()
+            .with_system(read)
+            .with_system(write)
+            .with_system(empty.exclusive_system())
+            .with_system(empty.exclusive_system());
+        let mut ambiguities = stage.ambiguities(&mut world);
+        assert_eq!(ambiguities.len(), 2);
+        let exclusive = ambiguities.pop().unwrap();
+        assert_eq!(exclusive.kind, SystemKind::ExclusiveAtStart);
+        assert!(exclusive.conflicts.is_empty());
+        let parallel = ambiguities.pop().unwrap();
+        assert_eq!(parallel.kind, SystemKind::Parallel);
+        assert_eq!(parallel.stage, None);
+        let mut systems = [parallel.system_a, parallel.system_b];
+        systems.sort();
+        assert_eq!(
+            systems,
+            [
+                "bevy_ecs::schedule::stage::tests::structured_ambiguities::read",
+                "bevy_ecs::schedule::stage::tests::structured_ambiguities::write",
+            ]
+        );
+        assert_eq!(
+            parallel.conflicts,
+            vec![std::any::type_name::<W<f32>>().to_string()]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn deny_ambiguities() {
+        fn write(_: ResMut<usize>) {}
+
+        let mut world = World::new();
+        world.insert_resource(0usize);
+        world.insert_resource(DenyExecutionOrderAmbiguities::default().ignore_crate("bevy_render"));
+        let mut stage = SystemStage::parallel()
+            .with_system(write)
+            .with_system(write);
+        stage.run(&mut world);
+    }
+
+    #[test]
+    fn deny_ambiguities_ignored_crate() {
+        fn write(_: ResMut<usize>) {}
+
+        let mut world = World::new();
+        world.insert_resource(0usize);
+        world.insert_resource(DenyExecutionOrderAmbiguities::default().ignore_crate("bevy_ecs"));
+        let mut stage = SystemStage::parallel()
+            .with_system(write)
+            .with_system(write);
+        stage.run(&mut world);
+        assert_eq!(stage.ambiguities(&mut world).len(), 1);
+    }
+
     #[test]
     #[should_panic]
     fn multiple_worlds_same_stage() {
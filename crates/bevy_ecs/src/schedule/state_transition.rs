@@ -0,0 +1,744 @@
+use crate::{
+    event::Events,
+    schedule::{IntoSystemDescriptor, Stage, StateData, SystemStage},
+    system::Res,
+    world::World,
+};
+use bevy_ecs_macros::all_tuples;
+use bevy_utils::HashMap;
+use downcast_rs::{impl_downcast, Downcast};
+use std::any::TypeId;
+
+/// The current value of the state `S`, driven by a [`StateTransitionStage`].
+///
+/// The resource only exists while the state does: it is inserted the first time the stage runs,
+/// and removed while the parent of a [`SubState`] is in a state without it, or while a
+/// [`ComputedState`] computes to `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentState<S: StateData>(S);
+
+impl<S: StateData> CurrentState<S> {
+    pub fn get(&self) -> &S {
+        &self.0
+    }
+}
+
+/// Queues a transition of the state `S`, applied the next time the [`StateTransitionStage`]
+/// runs.
+///
+/// Computed states cannot be queued, and a transition queued while a sub-state doesn't exist is
+/// discarded.
+#[derive(Debug)]
+pub struct NextState<S: StateData>(Option<S>);
+
+impl<S: StateData> Default for NextState<S> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<S: StateData> NextState<S> {
+    /// Queues a transition to `state`, replacing any transition queued before.
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+
+    /// Returns the queued state, if any.
+    pub fn get(&self) -> Option<&S> {
+        self.0.as_ref()
+    }
+}
+
+/// An event sent by the [`StateTransitionStage`] each time the state `S` changes.
+///
+/// `from` is `None` when the state starts existing, and `to` is `None` when it stops existing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateTransition<S: StateData> {
+    pub from: Option<S>,
+    pub to: Option<S>,
+}
+
+/// A state that only exists while its parent state is in some of its values.
+pub trait SubState: StateData {
+    type Parent: StateData;
+
+    /// Returns the value the state takes when it starts existing with its parent in `parent`,
+    /// or `None` if it doesn't exist while its parent is in `parent`.
+    fn initial(parent: &Self::Parent) -> Option<Self>;
+}
+
+/// A state derived from the values of other states, recomputed each time the
+/// [`StateTransitionStage`] runs.
+pub trait ComputedState: StateData {
+    /// The states this state is computed from, as a tuple.
+    type Sources: StateSources;
+
+    /// Returns the value of the state, or `None` if it doesn't exist.
+    fn compute(sources: <Self::Sources as StateSources>::Values) -> Option<Self>;
+}
+
+/// A tuple of states a [`ComputedState`] is computed from.
+pub trait StateSources {
+    /// The current values of the states, `None` for those that don't exist.
+    type Values;
+
+    fn values(world: &World) -> Self::Values;
+
+    /// Returns `true` if all the states were added to `stage`.
+    fn added_to(stage: &StateTransitionStage) -> bool;
+}
+
+macro_rules! impl_state_sources {
+    ($($state: ident),*) => {
+        impl<$($state: StateData),*> StateSources for ($($state,)*) {
+            type Values = ($(Option<$state>,)*);
+
+            #[allow(unused_variables, clippy::unused_unit)]
+            fn values(world: &World) -> Self::Values {
+                ($(world
+                    .get_resource::<CurrentState<$state>>()
+                    .map(|state| state.0.clone()),)*)
+            }
+
+            #[allow(unused_variables)]
+            fn added_to(stage: &StateTransitionStage) -> bool {
+                true $(&& stage.contains_state::<$state>())*
+            }
+        }
+    };
+}
+
+all_tuples!(impl_state_sources, 0, 8, S);
+
+/// Returns a run condition that is `true` while the state `S` is `state`.
+pub fn in_state<S: StateData>(
+    state: S,
+) -> impl FnMut(Option<Res<CurrentState<S>>>) -> bool + Send + Sync + 'static {
+    move |current: Option<Res<CurrentState<S>>>| matches!(current, Some(current) if current.0 == state)
+}
+
+/// A [`Stage`] that applies the transitions of a set of states, and runs the systems added
+/// when entering and exiting their values.
+///
+/// Each state is stored in a [`CurrentState`] resource, and transitions are queued with the
+/// [`NextState`] resource. When it runs, the stage computes the new values of the states in the
+/// order they were added, so a [`SubState`] or a [`ComputedState`] sees the new values of the
+/// states it depends on. It then runs the exit systems of the states that changed, in reverse
+/// order and while the [`CurrentState`] resources still hold the previous values, updates the
+/// [`CurrentState`] resources, and runs the enter systems of the states that changed, sending a
+/// [`StateTransition`] event for each of them.
+///
+/// The stage inserts and updates the `Events<StateTransition<S>>` resources itself, so they
+/// shouldn't be added to the `App` separately.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{
+///     prelude::*,
+///     schedule::{StateTransitionStage, SubState},
+/// };
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// enum AppState {
+///     Menu,
+///     InGame,
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// enum GameState {
+///     Running,
+///     Paused,
+/// }
+///
+/// impl SubState for GameState {
+///     type Parent = AppState;
+///
+///     fn initial(parent: &AppState) -> Option<Self> {
+///         match parent {
+///             AppState::InGame => Some(GameState::Running),
+///             AppState::Menu => None,
+///         }
+///     }
+/// }
+///
+/// struct Level(u32);
+///
+/// fn load_level(mut level: ResMut<Level>) {
+///     level.0 += 1;
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(Level(0));
+///
+/// let mut stage = StateTransitionStage::new()
+///     .with_state(AppState::Menu)
+///     .with_sub_state::<GameState>()
+///     .with_enter_system(GameState::Running, load_level);
+///
+/// stage.run(&mut world);
+/// assert!(world.get_resource::<CurrentState<GameState>>().is_none());
+///
+/// world.resource_mut::<NextState<AppState>>().set(AppState::InGame);
+/// stage.run(&mut world);
+/// assert_eq!(
+///     world.resource::<CurrentState<GameState>>().get(),
+///     &GameState::Running
+/// );
+/// assert_eq!(world.resource::<Level>().0, 1);
+/// ```
+#[derive(Default)]
+pub struct StateTransitionStage {
+    drivers: Vec<Box<dyn StateDriver>>,
+}
+
+impl StateTransitionStage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_state<S: StateData>(mut self, initial: S) -> Self {
+        self.add_state(initial);
+        self
+    }
+
+    /// Adds the state `S`, which starts in `initial` the first time the stage runs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` was already added to this stage.
+    pub fn add_state<S: StateData>(&mut self, initial: S) -> &mut Self {
+        self.add_driver(move |world: &mut World, current: Option<&S>| {
+            take_next_state(world)
+                .or_else(|| current.cloned())
+                .or_else(|| Some(initial.clone()))
+        })
+    }
+
+    #[must_use]
+    pub fn with_sub_state<S: SubState>(mut self) -> Self {
+        self.add_sub_state::<S>();
+        self
+    }
+
+    /// Adds the sub-state `S`, which exists while its parent is in a value for which
+    /// [`SubState::initial`] returns `Some`. It keeps its value while its parent changes
+    /// between such values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` was already added to this stage, or if its parent wasn't.
+    pub fn add_sub_state<S: SubState>(&mut self) -> &mut Self {
+        if !self.contains_state::<S::Parent>() {
+            panic!(
+                "The parent of the sub-state {} must be added to the stage first.",
+                std::any::type_name::<S>()
+            );
+        }
+        self.add_driver(|world: &mut World, current: Option<&S>| {
+            let next = take_next_state(world);
+            let initial = world
+                .get_resource::<CurrentState<S::Parent>>()
+                .and_then(|parent| S::initial(&parent.0))?;
+            next.or_else(|| current.cloned()).or(Some(initial))
+        })
+    }
+
+    #[must_use]
+    pub fn with_computed_state<S: ComputedState>(mut self) -> Self {
+        self.add_computed_state::<S>();
+        self
+    }
+
+    /// Adds the computed state `S`, after the states it is computed from.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` was already added to this stage, or if one of the states it is computed from
+    /// wasn't.
+    pub fn add_computed_state<S: ComputedState>(&mut self) -> &mut Self {
+        if !S::Sources::added_to(self) {
+            panic!(
+                "The sources of the computed state {} must be added to the stage first.",
+                std::any::type_name::<S>()
+            );
+        }
+        self.add_driver(|world: &mut World, _: Option<&S>| S::compute(S::Sources::values(world)))
+    }
+
+    #[must_use]
+    pub fn with_enter_system<S: StateData, Params>(
+        mut self,
+        state: S,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> Self {
+        self.add_enter_system(state, system);
+        self
+    }
+
+    /// Adds a system that runs each time the state `S` enters `state`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` wasn't added to this stage.
+    pub fn add_enter_system<S: StateData, Params>(
+        &mut self,
+        state: S,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        self.driver_mut::<S>()
+            .enter
+            .entry(state)
+            .or_insert_with(SystemStage::single_threaded)
+            .add_system(system);
+        self
+    }
+
+    #[must_use]
+    pub fn with_exit_system<S: StateData, Params>(
+        mut self,
+        state: S,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> Self {
+        self.add_exit_system(state, system);
+        self
+    }
+
+    /// Adds a system that runs each time the state `S` exits `state`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `S` wasn't added to this stage.
+    pub fn add_exit_system<S: StateData, Params>(
+        &mut self,
+        state: S,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        self.driver_mut::<S>()
+            .exit
+            .entry(state)
+            .or_insert_with(SystemStage::single_threaded)
+            .add_system(system);
+        self
+    }
+
+    fn contains_state<S: StateData>(&self) -> bool {
+        self.drivers
+            .iter()
+            .any(|driver| driver.is::<StateDriverOf<S>>())
+    }
+
+    fn add_driver<S: StateData>(
+        &mut self,
+        resolve: impl Fn(&mut World, Option<&S>) -> Option<S> + Send + Sync + 'static,
+    ) -> &mut Self {
+        if self.contains_state::<S>() {
+            panic!(
+                "The state {} was already added to the stage.",
+                std::any::type_name::<S>()
+            );
+        }
+        self.drivers.push(Box::new(StateDriverOf {
+            resolve: Box::new(resolve),
+            enter: HashMap::default(),
+            exit: HashMap::default(),
+            transition: None,
+        }));
+        self
+    }
+
+    fn driver_mut<S: StateData>(&mut self) -> &mut StateDriverOf<S> {
+        self.drivers
+            .iter_mut()
+            .find_map(|driver| driver.downcast_mut::<StateDriverOf<S>>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "The state {} wasn't added to the stage.",
+                    std::any::type_name::<S>()
+                )
+            })
+    }
+}
+
+impl Stage for StateTransitionStage {
+    fn run(&mut self, world: &mut World) {
+        // the new values are written as they are computed, for the states that depend on them,
+        // and the previous values are put back while the exit systems run
+        for driver in self.drivers.iter_mut() {
+            driver.compute_transition(world);
+        }
+        for driver in self.drivers.iter_mut().rev() {
+            driver.set_current_state(world, false);
+        }
+        for driver in self.drivers.iter_mut().rev() {
+            driver.run_exit_systems(world);
+        }
+        for driver in self.drivers.iter_mut() {
+            driver.set_current_state(world, true);
+        }
+        for driver in self.drivers.iter_mut() {
+            driver.run_enter_systems(world);
+            driver.send_transition_event(world);
+        }
+    }
+}
+
+fn take_next_state<S: StateData>(world: &mut World) -> Option<S> {
+    world
+        .get_resource_or_insert_with(NextState::<S>::default)
+        .0
+        .take()
+}
+
+trait StateDriver: Downcast + Send + Sync {
+    /// Computes the new value of the state and sets the [`CurrentState`] resource to it, and
+    /// remembers the transition if the state changed.
+    fn compute_transition(&mut self, world: &mut World);
+    /// Sets the [`CurrentState`] resource to the value after the transition if `to`, or else to
+    /// the value before it. Does nothing if the state didn't change.
+    fn set_current_state(&mut self, world: &mut World, to: bool);
+    fn run_exit_systems(&mut self, world: &mut World);
+    fn run_enter_systems(&mut self, world: &mut World);
+    fn send_transition_event(&mut self, world: &mut World);
+}
+
+impl_downcast!(StateDriver);
+
+#[allow(clippy::type_complexity)]
+struct StateDriverOf<S: StateData> {
+    /// Returns the new value of the state from its current value.
+    resolve: Box<dyn Fn(&mut World, Option<&S>) -> Option<S> + Send + Sync>,
+    enter: HashMap<S, SystemStage>,
+    exit: HashMap<S, SystemStage>,
+    transition: Option<StateTransition<S>>,
+}
+
+impl<S: StateData> StateDriver for StateDriverOf<S> {
+    fn compute_transition(&mut self, world: &mut World) {
+        world
+            .get_resource_or_insert_with(Events::<StateTransition<S>>::default)
+            .update();
+        let from = world
+            .get_resource::<CurrentState<S>>()
+            .map(|state| state.0.clone());
+        let to = (self.resolve)(world, from.as_ref());
+        if from == to {
+            return;
+        }
+        self.transition = Some(StateTransition { from, to });
+        self.set_current_state(world, true);
+    }
+
+    fn set_current_state(&mut self, world: &mut World, to: bool) {
+        let state = match &self.transition {
+            Some(transition) if to => &transition.to,
+            Some(transition) => &transition.from,
+            None => return,
+        };
+        match state {
+            Some(state) => world.insert_resource(CurrentState(state.clone())),
+            None if to => {
+                world.remove_resource::<CurrentState<S>>();
+            }
+            // the state didn't exist before the transition: restoring that isn't a removal of the
+            // resource, so it is kept out of `World::is_resource_removed`
+            None => {
+                let was_removed = world.is_resource_removed::<CurrentState<S>>();
+                world.remove_resource::<CurrentState<S>>();
+                if !was_removed {
+                    let component_id = world
+                        .components
+                        .get_resource_id(TypeId::of::<CurrentState<S>>())
+                        .unwrap();
+                    world.removed_resources.retain(|id| *id != component_id);
+                }
+            }
+        }
+    }
+
+    fn run_exit_systems(&mut self, world: &mut World) {
+        if let Some(StateTransition {
+            from: Some(from), ..
+        }) = &self.transition
+        {
+            if let Some(stage) = self.exit.get_mut(from) {
+                stage.run(world);
+            }
+        }
+    }
+
+    fn run_enter_systems(&mut self, world: &mut World) {
+        if let Some(StateTransition { to: Some(to), .. }) = &self.transition {
+            if let Some(stage) = self.enter.get_mut(to) {
+                stage.run(world);
+            }
+        }
+    }
+
+    fn send_transition_event(&mut self, world: &mut World) {
+        if let Some(transition) = self.transition.take() {
+            world
+                .resource_mut::<Events<StateTransition<S>>>()
+                .send(transition);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum AppState {
+        Menu,
+        InGame,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum GameState {
+        Running,
+        Paused,
+    }
+
+    impl SubState for GameState {
+        type Parent = AppState;
+
+        fn initial(parent: &AppState) -> Option<Self> {
+            match parent {
+                AppState::InGame => Some(GameState::Running),
+                AppState::Menu => None,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Simulating;
+
+    impl ComputedState for Simulating {
+        type Sources = (GameState,);
+
+        fn compute((game,): (Option<GameState>,)) -> Option<Self> {
+            match game {
+                Some(GameState::Running) => Some(Simulating),
+                _ => None,
+            }
+        }
+    }
+
+    fn push(name: &'static str) -> impl FnMut(ResMut<Vec<&'static str>>) {
+        move |mut log: ResMut<Vec<&'static str>>| log.push(name)
+    }
+
+    fn take_log(world: &mut World) -> Vec<&'static str> {
+        std::mem::take(&mut *world.resource_mut::<Vec<&'static str>>())
+    }
+
+    fn set<S: StateData>(world: &mut World, state: S) {
+        world.resource_mut::<NextState<S>>().set(state);
+    }
+
+    fn current<S: StateData>(world: &World) -> Option<S> {
+        world
+            .get_resource::<CurrentState<S>>()
+            .map(|state| state.get().clone())
+    }
+
+    fn stage() -> StateTransitionStage {
+        StateTransitionStage::new()
+            .with_state(AppState::Menu)
+            .with_sub_state::<GameState>()
+            .with_computed_state::<Simulating>()
+            .with_enter_system(AppState::InGame, push("enter InGame"))
+            .with_exit_system(AppState::InGame, push("exit InGame"))
+            .with_enter_system(GameState::Running, push("enter Running"))
+            .with_exit_system(GameState::Running, push("exit Running"))
+            .with_enter_system(Simulating, push("enter Simulating"))
+            .with_exit_system(Simulating, push("exit Simulating"))
+    }
+
+    #[test]
+    fn sub_and_computed_states() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+        let mut stage = stage();
+
+        stage.run(&mut world);
+        assert_eq!(current(&world), Some(AppState::Menu));
+        assert_eq!(current::<GameState>(&world), None);
+        assert_eq!(current::<Simulating>(&world), None);
+        assert!(take_log(&mut world).is_empty());
+
+        // transitions to a sub-state that doesn't exist are discarded
+        set(&mut world, GameState::Paused);
+        stage.run(&mut world);
+        assert_eq!(current::<GameState>(&world), None);
+
+        set(&mut world, AppState::InGame);
+        stage.run(&mut world);
+        assert_eq!(current(&world), Some(GameState::Running));
+        assert_eq!(current(&world), Some(Simulating));
+        assert_eq!(
+            take_log(&mut world),
+            vec!["enter InGame", "enter Running", "enter Simulating"]
+        );
+
+        set(&mut world, GameState::Paused);
+        stage.run(&mut world);
+        assert_eq!(current(&world), Some(GameState::Paused));
+        assert_eq!(current::<Simulating>(&world), None);
+        assert_eq!(
+            take_log(&mut world),
+            vec!["exit Simulating", "exit Running"]
+        );
+
+        set(&mut world, GameState::Running);
+        stage.run(&mut world);
+        take_log(&mut world);
+
+        set(&mut world, AppState::Menu);
+        stage.run(&mut world);
+        assert_eq!(current::<GameState>(&world), None);
+        assert_eq!(current::<Simulating>(&world), None);
+        assert_eq!(
+            take_log(&mut world),
+            vec!["exit Simulating", "exit Running", "exit InGame"]
+        );
+
+        // setting the current value is not a transition
+        set(&mut world, AppState::Menu);
+        stage.run(&mut world);
+        assert!(take_log(&mut world).is_empty());
+    }
+
+    #[test]
+    fn transition_events() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+        let mut stage = stage();
+        let reader = |world: &World| {
+            let events = world.resource::<Events<StateTransition<GameState>>>();
+            let mut reader = events.get_reader();
+            reader.iter(events).cloned().collect::<Vec<_>>()
+        };
+
+        stage.run(&mut world);
+        assert!(reader(&world).is_empty());
+
+        set(&mut world, AppState::InGame);
+        stage.run(&mut world);
+        assert_eq!(
+            reader(&world),
+            vec![StateTransition {
+                from: None,
+                to: Some(GameState::Running)
+            }]
+        );
+
+        set(&mut world, GameState::Paused);
+        stage.run(&mut world);
+        assert_eq!(
+            reader(&world),
+            vec![
+                StateTransition {
+                    from: None,
+                    to: Some(GameState::Running)
+                },
+                StateTransition {
+                    from: Some(GameState::Running),
+                    to: Some(GameState::Paused)
+                }
+            ]
+        );
+
+        stage.run(&mut world);
+        stage.run(&mut world);
+        assert!(reader(&world).is_empty());
+    }
+
+    #[test]
+    fn in_state_condition() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+        let mut transitions = stage();
+        let mut update = SystemStage::single_threaded()
+            .with_system(push("running").run_if(in_state(GameState::Running)));
+
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert!(take_log(&mut world).is_empty());
+
+        set(&mut world, AppState::InGame);
+        transitions.run(&mut world);
+        take_log(&mut world);
+        update.run(&mut world);
+        assert_eq!(take_log(&mut world), vec!["running"]);
+    }
+
+    #[test]
+    fn exit_and_enter_see_current_states() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<(Option<AppState>, Option<GameState>)>::new());
+        let record = |mut log: ResMut<Vec<(Option<AppState>, Option<GameState>)>>,
+                      app: Option<Res<CurrentState<AppState>>>,
+                      game: Option<Res<CurrentState<GameState>>>| {
+            log.push((
+                app.map(|state| state.get().clone()),
+                game.map(|state| state.get().clone()),
+            ));
+        };
+        let mut stage = StateTransitionStage::new()
+            .with_state(AppState::Menu)
+            .with_sub_state::<GameState>()
+            .with_exit_system(GameState::Running, record)
+            .with_enter_system(AppState::Menu, record);
+
+        stage.run(&mut world);
+        set(&mut world, AppState::InGame);
+        stage.run(&mut world);
+        set(&mut world, AppState::Menu);
+        stage.run(&mut world);
+        assert_eq!(
+            *world.resource::<Vec<(Option<AppState>, Option<GameState>)>>(),
+            vec![
+                (Some(AppState::Menu), None),
+                (Some(AppState::InGame), Some(GameState::Running)),
+                (Some(AppState::Menu), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn entering_a_state_is_not_a_removal() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<&'static str>::new());
+        let mut stage = stage();
+        stage.run(&mut world);
+
+        set(&mut world, AppState::InGame);
+        stage.run(&mut world);
+        assert!(!world.is_resource_removed::<CurrentState<GameState>>());
+        assert!(!world.is_resource_removed::<CurrentState<Simulating>>());
+
+        world.clear_trackers();
+        set(&mut world, AppState::Menu);
+        stage.run(&mut world);
+        assert!(world.is_resource_removed::<CurrentState<GameState>>());
+        assert!(world.is_resource_removed::<CurrentState<Simulating>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_state_without_parent() {
+        StateTransitionStage::new().add_sub_state::<GameState>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn computed_state_without_sources() {
+        StateTransitionStage::new()
+            .with_state(AppState::Menu)
+            .add_computed_state::<Simulating>();
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     schedule::{
-        AmbiguitySetLabel, BoxedAmbiguitySetLabel, BoxedSystemLabel, IntoRunCriteria,
-        RunCriteriaDescriptorOrLabel, SystemLabel,
+        run_condition::run_condition_criteria, AmbiguitySetLabel, BoxedAmbiguitySetLabel,
+        BoxedSystemLabel, IntoRunCondition, IntoRunCriteria, RunCriteriaDescriptorOrLabel,
+        SystemLabel,
     },
     system::{BoxedSystem, ExclusiveSystem, ExclusiveSystemCoerced, ExclusiveSystemFn, IntoSystem},
 };
@@ -122,6 +123,16 @@ pub trait ParallelSystemDescriptorCoercion<Params> {
         run_criteria: impl IntoRunCriteria<Marker>,
     ) -> ParallelSystemDescriptor;
 
+    /// Makes the system run only if `condition` returns `true`. This is a shorthand for
+    /// [`with_run_criteria`](Self::with_run_criteria), so it replaces any run criteria assigned
+    /// before; use [`IntoRunCondition::and`] to require several conditions.
+    fn run_if<Marker>(self, condition: impl IntoRunCondition<Marker>) -> ParallelSystemDescriptor
+    where
+        Self: Sized,
+    {
+        self.with_run_criteria(run_condition_criteria(condition))
+    }
+
     /// Assigns a label to the system; there can be more than one, and it doesn't have to be unique.
     fn label(self, label: impl SystemLabel) -> ParallelSystemDescriptor;
 
@@ -258,6 +269,16 @@ pub trait ExclusiveSystemDescriptorCoercion {
         run_criteria: impl IntoRunCriteria<Marker>,
     ) -> ExclusiveSystemDescriptor;
 
+    /// Makes the system run only if `condition` returns `true`. This is a shorthand for
+    /// [`with_run_criteria`](Self::with_run_criteria), so it replaces any run criteria assigned
+    /// before; use [`IntoRunCondition::and`] to require several conditions.
+    fn run_if<Marker>(self, condition: impl IntoRunCondition<Marker>) -> ExclusiveSystemDescriptor
+    where
+        Self: Sized,
+    {
+        self.with_run_criteria(run_condition_criteria(condition))
+    }
+
     /// Assigns a label to the system; there can be more than one, and it doesn't have to be unique.
     fn label(self, label: impl SystemLabel) -> ExclusiveSystemDescriptor;
 
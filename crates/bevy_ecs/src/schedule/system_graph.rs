@@ -0,0 +1,590 @@
+use crate::{
+    schedule::{
+        graph_utils, stage::unwrap_dependency_cycle_error, BoxedRunCriteria, BoxedSystemLabel,
+        ExclusiveSystemContainer, GraphNode, IntoSystemDescriptor, ParallelExecutor,
+        ParallelSystemContainer, ParallelSystemExecutor, RunCriteriaDescriptorOrLabel,
+        RunCriteriaSystem, ShouldRun, SingleThreadedExecutor, Stage, SystemContainer,
+        SystemDescriptor,
+    },
+    world::{World, WorldId},
+};
+use std::{borrow::Cow, ops::Range};
+
+/// An exclusive system that does nothing by itself, but marks an explicit sync point in a
+/// [`SystemGraph`].
+///
+/// Every exclusive system in a [`SystemGraph`] applies the buffers (such as
+/// [`Commands`](crate::system::Commands)) of the systems that ran before it, so ordering systems
+/// around `apply_buffers` guarantees that their commands have been applied:
+///
+/// ```
+/// use bevy_ecs::{prelude::*, schedule::apply_buffers};
+///
+/// #[derive(Component)]
+/// struct Enemy;
+///
+/// fn spawn(mut commands: Commands) {
+///     commands.spawn().insert(Enemy);
+/// }
+///
+/// fn count(query: Query<&Enemy>, mut count: ResMut<usize>) {
+///     *count = query.iter().count();
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(0usize);
+///
+/// let mut graph = SystemGraph::parallel().with_auto_apply_buffers(false);
+/// graph
+///     .add_system(spawn.label("spawn"))
+///     .add_system(apply_buffers.exclusive_system().label("flush").after("spawn"))
+///     .add_system(count.after("flush"));
+/// graph.run(&mut world);
+/// assert_eq!(*world.get_resource::<usize>().unwrap(), 1);
+/// ```
+pub fn apply_buffers(_world: &mut World) {}
+
+/// Stores and executes systems as a single dependency graph, without stage boundaries.
+///
+/// Systems are ordered with labels, using [`before`](crate::schedule::ParallelSystemDescriptorCoercion::before)
+/// and [`after`](crate::schedule::ParallelSystemDescriptorCoercion::after), across the whole graph.
+/// Parallel systems run on a [`ParallelSystemExecutor`], while exclusive systems act as sync
+/// points: before an exclusive system runs, the buffers of every system that ran before it are
+/// applied. The buffers of all systems are applied at the end of the graph as well.
+///
+/// By default, sync points are also inserted automatically: if a parallel system is ordered after
+/// another one, the buffers of the first are applied before the second runs, so it can observe
+/// the effects of its [`Commands`](crate::system::Commands). This can be turned off with
+/// [`SystemGraph::set_auto_apply_buffers`] to allow more parallelism, in which case
+/// [`apply_buffers`] can be used to insert sync points explicitly.
+///
+/// Systems can be guarded with [`run_if`](crate::schedule::ParallelSystemDescriptorCoercion::run_if).
+/// Conditions are evaluated right before the group of systems they belong to runs, after the
+/// preceding sync point. Run criteria which are piped or referenced by label are not supported;
+/// run criteria returning [`ShouldRun::YesAndCheckAgain`] only make their system run once.
+///
+/// `SystemGraph` implements [`Stage`], so it can be added to a
+/// [`Schedule`](crate::schedule::Schedule).
+pub struct SystemGraph {
+    /// The WorldId this graph was last run on.
+    world_id: Option<WorldId>,
+    /// Creates the executor used for each group of parallel systems.
+    executor_factory: fn() -> Box<dyn ParallelSystemExecutor>,
+    /// Parallel systems, sorted by segment once the graph is built.
+    parallel: Vec<ParallelSystemContainer>,
+    parallel_conditions: Vec<BoxedRunCriteria>,
+    exclusive: Vec<ExclusiveSystemContainer>,
+    exclusive_conditions: Vec<BoxedRunCriteria>,
+    uninitialized_parallel: Vec<usize>,
+    uninitialized_exclusive: Vec<usize>,
+    /// Groups of parallel systems, each followed by a sync point.
+    segments: Vec<Segment>,
+    auto_apply_buffers: bool,
+    systems_modified: bool,
+    last_tick_check: u32,
+}
+
+/// A group of parallel systems that run together, followed by a sync point where their buffers
+/// are applied and exclusive systems run.
+struct Segment {
+    parallel: Range<usize>,
+    executor: Box<dyn ParallelSystemExecutor>,
+    /// Indices of the exclusive systems that run at the end of this segment, in order.
+    exclusive: Vec<usize>,
+}
+
+impl Default for SystemGraph {
+    fn default() -> Self {
+        Self::parallel()
+    }
+}
+
+impl SystemGraph {
+    pub fn new(executor_factory: fn() -> Box<dyn ParallelSystemExecutor>) -> Self {
+        Self {
+            world_id: None,
+            executor_factory,
+            parallel: Default::default(),
+            parallel_conditions: Default::default(),
+            exclusive: Default::default(),
+            exclusive_conditions: Default::default(),
+            uninitialized_parallel: Default::default(),
+            uninitialized_exclusive: Default::default(),
+            segments: Default::default(),
+            auto_apply_buffers: true,
+            systems_modified: true,
+            last_tick_check: Default::default(),
+        }
+    }
+
+    pub fn single_threaded() -> Self {
+        Self::new(|| Box::new(SingleThreadedExecutor::default()))
+    }
+
+    pub fn parallel() -> Self {
+        Self::new(|| Box::new(ParallelExecutor::default()))
+    }
+
+    #[must_use]
+    pub fn with_system<Params>(mut self, system: impl IntoSystemDescriptor<Params>) -> Self {
+        self.add_system(system);
+        self
+    }
+
+    /// Adds a system to the graph.
+    ///
+    /// The [insertion point](crate::schedule::ExclusiveSystemDescriptorCoercion::at_start) of
+    /// exclusive systems is ignored: they run as early as their ordering constraints allow.
+    pub fn add_system<Params>(&mut self, system: impl IntoSystemDescriptor<Params>) -> &mut Self {
+        self.systems_modified = true;
+        match system.into_descriptor() {
+            SystemDescriptor::Parallel(mut descriptor) => {
+                let condition =
+                    into_condition(descriptor.run_criteria.take(), || descriptor.system.name());
+                self.uninitialized_parallel.push(self.parallel.len());
+                self.parallel
+                    .push(ParallelSystemContainer::from_descriptor(descriptor));
+                self.parallel_conditions.push(condition);
+            }
+            SystemDescriptor::Exclusive(mut descriptor) => {
+                let condition =
+                    into_condition(descriptor.run_criteria.take(), || descriptor.system.name());
+                self.uninitialized_exclusive.push(self.exclusive.len());
+                self.exclusive
+                    .push(ExclusiveSystemContainer::from_descriptor(descriptor));
+                self.exclusive_conditions.push(condition);
+            }
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn with_auto_apply_buffers(mut self, auto_apply_buffers: bool) -> Self {
+        self.set_auto_apply_buffers(auto_apply_buffers);
+        self
+    }
+
+    /// Sets whether ordering a parallel system after another one also applies the buffers of the
+    /// first before the second runs. Defaults to `true`.
+    pub fn set_auto_apply_buffers(&mut self, auto_apply_buffers: bool) -> &mut Self {
+        self.auto_apply_buffers = auto_apply_buffers;
+        self.systems_modified = true;
+        self
+    }
+
+    /// Returns the parallel systems of this graph, in execution order once it has run.
+    pub fn parallel_systems(&self) -> &[impl SystemContainer] {
+        &self.parallel
+    }
+
+    /// Returns the exclusive systems of this graph, in insertion order.
+    pub fn exclusive_systems(&self) -> &[impl SystemContainer] {
+        &self.exclusive
+    }
+
+    /// Returns the number of sync points of this graph, including the one at its end. This is
+    /// only up to date once the graph has run.
+    pub fn sync_points(&self) -> usize {
+        self.segments.len()
+    }
+
+    fn initialize_systems(&mut self, world: &mut World) {
+        for index in self.uninitialized_parallel.drain(..) {
+            self.parallel[index].system_mut().initialize(world);
+        }
+        for index in self.uninitialized_exclusive.drain(..) {
+            self.exclusive[index].system_mut().initialize(world);
+        }
+    }
+
+    /// Sorts all systems and splits them into segments. Systems must be initialized.
+    fn rebuild_segments(&mut self) {
+        debug_assert!(
+            self.uninitialized_parallel.is_empty() && self.uninitialized_exclusive.is_empty()
+        );
+        let parallel_count = self.parallel.len();
+        let nodes = self
+            .parallel
+            .iter()
+            .map(|container| GraphSystem(container))
+            .chain(
+                self.exclusive
+                    .iter()
+                    .map(|container| GraphSystem(container)),
+            )
+            .collect::<Vec<_>>();
+        let graph = graph_utils::build_dependency_graph(&nodes);
+        let order = unwrap_dependency_cycle_error(
+            graph_utils::topological_order(&graph),
+            &nodes,
+            "system graph",
+        );
+
+        // The level of a parallel system is the segment it runs in, while the level of an
+        // exclusive system is the segment it runs at the end of.
+        let mut levels = vec![0; nodes.len()];
+        for &node in &order {
+            let is_parallel = node < parallel_count;
+            levels[node] = graph[&node]
+                .keys()
+                .map(|&dependency| {
+                    let dependency_is_parallel = dependency < parallel_count;
+                    if is_parallel && (!dependency_is_parallel || self.auto_apply_buffers) {
+                        levels[dependency] + 1
+                    } else {
+                        levels[dependency]
+                    }
+                })
+                .max()
+                .unwrap_or(0);
+        }
+        let segment_count = levels.iter().max().map_or(0, |level| level + 1);
+
+        let mut parallel_order = Vec::with_capacity(parallel_count);
+        let mut ranges = Vec::with_capacity(segment_count);
+        let mut exclusive = vec![Vec::new(); segment_count];
+        for (level, exclusive) in exclusive.iter_mut().enumerate() {
+            let start = parallel_order.len();
+            for &node in &order {
+                if levels[node] != level {
+                    continue;
+                }
+                if node < parallel_count {
+                    parallel_order.push(node);
+                } else {
+                    exclusive.push(node - parallel_count);
+                }
+            }
+            ranges.push(start..parallel_order.len());
+        }
+        drop(nodes);
+
+        let mut new_indices = vec![0; parallel_count];
+        for (new_index, &old_index) in parallel_order.iter().enumerate() {
+            new_indices[old_index] = new_index;
+        }
+        let mut parallel = self
+            .parallel
+            .drain(..)
+            .zip(self.parallel_conditions.drain(..))
+            .map(Some)
+            .collect::<Vec<_>>();
+        for &old_index in &parallel_order {
+            let (mut container, condition) = parallel[old_index].take().unwrap();
+            // Only dependencies within the same segment need to be handled by the executor;
+            // the others are satisfied by the segment boundaries.
+            let start = ranges[levels[old_index]].start;
+            container.set_dependencies(
+                graph[&old_index]
+                    .keys()
+                    .filter(|&&dependency| {
+                        dependency < parallel_count && levels[dependency] == levels[old_index]
+                    })
+                    .map(|&dependency| new_indices[dependency] - start),
+            );
+            self.parallel.push(container);
+            self.parallel_conditions.push(condition);
+        }
+
+        let executor_factory = self.executor_factory;
+        self.segments = ranges
+            .into_iter()
+            .zip(exclusive)
+            .map(|(range, exclusive)| {
+                let mut executor = executor_factory();
+                executor.rebuild_cached_data(&self.parallel[range.clone()]);
+                Segment {
+                    parallel: range,
+                    executor,
+                    exclusive,
+                }
+            })
+            .collect();
+    }
+
+    /// Checks for old component and system change ticks
+    fn check_change_ticks(&mut self, world: &mut World) {
+        let change_tick = world.change_tick();
+        let time_since_last_check = change_tick.wrapping_sub(self.last_tick_check);
+        // Only check after at least `u32::MAX / 8` counts, and at most `u32::MAX / 4` counts
+        // since the max number of [System] in a [SystemGraph] is limited to `u32::MAX / 8`
+        // and this function is called at the end of each run
+        const MIN_TIME_SINCE_LAST_CHECK: u32 = u32::MAX / 8;
+
+        if time_since_last_check > MIN_TIME_SINCE_LAST_CHECK {
+            for exclusive_system in &mut self.exclusive {
+                exclusive_system.system_mut().check_change_tick(change_tick);
+            }
+            for parallel_system in &mut self.parallel {
+                parallel_system.system_mut().check_change_tick(change_tick);
+            }
+
+            world.check_change_ticks();
+
+            self.last_tick_check = change_tick;
+        }
+    }
+}
+
+fn into_condition(
+    run_criteria: Option<RunCriteriaDescriptorOrLabel>,
+    name: impl FnOnce() -> Cow<'static, str>,
+) -> BoxedRunCriteria {
+    let mut condition = BoxedRunCriteria::default();
+    match run_criteria {
+        None => (),
+        Some(RunCriteriaDescriptorOrLabel::Descriptor(descriptor)) => match descriptor.system {
+            RunCriteriaSystem::Single(system) => condition.set(system),
+            RunCriteriaSystem::Piped(_) => panic!(
+                "System {} uses piped run criteria, which are not supported by `SystemGraph`. \
+                Consider using `run_if` instead.",
+                name()
+            ),
+        },
+        Some(RunCriteriaDescriptorOrLabel::Label(label)) => panic!(
+            "System {} refers to run criteria {:?} by label, which is not supported by \
+            `SystemGraph`. Consider using `run_if` instead.",
+            name(),
+            label
+        ),
+    }
+    condition
+}
+
+fn should_run(condition: &mut BoxedRunCriteria, world: &mut World) -> bool {
+    matches!(
+        condition.should_run(world),
+        ShouldRun::Yes | ShouldRun::YesAndCheckAgain
+    )
+}
+
+/// Lets parallel and exclusive systems be sorted together.
+struct GraphSystem<'a>(&'a dyn GraphNode<Label = BoxedSystemLabel>);
+
+impl GraphNode for GraphSystem<'_> {
+    type Label = BoxedSystemLabel;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.0.name()
+    }
+
+    fn labels(&self) -> &[BoxedSystemLabel] {
+        self.0.labels()
+    }
+
+    fn before(&self) -> &[BoxedSystemLabel] {
+        self.0.before()
+    }
+
+    fn after(&self) -> &[BoxedSystemLabel] {
+        self.0.after()
+    }
+}
+
+impl Stage for SystemGraph {
+    fn run(&mut self, world: &mut World) {
+        if let Some(world_id) = self.world_id {
+            assert!(
+                world.id() == world_id,
+                "Cannot run SystemGraph on two different Worlds"
+            );
+        } else {
+            self.world_id = Some(world.id());
+        }
+
+        if self.systems_modified {
+            self.initialize_systems(world);
+            self.rebuild_segments();
+            self.systems_modified = false;
+        }
+
+        for segment in &mut self.segments {
+            let systems = &mut self.parallel[segment.parallel.clone()];
+            let conditions = &mut self.parallel_conditions[segment.parallel.clone()];
+            for (container, condition) in systems.iter_mut().zip(conditions) {
+                container.should_run = should_run(condition, world);
+            }
+            segment.executor.run_systems(systems, world);
+
+            // Apply parallel systems' buffers.
+            for container in systems.iter_mut() {
+                if container.should_run {
+                    #[cfg(feature = "trace")]
+                    let span = bevy_utils::tracing::info_span!(
+                        "system_commands",
+                        name = &*container.name()
+                    );
+                    #[cfg(feature = "trace")]
+                    let _guard = span.enter();
+                    container.system_mut().apply_buffers(world);
+                }
+            }
+
+            for &index in &segment.exclusive {
+                if should_run(&mut self.exclusive_conditions[index], world) {
+                    let container = &mut self.exclusive[index];
+                    #[cfg(feature = "trace")]
+                    let system_span = bevy_utils::tracing::info_span!(
+                        "exclusive_system",
+                        name = &*container.name()
+                    );
+                    #[cfg(feature = "trace")]
+                    let _guard = system_span.enter();
+                    container.system_mut().run(world);
+                }
+            }
+        }
+
+        self.check_change_ticks(world);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        component::Component,
+        schedule::{
+            apply_buffers, ExclusiveSystemDescriptorCoercion, IntoRunCondition,
+            ParallelSystemDescriptorCoercion, Stage, SystemGraph,
+        },
+        system::{Commands, IntoExclusiveSystem, Query, Res, ResMut},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct A;
+
+    fn spawn(mut commands: Commands) {
+        commands.spawn().insert(A);
+    }
+
+    fn count(query: Query<&A>, mut counts: ResMut<Vec<usize>>) {
+        counts.push(query.iter().count());
+    }
+
+    fn push(value: usize) -> impl FnMut(ResMut<Vec<usize>>) {
+        move |mut values: ResMut<Vec<usize>>| values.push(value)
+    }
+
+    fn exclusive_push(value: usize) -> impl FnMut(&mut World) {
+        move |world| world.get_resource_mut::<Vec<usize>>().unwrap().push(value)
+    }
+
+    #[test]
+    fn auto_apply_buffers() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut graph = SystemGraph::parallel()
+            .with_system(count.after("spawn").label("count"))
+            .with_system(spawn.label("spawn"))
+            .with_system(spawn.after("count"));
+        graph.run(&mut world);
+        assert_eq!(graph.sync_points(), 3);
+        graph.run(&mut world);
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![1, 3]);
+    }
+
+    #[test]
+    fn explicit_apply_buffers() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut graph = SystemGraph::single_threaded().with_auto_apply_buffers(false);
+        graph
+            .add_system(spawn.label("spawn"))
+            .add_system(count.label("count").after("spawn"))
+            .add_system(
+                apply_buffers
+                    .exclusive_system()
+                    .label("flush")
+                    .after("count"),
+            )
+            .add_system(count.after("flush"));
+        graph.run(&mut world);
+        assert_eq!(graph.sync_points(), 2);
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![0, 1]);
+    }
+
+    #[test]
+    fn order_across_sync_points() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut graph = SystemGraph::parallel();
+        graph
+            .add_system(push(5).label("5").after("4"))
+            .add_system(exclusive_push(4).exclusive_system().label("4").after("3"))
+            .add_system(push(3).label("3").after("2"))
+            .add_system(exclusive_push(2).exclusive_system().label("2").after("1"))
+            .add_system(exclusive_push(1).exclusive_system().label("1").after("0"))
+            .add_system(push(0).label("0"));
+        graph.run(&mut world);
+        graph.run(&mut world);
+        assert_eq!(
+            *world.get_resource::<Vec<usize>>().unwrap(),
+            vec![0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn run_conditions() {
+        struct Enabled(bool);
+
+        fn enabled(enabled: Res<Enabled>) -> bool {
+            enabled.0
+        }
+
+        fn has_entities(query: Query<&A>) -> bool {
+            !query.is_empty()
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        world.insert_resource(Enabled(false));
+        let mut graph = SystemGraph::parallel()
+            .with_system(spawn.run_if(enabled).label("spawn"))
+            .with_system(
+                push(1)
+                    .run_if(has_entities.and(enabled))
+                    .label("1")
+                    .after("spawn"),
+            )
+            .with_system(
+                exclusive_push(2)
+                    .exclusive_system()
+                    .run_if(has_entities.or(enabled))
+                    .after("1"),
+            );
+        graph.run(&mut world);
+        assert!(world.get_resource::<Vec<usize>>().unwrap().is_empty());
+        world.get_resource_mut::<Enabled>().unwrap().0 = true;
+        graph.run(&mut world);
+        world.get_resource_mut::<Enabled>().unwrap().0 = false;
+        graph.run(&mut world);
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![1, 2, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Found a dependency cycle in system graph")]
+    fn cycle() {
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut graph = SystemGraph::parallel()
+            .with_system(push(0).label("0").after("1"))
+            .with_system(exclusive_push(1).exclusive_system().label("1").after("0"));
+        graph.run(&mut world);
+    }
+
+    #[test]
+    #[should_panic(expected = "not supported by `SystemGraph`")]
+    fn labelled_run_criteria() {
+        SystemGraph::parallel().add_system(push(0).with_run_criteria("criteria"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot run SystemGraph on two different Worlds")]
+    fn multiple_worlds() {
+        let mut graph = SystemGraph::single_threaded();
+        graph.run(&mut World::new());
+        graph.run(&mut World::new());
+    }
+}
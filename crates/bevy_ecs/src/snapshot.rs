@@ -0,0 +1,632 @@
+//! Types for saving the state of selected components and restoring it later, e.g. for rollback
+//! networking.
+//!
+//! A [`SnapshotRegistry`] lists the components that take part in snapshots. Taking a
+//! [`Snapshot`] copies the table columns of those components, keyed by [`Entity`], and restoring
+//! it writes the values back in place. A [`SnapshotHistory`] keeps the snapshots of the last few
+//! frames, and [`Snapshot::diff`] reports what changed between two snapshots.
+
+use crate::{
+    component::{Component, ComponentStorage, StorageType},
+    entity::Entity,
+    query::{AllowDisabled, With},
+    world::World,
+};
+use bevy_utils::{tracing::warn, HashMap, HashSet};
+use std::{
+    any::{Any, TypeId},
+    collections::VecDeque,
+};
+
+/// The values of a single component type in a [`Snapshot`].
+trait ComponentSnapshot: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    fn entities(&self) -> &[Entity];
+
+    fn restore(&self, world: &mut World);
+
+    /// Compares `self` to `newer`, which must store the same component type.
+    fn diff(&self, newer: &dyn ComponentSnapshot) -> ComponentDiff;
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct TypedComponentSnapshot<T> {
+    entities: Vec<Entity>,
+    values: Vec<T>,
+}
+
+impl<T: Component + Clone + PartialEq> TypedComponentSnapshot<T> {
+    fn new(world: &World) -> Self {
+        let mut snapshot = Self {
+            entities: Vec::new(),
+            values: Vec::new(),
+        };
+        let component_id = match world.components().get_id(TypeId::of::<T>()) {
+            Some(component_id) => component_id,
+            None => return snapshot,
+        };
+        let storages = world.storages();
+        match T::Storage::STORAGE_TYPE {
+            StorageType::Table => {
+                for table in storages.tables.iter() {
+                    if let Some(column) = table.get_column(component_id) {
+                        // SAFE: the column stores `table.len()` values of type `T`
+                        let values = unsafe {
+                            std::slice::from_raw_parts(
+                                column.get_data_ptr().cast::<T>().as_ptr(),
+                                table.len(),
+                            )
+                        };
+                        snapshot.entities.extend_from_slice(table.entities());
+                        snapshot.values.extend_from_slice(values);
+                    }
+                }
+            }
+            StorageType::SparseSet => {
+                if let Some(sparse_set) = storages.sparse_sets.get(component_id) {
+                    for &entity in sparse_set.entities() {
+                        // SAFE: the sparse set stores values of type `T`
+                        let value = unsafe { &*sparse_set.get(entity).unwrap().cast::<T>() };
+                        snapshot.entities.push(entity);
+                        snapshot.values.push(value.clone());
+                    }
+                }
+            }
+        }
+        snapshot
+    }
+}
+
+impl<T: Component + Clone + PartialEq> ComponentSnapshot for TypedComponentSnapshot<T> {
+    fn name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    fn restore(&self, world: &mut World) {
+        let saved = self.entities.iter().copied().collect::<HashSet<_>>();
+        let stale = world
+            .query_filtered::<Entity, (With<T>, AllowDisabled)>()
+            .iter(world)
+            .filter(|entity| !saved.contains(entity))
+            .collect::<Vec<_>>();
+        for entity in stale {
+            world.entity_mut(entity).remove::<T>();
+        }
+
+        for (&entity, value) in self.entities.iter().zip(&self.values) {
+            let mut entity_mut = match world.get_or_spawn(entity) {
+                Some(entity_mut) => entity_mut,
+                None => {
+                    warn!(
+                        "Could not restore {} on entity {:?} because its id was reused.",
+                        self.name(),
+                        entity
+                    );
+                    continue;
+                }
+            };
+            match entity_mut.get_mut::<T>() {
+                // only write changed values, so change detection reports what was rolled back
+                Some(mut current) => {
+                    if *current != *value {
+                        *current = value.clone();
+                    }
+                }
+                None => {
+                    entity_mut.insert(value.clone());
+                }
+            }
+        }
+    }
+
+    fn diff(&self, newer: &dyn ComponentSnapshot) -> ComponentDiff {
+        let newer = newer.as_any().downcast_ref::<Self>().unwrap();
+        let old_values = self
+            .entities
+            .iter()
+            .copied()
+            .zip(&self.values)
+            .collect::<HashMap<_, _>>();
+        let mut diff = ComponentDiff {
+            type_id: TypeId::of::<T>(),
+            name: self.name(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        let mut kept = HashSet::default();
+        for (&entity, value) in newer.entities.iter().zip(&newer.values) {
+            match old_values.get(&entity) {
+                Some(old_value) => {
+                    kept.insert(entity);
+                    if *old_value != value {
+                        diff.changed.push(entity);
+                    }
+                }
+                None => diff.added.push(entity),
+            }
+        }
+        diff.removed.extend(
+            self.entities
+                .iter()
+                .filter(|entity| !kept.contains(*entity)),
+        );
+        diff
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Takes a snapshot of a single component type.
+type SnapshotFn = fn(&World) -> Box<dyn ComponentSnapshot>;
+
+/// The set of components that are saved by [`SnapshotRegistry::snapshot`].
+///
+/// Components can be added one by one with [`SnapshotRegistry::register`], or collected from the
+/// types in a [`TypeRegistry`](bevy_reflect::TypeRegistry) that were registered with
+/// [`ReflectSnapshot`](crate::reflect::ReflectSnapshot) type data.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{prelude::*, snapshot::SnapshotRegistry};
+///
+/// #[derive(Component, Clone, PartialEq)]
+/// struct Position(i32);
+///
+/// let mut registry = SnapshotRegistry::default();
+/// registry.register::<Position>();
+///
+/// let mut world = World::new();
+/// let entity = world.spawn().insert(Position(0)).id();
+/// let snapshot = registry.snapshot(&world);
+///
+/// world.get_mut::<Position>(entity).unwrap().0 = 10;
+/// world.spawn().insert(Position(20));
+/// snapshot.restore(&mut world);
+///
+/// let positions = world.query::<&Position>().iter(&world).map(|p| p.0).collect::<Vec<_>>();
+/// assert_eq!(positions, vec![0]);
+/// ```
+#[derive(Default, Clone)]
+pub struct SnapshotRegistry {
+    components: Vec<(TypeId, SnapshotFn)>,
+}
+
+impl SnapshotRegistry {
+    /// Adds the component `T` to the snapshots taken with this registry.
+    pub fn register<T: Component + Clone + PartialEq>(&mut self) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        if !self.contains(type_id) {
+            self.components.push((type_id, |world| {
+                Box::new(TypedComponentSnapshot::<T>::new(world))
+            }));
+        }
+        self
+    }
+
+    /// Returns a registry of every type in `type_registry` that has
+    /// [`ReflectSnapshot`](crate::reflect::ReflectSnapshot) type data.
+    #[cfg(feature = "bevy_reflect")]
+    pub fn from_type_registry(type_registry: &bevy_reflect::TypeRegistry) -> Self {
+        let mut registry = Self::default();
+        for registration in type_registry.iter() {
+            if let Some(reflect_snapshot) = registration.data::<crate::reflect::ReflectSnapshot>() {
+                reflect_snapshot.register(&mut registry);
+            }
+        }
+        registry
+    }
+
+    /// Returns `true` if the component with the given [`TypeId`] is part of the snapshots.
+    #[inline]
+    pub fn contains(&self, type_id: TypeId) -> bool {
+        self.components.iter().any(|(id, _)| *id == type_id)
+    }
+
+    /// Returns the number of registered components.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Copies the values of every registered component in `world`.
+    pub fn snapshot(&self, world: &World) -> Snapshot {
+        Snapshot {
+            components: self
+                .components
+                .iter()
+                .map(|(type_id, snapshot)| (*type_id, snapshot(world)))
+                .collect(),
+        }
+    }
+}
+
+/// The values of a set of components at one point in time, taken with
+/// [`SnapshotRegistry::snapshot`].
+pub struct Snapshot {
+    components: Vec<(TypeId, Box<dyn ComponentSnapshot>)>,
+}
+
+impl Snapshot {
+    /// Returns the entities that had at least one of the snapshotted components.
+    pub fn entities(&self) -> HashSet<Entity> {
+        self.components
+            .iter()
+            .flat_map(|(_, snapshot)| snapshot.entities().iter().copied())
+            .collect()
+    }
+
+    /// Writes the snapshotted components back into `world`.
+    ///
+    /// For every snapshotted component type, each entity ends up with exactly the value it had
+    /// when the snapshot was taken: values are overwritten in place, re-inserted if they were
+    /// removed, and removed from entities that did not have them. Entities that were despawned
+    /// are spawned again with the same [`Entity`] id, unless that id has been reused by another
+    /// entity in the meantime, in which case a warning is logged. Entities are never despawned,
+    /// and components that are not part of the snapshot are left untouched.
+    ///
+    /// Values that did not change are not written, so change detection only reports the
+    /// components that were actually rolled back.
+    pub fn restore(&self, world: &mut World) {
+        for (_, snapshot) in &self.components {
+            snapshot.restore(world);
+        }
+    }
+
+    /// Returns the differences from `self` to `newer`, for the components that are part of both
+    /// snapshots.
+    pub fn diff(&self, newer: &Snapshot) -> SnapshotDiff {
+        let components = self
+            .components
+            .iter()
+            .filter_map(|(type_id, snapshot)| {
+                let (_, newer) = newer.components.iter().find(|(id, _)| id == type_id)?;
+                Some(snapshot.diff(newer.as_ref()))
+            })
+            .filter(|diff| !diff.is_empty())
+            .collect();
+        SnapshotDiff { components }
+    }
+}
+
+/// The differences of a single component type between two [`Snapshot`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentDiff {
+    type_id: TypeId,
+    name: &'static str,
+    added: Vec<Entity>,
+    removed: Vec<Entity>,
+    changed: Vec<Entity>,
+}
+
+impl ComponentDiff {
+    /// Returns the type name of the component.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the entities that only have the component in the newer snapshot.
+    #[inline]
+    pub fn added(&self) -> &[Entity] {
+        &self.added
+    }
+
+    /// Returns the entities that only have the component in the older snapshot.
+    #[inline]
+    pub fn removed(&self) -> &[Entity] {
+        &self.removed
+    }
+
+    /// Returns the entities whose component value differs between the snapshots.
+    #[inline]
+    pub fn changed(&self) -> &[Entity] {
+        &self.changed
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The differences between two [`Snapshot`]s, created by [`Snapshot::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    components: Vec<ComponentDiff>,
+}
+
+impl SnapshotDiff {
+    /// Returns the differences of every component type that changed.
+    #[inline]
+    pub fn components(&self) -> &[ComponentDiff] {
+        &self.components
+    }
+
+    /// Returns the differences of the component `T`, if it changed.
+    pub fn get<T: Component>(&self) -> Option<&ComponentDiff> {
+        self.components
+            .iter()
+            .find(|diff| diff.type_id == TypeId::of::<T>())
+    }
+
+    /// Returns `true` if no component changed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+/// A ring buffer of the [`Snapshot`]s of the last few frames.
+///
+/// Pushing a snapshot when the history is full drops the oldest one.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{prelude::*, snapshot::{SnapshotHistory, SnapshotRegistry}};
+///
+/// #[derive(Component, Clone, PartialEq)]
+/// struct Health(u32);
+///
+/// let mut registry = SnapshotRegistry::default();
+/// registry.register::<Health>();
+/// let mut history = SnapshotHistory::new(8);
+///
+/// let mut world = World::new();
+/// let player = world.spawn().insert(Health(10)).id();
+/// for frame in 0..10 {
+///     history.push(frame, registry.snapshot(&world));
+///     world.get_mut::<Health>(player).unwrap().0 -= 1;
+/// }
+/// assert_eq!(history.oldest_frame(), Some(2));
+///
+/// // a late input arrived for frame 5: roll back and resimulate from there
+/// history.rollback(5, &mut world).unwrap();
+/// assert_eq!(world.get::<Health>(player).unwrap().0, 5);
+/// assert_eq!(history.latest_frame(), Some(5));
+/// ```
+pub struct SnapshotHistory {
+    snapshots: VecDeque<(u64, Snapshot)>,
+    capacity: usize,
+}
+
+impl SnapshotHistory {
+    /// Creates a history that keeps the snapshots of the last `capacity` frames.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a SnapshotHistory must have a capacity of at least 1"
+        );
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Stores the snapshot of `frame`, dropping the oldest snapshot if the history is full.
+    ///
+    /// Snapshots of `frame` and later frames are dropped first, since they belong to a timeline
+    /// that no longer exists.
+    pub fn push(&mut self, frame: u64, snapshot: Snapshot) {
+        while matches!(self.snapshots.back(), Some((last, _)) if *last >= frame) {
+            self.snapshots.pop_back();
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((frame, snapshot));
+    }
+
+    /// Returns the snapshot of `frame`, if it is still in the history.
+    pub fn get(&self, frame: u64) -> Option<&Snapshot> {
+        self.snapshots
+            .iter()
+            .find(|(snapshot_frame, _)| *snapshot_frame == frame)
+            .map(|(_, snapshot)| snapshot)
+    }
+
+    /// Restores the snapshot of `frame` into `world` and drops every later snapshot.
+    ///
+    /// Returns `None`, leaving `world` untouched, if the snapshot is no longer in the history.
+    pub fn rollback(&mut self, frame: u64, world: &mut World) -> Option<&Snapshot> {
+        self.get(frame)?.restore(world);
+        self.truncate_after(frame);
+        self.snapshots.back().map(|(_, snapshot)| snapshot)
+    }
+
+    /// Drops the snapshots of every frame after `frame`.
+    pub fn truncate_after(&mut self, frame: u64) {
+        while matches!(self.snapshots.back(), Some((last, _)) if *last > frame) {
+            self.snapshots.pop_back();
+        }
+    }
+
+    /// Returns the frame of the oldest snapshot in the history.
+    #[inline]
+    pub fn oldest_frame(&self) -> Option<u64> {
+        self.snapshots.front().map(|(frame, _)| *frame)
+    }
+
+    /// Returns the frame of the latest snapshot in the history.
+    #[inline]
+    pub fn latest_frame(&self) -> Option<u64> {
+        self.snapshots.back().map(|(frame, _)| *frame)
+    }
+
+    /// Returns the maximum number of snapshots kept.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SnapshotHistory, SnapshotRegistry};
+    use crate::{
+        self as bevy_ecs,
+        component::{Component, Disabled},
+        world::World,
+    };
+
+    #[derive(Component, Debug, Clone, PartialEq)]
+    struct A(usize);
+
+    #[derive(Component, Debug, Clone, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct B(String);
+
+    #[derive(Component, Debug, Clone, PartialEq)]
+    struct NotSaved(usize);
+
+    fn registry() -> SnapshotRegistry {
+        let mut registry = SnapshotRegistry::default();
+        registry.register::<A>().register::<B>().register::<A>();
+        registry
+    }
+
+    #[test]
+    fn restore_in_place() {
+        let registry = registry();
+        assert_eq!(registry.len(), 2);
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), NotSaved(1))).id();
+        let e2 = world.spawn().insert(B("b".to_string())).id();
+        let e3 = world.spawn().insert(A(3)).id();
+        let e4 = world.spawn().insert(A(4)).id();
+        let snapshot = registry.snapshot(&world);
+
+        world.get_mut::<A>(e1).unwrap().0 = 10;
+        world.get_mut::<NotSaved>(e1).unwrap().0 = 10;
+        world.entity_mut(e2).insert(A(2)).remove::<B>();
+        // spawned first so that it does not reuse the id of `e3`
+        let e5 = world.spawn().insert(B("new".to_string())).id();
+        world.despawn(e3);
+
+        let change_tick = world.change_tick();
+        world.increment_change_tick();
+        snapshot.restore(&mut world);
+
+        assert_eq!(world.get::<A>(e1), Some(&A(1)));
+        assert_eq!(world.get::<NotSaved>(e1), Some(&NotSaved(10)));
+        assert_eq!(world.get::<A>(e2), None);
+        assert_eq!(world.get::<B>(e2), Some(&B("b".to_string())));
+        assert_eq!(world.get::<A>(e3), Some(&A(3)));
+        assert!(world.get_entity(e5).is_some());
+        assert_eq!(world.get::<B>(e5), None);
+        assert!(snapshot.diff(&registry.snapshot(&world)).is_empty());
+
+        // unchanged values are not written
+        let a = world.init_component::<A>();
+        let is_changed = |entity| {
+            world
+                .entity(entity)
+                .get_by_id(a)
+                .unwrap()
+                .ticks()
+                .is_changed(change_tick, world.read_change_tick())
+        };
+        assert!(is_changed(e1));
+        assert!(!is_changed(e4));
+    }
+
+    #[test]
+    fn restore_disabled_entities() {
+        let registry = registry();
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), Disabled)).id();
+        let snapshot = registry.snapshot(&world);
+
+        world.get_mut::<A>(e1).unwrap().0 = 10;
+        let e2 = world.spawn().insert_bundle((A(2), Disabled)).id();
+        snapshot.restore(&mut world);
+
+        assert_eq!(world.get::<A>(e1), Some(&A(1)));
+        assert_eq!(world.get::<A>(e2), None);
+        assert!(world.get::<Disabled>(e2).is_some());
+    }
+
+    #[test]
+    fn diff() {
+        let registry = registry();
+        let mut world = World::new();
+        let e1 = world.spawn().insert(A(1)).id();
+        let e2 = world.spawn().insert_bundle((A(2), B("b".to_string()))).id();
+        let e3 = world.spawn().insert(A(3)).id();
+        let old = registry.snapshot(&world);
+
+        world.get_mut::<A>(e1).unwrap().0 = 10;
+        world.get_mut::<A>(e2).unwrap().0 = 2;
+        world.despawn(e3);
+        let e4 = world.spawn().insert(A(4)).id();
+        let new = registry.snapshot(&world);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.components().len(), 1);
+        let a = diff.get::<A>().unwrap();
+        assert_eq!(a.changed(), &[e1]);
+        assert_eq!(a.added(), &[e4]);
+        assert_eq!(a.removed(), &[e3]);
+        assert!(diff.get::<B>().is_none());
+        assert!(old.entities().contains(&e2));
+    }
+
+    #[test]
+    fn history() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn().insert(A(0)).id();
+        let mut history = SnapshotHistory::new(3);
+        for frame in 0..5 {
+            world.get_mut::<A>(entity).unwrap().0 = frame as usize;
+            history.push(frame, registry.snapshot(&world));
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.oldest_frame(), Some(2));
+        assert!(history.get(1).is_none());
+        assert!(history.rollback(1, &mut world).is_none());
+        assert_eq!(world.get::<A>(entity), Some(&A(4)));
+
+        assert!(history.rollback(3, &mut world).is_some());
+        assert_eq!(world.get::<A>(entity), Some(&A(3)));
+        assert_eq!(history.latest_frame(), Some(3));
+
+        // pushing an earlier frame replaces the snapshots from that frame on
+        history.push(2, registry.snapshot(&world));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.latest_frame(), Some(2));
+    }
+}
@@ -58,6 +58,16 @@ impl BlobVec {
         self.capacity
     }
 
+    /// Returns the number of bytes allocated for the items, including the unused capacity.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        if self.item_layout.size() == 0 {
+            0
+        } else {
+            self.capacity * self.item_layout.size()
+        }
+    }
+
     pub fn reserve_exact(&mut self, additional: usize) {
         let available_space = self.capacity - self.len;
         if available_space < additional {
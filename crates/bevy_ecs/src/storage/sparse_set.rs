@@ -126,6 +126,15 @@ impl ComponentSparseSet {
         self.dense.len() == 0
     }
 
+    /// Returns the number of bytes allocated for the components, their change ticks, the
+    /// entities and the sparse array indexing them, including the unused capacity.
+    pub fn allocated_bytes(&self) -> usize {
+        self.dense.allocated_bytes()
+            + self.ticks.capacity() * std::mem::size_of::<ComponentTicks>()
+            + self.entities.capacity() * std::mem::size_of::<Entity>()
+            + self.sparse.values.capacity() * std::mem::size_of::<Option<usize>>()
+    }
+
     /// Inserts the `entity` key and component `value` pair into this sparse
     /// set. This collection takes ownership of the contents of `value`, and
     /// will drop the value when needed. Also, it may overwrite the contents of
@@ -160,6 +169,12 @@ impl ComponentSparseSet {
         self.sparse.contains(entity)
     }
 
+    /// Returns the entities that have a value in this sparse set.
+    #[inline]
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
     /// # Safety
     /// ensure the same entity is not accessed twice at the same time
     #[inline]
@@ -425,6 +440,22 @@ impl SparseSets {
         self.sets.get_mut(component_id)
     }
 
+    /// Returns the number of component sparse sets.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Iterates over the component sparse sets and the id of their component.
+    pub fn iter(&self) -> impl Iterator<Item = (ComponentId, &ComponentSparseSet)> {
+        self.sets.indices().zip(self.sets.values())
+    }
+
     pub fn clear(&mut self) {
         for set in self.sets.values_mut() {
             set.clear();
@@ -92,6 +92,13 @@ impl Column {
         self.data.is_empty()
     }
 
+    /// Returns the number of bytes allocated for the components and their change ticks, including
+    /// the unused capacity.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.data.allocated_bytes() + self.ticks.capacity() * std::mem::size_of::<ComponentTicks>()
+    }
+
     /// # Safety
     /// index must be in-bounds
     #[inline]
@@ -391,6 +398,16 @@ impl Table {
         self.entities.is_empty()
     }
 
+    /// Returns the number of bytes allocated for the columns and the entities of the table,
+    /// including the unused capacity.
+    pub fn allocated_bytes(&self) -> usize {
+        self.columns
+            .values()
+            .map(|column| column.allocated_bytes())
+            .sum::<usize>()
+            + self.entities.capacity() * std::mem::size_of::<Entity>()
+    }
+
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         for column in self.columns.values_mut() {
             column.check_change_ticks(change_tick);
@@ -37,6 +37,20 @@ impl CommandQueue {
         /// accesses are safe.
         unsafe fn write_command<T: Command>(command: *mut u8, world: &mut World) {
             let command = command.cast::<T>().read_unaligned();
+            // the recorder is looked up for each command, so that the commands applied while
+            // writing this one, e.g. by a registered system, are recorded as well
+            #[cfg(feature = "bevy_reflect")]
+            if world.records_commands {
+                if let Some(mut recorder) =
+                    world.get_resource_mut::<crate::recording::CommandRecorder>()
+                {
+                    recorder.record(
+                        std::any::TypeId::of::<T>(),
+                        std::any::type_name::<T>(),
+                        &command as *const T as *const u8,
+                    );
+                }
+            }
             command.write(world);
         }
 
@@ -71,6 +85,9 @@ impl CommandQueue {
 
     /// Execute the queued [`Command`]s in the world.
     /// This clears the queue.
+    ///
+    /// If the world has a [`CommandRecorder`](crate::recording::CommandRecorder) resource, each
+    /// command is recorded before being executed.
     #[inline]
     pub fn apply(&mut self, world: &mut World) {
         // flush the previously queued entities
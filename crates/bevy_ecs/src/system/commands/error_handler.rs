@@ -0,0 +1,134 @@
+use super::{Command, FallibleCommand};
+use crate::{entity::Entity, world::World};
+use bevy_utils::tracing::{error, warn};
+use std::{fmt, sync::Arc};
+use thiserror::Error;
+
+/// An error returned by a [`FallibleCommand`].
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("Could not {action} entity {entity:?} because it doesn't exist in this World.\n\
+            If this command was added to a newly spawned entity, ensure that you have not despawned that entity within the same stage.\n\
+            This may have occurred due to system order ambiguity, or if the spawning system has multiple command buffers")]
+    NoSuchEntity {
+        entity: Entity,
+        /// What the command tried to do, such as "despawn" or "add a component (of type `T`) to".
+        action: String,
+    },
+    #[error("{0}")]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl CommandError {
+    /// Creates a [`CommandError::Other`] from any error.
+    pub fn other(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        CommandError::Other(error.into())
+    }
+}
+
+/// A function that handles the errors of [`FallibleCommand`]s.
+pub type CustomCommandErrorHandler = Arc<dyn Fn(&mut World, CommandError) + Send + Sync>;
+
+/// Determines what happens when a [`FallibleCommand`] fails.
+///
+/// A handler can be set for the commands added by a single [`Commands`](super::Commands) with
+/// [`Commands::set_error_handler`](super::Commands::set_error_handler), or for every command by
+/// inserting it as a resource. Without either, each command falls back to its own behavior: the
+/// built-in commands that insert components panic, [`Despawn`](super::Despawn) logs a warning and
+/// the commands that remove components ignore the error.
+///
+/// # Example
+///
+/// ```
+/// use bevy_ecs::{prelude::*, system::CommandErrorHandler};
+///
+/// #[derive(Component)]
+/// struct Health(u32);
+///
+/// let mut world = World::new();
+/// // log the errors of every command instead of panicking
+/// world.insert_resource(CommandErrorHandler::Error);
+///
+/// let entity = world.spawn().id();
+/// let mut stage = SystemStage::single(move |mut commands: Commands| {
+///     commands.entity(entity).despawn();
+///     // the entity does not exist anymore when this command is applied
+///     commands.entity(entity).insert(Health(10));
+/// });
+/// stage.run(&mut world);
+/// ```
+#[derive(Clone)]
+pub enum CommandErrorHandler {
+    /// Silently ignores the error.
+    Ignore,
+    /// Logs the error as a warning.
+    Warn,
+    /// Logs the error as an error.
+    Error,
+    /// Panics with the error message.
+    Panic,
+    /// Passes the error to a function.
+    Custom(CustomCommandErrorHandler),
+}
+
+impl CommandErrorHandler {
+    /// Creates a [`CommandErrorHandler::Custom`] handler that passes errors to `handler`.
+    pub fn custom(handler: impl Fn(&mut World, CommandError) + Send + Sync + 'static) -> Self {
+        CommandErrorHandler::Custom(Arc::new(handler))
+    }
+
+    /// Handles an `error` that occurred while applying a command to `world`.
+    pub fn handle(&self, world: &mut World, error: CommandError) {
+        match self {
+            CommandErrorHandler::Ignore => {}
+            CommandErrorHandler::Warn => warn!("{}", error),
+            CommandErrorHandler::Error => error!("{}", error),
+            CommandErrorHandler::Panic => panic!("{}", error),
+            CommandErrorHandler::Custom(handler) => handler(world, error),
+        }
+    }
+}
+
+impl fmt::Debug for CommandErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandErrorHandler::Ignore => write!(f, "Ignore"),
+            CommandErrorHandler::Warn => write!(f, "Warn"),
+            CommandErrorHandler::Error => write!(f, "Error"),
+            CommandErrorHandler::Panic => write!(f, "Panic"),
+            CommandErrorHandler::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Passes `error` to `handler` if there is one, or else to the [`CommandErrorHandler`] resource
+/// of `world` if it exists, or else to `fallback`.
+pub(crate) fn handle_command_error(
+    world: &mut World,
+    error: CommandError,
+    handler: Option<CommandErrorHandler>,
+    fallback: CommandErrorHandler,
+) {
+    handler
+        .or_else(|| world.get_resource::<CommandErrorHandler>().cloned())
+        .unwrap_or(fallback)
+        .handle(world, error);
+}
+
+/// A [`Command`] that applies a [`FallibleCommand`] and passes its error to a
+/// [`CommandErrorHandler`].
+///
+/// If `handler` is `None`, the [`CommandErrorHandler`] resource is used if it exists, or else the
+/// error is logged.
+pub struct WithErrorHandler<C> {
+    pub command: C,
+    pub handler: Option<CommandErrorHandler>,
+}
+
+impl<C: FallibleCommand> Command for WithErrorHandler<C> {
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.command.try_write(world) {
+            handle_command_error(world, error, self.handler, CommandErrorHandler::Error);
+        }
+    }
+}
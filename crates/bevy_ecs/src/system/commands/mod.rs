@@ -1,4 +1,5 @@
 mod command_queue;
+mod error_handler;
 
 use crate::{
     bundle::Bundle,
@@ -6,17 +7,29 @@ use crate::{
     entity::{Entities, Entity},
     world::{FromWorld, World},
 };
-use bevy_utils::tracing::{error, warn};
+use bevy_utils::tracing::error;
 pub use command_queue::CommandQueue;
+use error_handler::handle_command_error;
+pub use error_handler::{
+    CommandError, CommandErrorHandler, CustomCommandErrorHandler, WithErrorHandler,
+};
 use std::marker::PhantomData;
 
-use super::Resource;
+use super::{Resource, SystemId};
 
 /// A [`World`] mutation.
 pub trait Command: Send + Sync + 'static {
     fn write(self, world: &mut World);
 }
 
+/// A [`World`] mutation that can fail.
+///
+/// Fallible commands are added with [`Commands::add_fallible`], and their errors are passed to a
+/// [`CommandErrorHandler`].
+pub trait FallibleCommand: Send + Sync + 'static {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError>;
+}
+
 /// A list of commands that modify a [`World`], running at the end of the stage where they
 /// have been invoked.
 ///
@@ -37,6 +50,7 @@ pub trait Command: Send + Sync + 'static {
 pub struct Commands<'w, 's> {
     queue: &'s mut CommandQueue,
     entities: &'w Entities,
+    error_handler: Option<CommandErrorHandler>,
 }
 
 impl<'w, 's> Commands<'w, 's> {
@@ -45,9 +59,40 @@ impl<'w, 's> Commands<'w, 's> {
         Self {
             queue,
             entities: world.entities(),
+            error_handler: None,
         }
     }
 
+    /// Sets the [`CommandErrorHandler`] of the fallible commands added by this [`Commands`] from
+    /// now on, such as [`EntityCommands::insert`] or commands added with
+    /// [`add_fallible`](Self::add_fallible).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ecs::{prelude::*, system::CommandErrorHandler};
+    ///
+    /// #[derive(Component)]
+    /// struct Target;
+    ///
+    /// fn mark_targets(mut commands: Commands, hits: Res<Vec<Entity>>) {
+    ///     // the targets may have been despawned by another system, which is fine
+    ///     commands.set_error_handler(CommandErrorHandler::Ignore);
+    ///     for entity in hits.iter() {
+    ///         commands.entity(*entity).insert(Target);
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(mark_targets);
+    /// ```
+    pub fn set_error_handler(&mut self, handler: CommandErrorHandler) {
+        self.error_handler = Some(handler);
+    }
+
+    /// Returns the [`CommandErrorHandler`] set with [`set_error_handler`](Self::set_error_handler).
+    pub fn error_handler(&self) -> Option<&CommandErrorHandler> {
+        self.error_handler.as_ref()
+    }
+
     /// Creates a new empty [`Entity`] and returns an [`EntityCommands`] builder for it.
     ///
     /// To directly spawn an entity with a [`Bundle`] included, you can use
@@ -203,6 +248,43 @@ impl<'w, 's> Commands<'w, 's> {
         }
     }
 
+    /// Spawns a copy of `entity` with all of its components, and returns an [`EntityCommands`]
+    /// for the copy.
+    ///
+    /// The components are copied when the command is applied, through their reflection data. See
+    /// [`World::clone_entity`] for the requirements on the components.
+    ///
+    /// If the clone fails, e.g. because a component isn't registered, the error is passed to the
+    /// [`CommandErrorHandler`] and the copy is left as an empty entity, so that the commands added
+    /// to the returned [`EntityCommands`] still apply.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Prefab;
+    /// # struct Spawner { prefab: Entity }
+    /// #
+    /// fn spawn_from_prefab(mut commands: Commands, spawner: Res<Spawner>) {
+    ///     commands.clone_entity(spawner.prefab).remove::<Prefab>();
+    /// }
+    /// # bevy_ecs::system::assert_is_system(spawn_from_prefab);
+    /// ```
+    #[cfg(feature = "bevy_reflect")]
+    pub fn clone_entity<'a>(&'a mut self, entity: Entity) -> EntityCommands<'w, 's, 'a> {
+        let clone = self.entities.reserve_entity();
+        self.add_entity_command(CloneEntity {
+            source: entity,
+            destination: clone,
+        });
+        EntityCommands {
+            entity: clone,
+            commands: self,
+        }
+    }
+
     /// Spawns entities to the [`World`] according to the given iterator (or a type that can
     /// be converted to it).
     ///
@@ -348,6 +430,26 @@ impl<'w, 's> Commands<'w, 's> {
         });
     }
 
+    /// Runs the system registered as `id` with [`World::register_system`].
+    ///
+    /// See [`World::run_system`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, system::SystemId};
+    /// #
+    /// struct OnClick(SystemId);
+    ///
+    /// fn click_system(mut commands: Commands, on_click: Res<OnClick>) {
+    ///     commands.run_system(on_click.0);
+    /// }
+    /// # bevy_ecs::system::assert_is_system(click_system);
+    /// ```
+    pub fn run_system(&mut self, id: SystemId) {
+        self.queue.push(RunRegisteredSystem { id });
+    }
+
     /// Adds a command directly to the command list.
     ///
     /// # Example
@@ -386,6 +488,48 @@ impl<'w, 's> Commands<'w, 's> {
     pub fn add<C: Command>(&mut self, command: C) {
         self.queue.push(command);
     }
+
+    /// Adds a [`FallibleCommand`] to the command list. If it fails, its error is passed to the
+    /// [`CommandErrorHandler`] of this [`Commands`], or else to the [`CommandErrorHandler`]
+    /// resource if it exists, or else logged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// use bevy_ecs::system::CommandError;
+    ///
+    /// struct Score(u32);
+    ///
+    /// fn add_score_system(mut commands: Commands) {
+    ///     commands.add_fallible(|world: &mut World| {
+    ///         let mut score = world
+    ///             .get_resource_mut::<Score>()
+    ///             .ok_or_else(|| CommandError::other("there is no score"))?;
+    ///         score.0 += 1;
+    ///         Ok(())
+    ///     });
+    /// }
+    /// # bevy_ecs::system::assert_is_system(add_score_system);
+    /// ```
+    pub fn add_fallible<C: FallibleCommand>(&mut self, command: C) {
+        self.queue.push(WithErrorHandler {
+            command,
+            handler: self.error_handler.clone(),
+        });
+    }
+
+    /// Adds one of the built-in entity commands, which fall back to their own error handling when
+    /// no [`CommandErrorHandler`] is set.
+    fn add_entity_command<C: Command + FallibleCommand>(&mut self, command: C) {
+        match &self.error_handler {
+            Some(handler) => self.queue.push(WithErrorHandler {
+                command,
+                handler: Some(handler.clone()),
+            }),
+            None => self.queue.push(command),
+        }
+    }
 }
 
 /// A list of commands that will be run to modify an [entity](crate::entity).
@@ -445,7 +589,7 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
     /// # bevy_ecs::system::assert_is_system(add_combat_stats_system);
     /// ```
     pub fn insert_bundle(&mut self, bundle: impl Bundle) -> &mut Self {
-        self.commands.add(InsertBundle {
+        self.commands.add_entity_command(InsertBundle {
             entity: self.entity,
             bundle,
         });
@@ -479,13 +623,57 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
     /// # bevy_ecs::system::assert_is_system(example_system);
     /// ```
     pub fn insert(&mut self, component: impl Component) -> &mut Self {
-        self.commands.add(Insert {
+        self.commands.add_entity_command(Insert {
             entity: self.entity,
             component,
         });
         self
     }
 
+    /// Like [`insert_bundle`](Self::insert_bundle), except that nothing happens if the entity
+    /// does not exist anymore when the command is applied, regardless of the
+    /// [`CommandErrorHandler`].
+    pub fn try_insert_bundle(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.commands.add(WithErrorHandler {
+            command: InsertBundle {
+                entity: self.entity,
+                bundle,
+            },
+            handler: Some(CommandErrorHandler::Ignore),
+        });
+        self
+    }
+
+    /// Like [`insert`](Self::insert), except that nothing happens if the entity does not exist
+    /// anymore when the command is applied, regardless of the [`CommandErrorHandler`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Burning;
+    /// #
+    /// fn ignite_system(mut commands: Commands, hits: Res<Vec<Entity>>) {
+    ///     for entity in hits.iter() {
+    ///         // the entity may have been despawned by another system in the meantime
+    ///         commands.entity(*entity).try_insert(Burning);
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(ignite_system);
+    /// ```
+    pub fn try_insert(&mut self, component: impl Component) -> &mut Self {
+        self.commands.add(WithErrorHandler {
+            command: Insert {
+                entity: self.entity,
+                component,
+            },
+            handler: Some(CommandErrorHandler::Ignore),
+        });
+        self
+    }
+
     /// Removes a [`Bundle`] of components from the entity.
     ///
     /// See [`EntityMut::remove_bundle`](crate::world::EntityMut::remove_bundle) for more
@@ -512,7 +700,7 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
     where
         T: Bundle,
     {
-        self.commands.add(RemoveBundle::<T> {
+        self.commands.add_entity_command(RemoveBundle::<T> {
             entity: self.entity,
             phantom: PhantomData,
         });
@@ -541,8 +729,46 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
     where
         T: Component,
     {
-        self.commands.add(Remove::<T> {
+        self.commands.add_entity_command(Remove::<T> {
+            entity: self.entity,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// Adds a relation of kind `R` from the entity to `target`.
+    ///
+    /// See [`EntityMut::relate`](crate::world::EntityMut::relate) for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # struct Sword { entity: Entity }
+    /// struct Owns;
+    ///
+    /// fn spawn_player_system(mut commands: Commands, sword: Res<Sword>) {
+    ///     commands.spawn().relate::<Owns>(sword.entity);
+    /// }
+    /// # bevy_ecs::system::assert_is_system(spawn_player_system);
+    /// ```
+    pub fn relate<R: Send + Sync + 'static>(&mut self, target: Entity) -> &mut Self {
+        self.commands.add_entity_command(Relate::<R> {
+            entity: self.entity,
+            target,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// Removes the relation of kind `R` from the entity to `target`.
+    ///
+    /// See [`EntityMut::unrelate`](crate::world::EntityMut::unrelate) for more details.
+    pub fn unrelate<R: Send + Sync + 'static>(&mut self, target: Entity) -> &mut Self {
+        self.commands.add_entity_command(Unrelate::<R> {
             entity: self.entity,
+            target,
             phantom: PhantomData,
         });
         self
@@ -569,11 +795,31 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
     /// # bevy_ecs::system::assert_is_system(remove_character_system);
     /// ```
     pub fn despawn(&mut self) {
-        self.commands.add(Despawn {
+        self.commands.add_entity_command(Despawn {
             entity: self.entity,
         });
     }
 
+    /// Like [`despawn`](Self::despawn), except that nothing happens if the entity does not exist
+    /// anymore when the command is applied, regardless of the [`CommandErrorHandler`].
+    pub fn try_despawn(&mut self) {
+        self.commands.add(WithErrorHandler {
+            command: Despawn {
+                entity: self.entity,
+            },
+            handler: Some(CommandErrorHandler::Ignore),
+        });
+    }
+
+    /// Spawns a copy of the entity with all of its components, and returns an
+    /// [`EntityCommands`] for the copy.
+    ///
+    /// See [`Commands::clone_entity`] for more details.
+    #[cfg(feature = "bevy_reflect")]
+    pub fn clone(&mut self) -> EntityCommands<'w, 's, '_> {
+        self.commands.clone_entity(self.entity)
+    }
+
     /// Returns the underlying [`Commands`].
     pub fn commands(&mut self) -> &mut Commands<'w, 's> {
         self.commands
@@ -589,6 +835,15 @@ where
     }
 }
 
+impl<F> FallibleCommand for F
+where
+    F: FnOnce(&mut World) -> Result<(), CommandError> + Send + Sync + 'static,
+{
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
+        self(world)
+    }
+}
+
 #[derive(Debug)]
 pub struct Spawn<T> {
     pub bundle: T,
@@ -662,12 +917,58 @@ pub struct Despawn {
     pub entity: Entity,
 }
 
+impl FallibleCommand for Despawn {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
+        if world.despawn(self.entity) {
+            Ok(())
+        } else {
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: "despawn".to_string(),
+            })
+        }
+    }
+}
+
 impl Command for Despawn {
     fn write(self, world: &mut World) {
-        if !world.despawn(self.entity) {
-            warn!("Could not despawn entity {:?} because it doesn't exist in this World.\n\
-                    If this command was added to a newly spawned entity, ensure that you have not despawned that entity within the same stage.\n\
-                    This may have occurred due to system order ambiguity, or if the spawning system has multiple command buffers", self.entity);
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Warn);
+        }
+    }
+}
+
+#[cfg(feature = "bevy_reflect")]
+#[derive(Debug)]
+pub struct CloneEntity {
+    pub source: Entity,
+    pub destination: Entity,
+}
+
+#[cfg(feature = "bevy_reflect")]
+impl FallibleCommand for CloneEntity {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
+        let mut entity_map = crate::entity::EntityMap::default();
+        entity_map.insert(self.source, self.destination);
+        world
+            .clone_entities_into(&entity_map)
+            .map_err(|error| match error {
+                crate::world::CloneEntityError::NoSuchEntity(entity) => {
+                    CommandError::NoSuchEntity {
+                        entity,
+                        action: "clone".to_string(),
+                    }
+                }
+                error => CommandError::other(error),
+            })
+    }
+}
+
+#[cfg(feature = "bevy_reflect")]
+impl Command for CloneEntity {
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Error);
         }
     }
 }
@@ -677,17 +978,33 @@ pub struct InsertBundle<T> {
     pub bundle: T,
 }
 
-impl<T> Command for InsertBundle<T>
+impl<T> FallibleCommand for InsertBundle<T>
 where
     T: Bundle + 'static,
 {
-    fn write(self, world: &mut World) {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
         if let Some(mut entity) = world.get_entity_mut(self.entity) {
             entity.insert_bundle(self.bundle);
+            Ok(())
         } else {
-            panic!("Could not insert a bundle (of type `{}`) for entity {:?} because it doesn't exist in this World.\n\
-                    If this command was added to a newly spawned entity, ensure that you have not despawned that entity within the same stage.\n\
-                    This may have occurred due to system order ambiguity, or if the spawning system has multiple command buffers", std::any::type_name::<T>(), self.entity);
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: format!(
+                    "insert a bundle (of type `{}`) for",
+                    std::any::type_name::<T>()
+                ),
+            })
+        }
+    }
+}
+
+impl<T> Command for InsertBundle<T>
+where
+    T: Bundle + 'static,
+{
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Panic);
         }
     }
 }
@@ -698,17 +1015,33 @@ pub struct Insert<T> {
     pub component: T,
 }
 
-impl<T> Command for Insert<T>
+impl<T> FallibleCommand for Insert<T>
 where
     T: Component,
 {
-    fn write(self, world: &mut World) {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
         if let Some(mut entity) = world.get_entity_mut(self.entity) {
             entity.insert(self.component);
+            Ok(())
         } else {
-            panic!("Could not add a component (of type `{}`) to entity {:?} because it doesn't exist in this World.\n\
-                    If this command was added to a newly spawned entity, ensure that you have not despawned that entity within the same stage.\n\
-                    This may have occurred due to system order ambiguity, or if the spawning system has multiple command buffers", std::any::type_name::<T>(), self.entity);
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: format!(
+                    "add a component (of type `{}`) to",
+                    std::any::type_name::<T>()
+                ),
+            })
+        }
+    }
+}
+
+impl<T> Command for Insert<T>
+where
+    T: Component,
+{
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Panic);
         }
     }
 }
@@ -719,13 +1052,33 @@ pub struct Remove<T> {
     pub phantom: PhantomData<T>,
 }
 
-impl<T> Command for Remove<T>
+impl<T> FallibleCommand for Remove<T>
 where
     T: Component,
 {
-    fn write(self, world: &mut World) {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
         if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
             entity_mut.remove::<T>();
+            Ok(())
+        } else {
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: format!(
+                    "remove a component (of type `{}`) from",
+                    std::any::type_name::<T>()
+                ),
+            })
+        }
+    }
+}
+
+impl<T> Command for Remove<T>
+where
+    T: Component,
+{
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Ignore);
         }
     }
 }
@@ -736,15 +1089,111 @@ pub struct RemoveBundle<T> {
     pub phantom: PhantomData<T>,
 }
 
-impl<T> Command for RemoveBundle<T>
+impl<T> FallibleCommand for RemoveBundle<T>
 where
     T: Bundle,
 {
-    fn write(self, world: &mut World) {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
         if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
             // remove intersection to gracefully handle components that were removed before running
             // this command
             entity_mut.remove_bundle_intersection::<T>();
+            Ok(())
+        } else {
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: format!(
+                    "remove a bundle (of type `{}`) from",
+                    std::any::type_name::<T>()
+                ),
+            })
+        }
+    }
+}
+
+impl<T> Command for RemoveBundle<T>
+where
+    T: Bundle,
+{
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Ignore);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Relate<R> {
+    pub entity: Entity,
+    pub target: Entity,
+    pub phantom: PhantomData<R>,
+}
+
+impl<R> FallibleCommand for Relate<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
+        if let Some(mut entity) = world.get_entity_mut(self.entity) {
+            entity.relate::<R>(self.target);
+            Ok(())
+        } else {
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: format!(
+                    "add a relation (of kind `{}`) to",
+                    std::any::type_name::<R>()
+                ),
+            })
+        }
+    }
+}
+
+impl<R> Command for Relate<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Panic);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Unrelate<R> {
+    pub entity: Entity,
+    pub target: Entity,
+    pub phantom: PhantomData<R>,
+}
+
+impl<R> FallibleCommand for Unrelate<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
+            entity_mut.unrelate::<R>(self.target);
+            Ok(())
+        } else {
+            Err(CommandError::NoSuchEntity {
+                entity: self.entity,
+                action: format!(
+                    "remove a relation (of kind `{}`) from",
+                    std::any::type_name::<R>()
+                ),
+            })
+        }
+    }
+}
+
+impl<R> Command for Unrelate<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn write(self, world: &mut World) {
+        if let Err(error) = self.try_write(world) {
+            handle_command_error(world, error, None, CommandErrorHandler::Ignore);
         }
     }
 }
@@ -779,13 +1228,26 @@ impl<R: Resource> Command for RemoveResource<R> {
     }
 }
 
+#[derive(Debug)]
+pub struct RunRegisteredSystem {
+    pub id: SystemId,
+}
+
+impl Command for RunRegisteredSystem {
+    fn write(self, world: &mut World) {
+        if let Err(err) = world.run_system(self.id) {
+            error!("Could not run system: {}", err);
+        }
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::float_cmp, clippy::approx_constant)]
 mod tests {
     use crate::{
         self as bevy_ecs,
         component::Component,
-        system::{CommandQueue, Commands},
+        system::{CommandError, CommandErrorHandler, CommandQueue, Commands},
         world::World,
     };
     use std::sync::{
@@ -940,4 +1402,105 @@ mod tests {
         assert!(!world.contains_resource::<i32>());
         assert!(world.contains_resource::<f64>());
     }
+
+    #[test]
+    #[should_panic]
+    fn insert_into_despawned_entity() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        Commands::new(&mut queue, &world)
+            .entity(entity)
+            .insert(W(0u32));
+        world.despawn(entity);
+        queue.apply(&mut world);
+    }
+
+    #[test]
+    fn try_commands_on_despawned_entity() {
+        let mut world = World::default();
+        // try_ commands ignore the global handler
+        world.insert_resource(CommandErrorHandler::Panic);
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(entity).despawn();
+            commands
+                .entity(entity)
+                .try_insert(W(0u32))
+                .try_insert_bundle((W(1u64), W(2u8)))
+                .try_despawn();
+        }
+        queue.apply(&mut world);
+        assert!(world.get_entity(entity).is_none());
+    }
+
+    #[derive(Default)]
+    struct Errors(Vec<String>);
+
+    fn record_errors() -> CommandErrorHandler {
+        CommandErrorHandler::custom(|world, error| {
+            let message = match error {
+                CommandError::NoSuchEntity { action, .. } => action,
+                CommandError::Other(error) => error.to_string(),
+            };
+            world.get_resource_mut::<Errors>().unwrap().0.push(message);
+        })
+    }
+
+    #[test]
+    fn commands_error_handler() {
+        let mut world = World::default();
+        world.init_resource::<Errors>();
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            // errors of commands added before the handler is set are not handled by it
+            commands.entity(entity).remove::<W<u32>>();
+            commands.set_error_handler(record_errors());
+            commands.entity(entity).insert(W(0u32)).remove::<W<u32>>();
+            commands.add_fallible(|_: &mut World| Err(CommandError::other("custom")));
+            commands.add_fallible(|_: &mut World| Ok(()));
+            commands.entity(entity).despawn();
+        }
+        world.despawn(entity);
+        queue.apply(&mut world);
+        assert_eq!(
+            world.get_resource::<Errors>().unwrap().0,
+            vec![
+                format!(
+                    "add a component (of type `{}`) to",
+                    std::any::type_name::<W<u32>>()
+                ),
+                format!(
+                    "remove a component (of type `{}`) from",
+                    std::any::type_name::<W<u32>>()
+                ),
+                "custom".to_string(),
+                "despawn".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn global_error_handler() {
+        let mut world = World::default();
+        world.init_resource::<Errors>();
+        world.insert_resource(record_errors());
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(entity).insert(W(0u32)).despawn();
+            commands.add_fallible(|_: &mut World| Err(CommandError::other("custom")));
+            // a Commands handler takes precedence over the global one
+            commands.set_error_handler(CommandErrorHandler::Ignore);
+            commands.entity(entity).insert_bundle((W(0u32), W(0u64)));
+        }
+        world.despawn(entity);
+        queue.apply(&mut world);
+        assert_eq!(world.get_resource::<Errors>().unwrap().0.len(), 3);
+    }
 }
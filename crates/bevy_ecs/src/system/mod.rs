@@ -58,6 +58,7 @@
 //! - [`NonSendMut`] and `Option<NonSendMut>`
 //! - [`&World`](crate::world::World)
 //! - [`RemovedComponents`]
+//! - [`RemovedResources`]
 //! - [`SystemChangeTick`]
 //! - [`Archetypes`](crate::archetype::Archetypes) (Provides Archetype metadata)
 //! - [`Bundles`](crate::bundle::Bundles) (Provides Bundles metadata)
@@ -74,6 +75,7 @@ mod query;
 mod system;
 mod system_chaining;
 mod system_param;
+mod system_registry;
 
 pub use commands::*;
 pub use exclusive_system::*;
@@ -82,6 +84,7 @@ pub use query::*;
 pub use system::*;
 pub use system_chaining::*;
 pub use system_param::*;
+pub use system_registry::*;
 
 pub fn assert_is_system<In, Out, Params, S: IntoSystem<In, Out, Params>>(sys: S) {
     if false {
@@ -98,13 +101,14 @@ mod tests {
         self as bevy_ecs,
         archetype::Archetypes,
         bundle::Bundles,
+        change_detection::DetectChanges,
         component::{Component, Components},
         entity::{Entities, Entity},
         query::{Added, Changed, Or, QueryState, With, Without},
         schedule::{Schedule, Stage, SystemStage},
         system::{
             IntoExclusiveSystem, IntoSystem, Local, NonSend, NonSendMut, Query, QuerySet,
-            RemovedComponents, Res, ResMut, System, SystemState,
+            RemovedComponents, RemovedResources, Res, ResMut, System, SystemState,
         },
         world::{FromWorld, World},
     };
@@ -284,6 +288,60 @@ mod tests {
         assert_eq!(world.resource::<Changed>().0, 2);
     }
 
+    #[test]
+    fn bypass_change_detection_system() {
+        struct Changed(usize);
+        fn count_changes(value: Res<u32>, mut changed: ResMut<Changed>) {
+            if value.is_changed() {
+                changed.0 += 1;
+            }
+        }
+
+        let mut world = World::default();
+        world.insert_resource(0u32);
+        world.insert_resource(Changed(0));
+        let mut stage = SystemStage::parallel().with_system(count_changes);
+        stage.run(&mut world);
+        assert_eq!(world.resource::<Changed>().0, 1);
+
+        let last_changed = world.resource_mut::<u32>().last_changed();
+        *world.resource_mut::<u32>().bypass_change_detection() = 1;
+        assert_eq!(world.resource_mut::<u32>().last_changed(), last_changed);
+        stage.run(&mut world);
+        assert_eq!(world.resource::<Changed>().0, 1);
+        assert_eq!(*world.resource::<u32>(), 1);
+
+        *world.resource_mut::<u32>() = 2;
+        assert_ne!(world.resource_mut::<u32>().last_changed(), last_changed);
+        stage.run(&mut world);
+        assert_eq!(world.resource::<Changed>().0, 2);
+    }
+
+    #[test]
+    fn removed_resource_system() {
+        struct Removed(bool);
+        fn track_removal(removed_u32: RemovedResources<u32>, mut removed: ResMut<Removed>) {
+            removed.0 = removed_u32.is_removed();
+        }
+
+        let mut world = World::default();
+        world.insert_resource(0u32);
+        world.insert_resource(Removed(false));
+        let mut stage = SystemStage::parallel().with_system(track_removal);
+        stage.run(&mut world);
+        assert!(!world.resource::<Removed>().0);
+
+        world.remove_resource::<u32>();
+        assert!(world.is_resource_removed::<u32>());
+        stage.run(&mut world);
+        assert!(world.resource::<Removed>().0);
+
+        world.clear_trackers();
+        assert!(!world.is_resource_removed::<u32>());
+        stage.run(&mut world);
+        assert!(!world.resource::<Removed>().0);
+    }
+
     #[test]
     #[should_panic]
     fn conflicting_query_mut_system() {
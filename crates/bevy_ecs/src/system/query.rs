@@ -2,8 +2,8 @@ use crate::{
     component::Component,
     entity::Entity,
     query::{
-        Fetch, FilterFetch, NopFetch, QueryCombinationIter, QueryEntityError, QueryIter,
-        QueryState, WorldQuery,
+        verify_entities_unique, Fetch, FilterFetch, NopFetch, QueryCombinationIter,
+        QueryEntityError, QueryIter, QueryParIter, QueryState, WorldQuery,
     },
     world::{Mut, World},
 };
@@ -568,6 +568,79 @@ where
         };
     }
 
+    /// Returns a [`ParallelIterator`](bevy_tasks::ParallelIterator) over the query results.
+    ///
+    /// Unlike [`Self::par_for_each`], the batch size is picked automatically from the number of
+    /// entities the query matches, and can be overridden with [`QueryParIter::batch_size`].
+    ///
+    /// This can only return immutable data, see [`Self::par_iter_mut`] for mutable access.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_tasks::{ComputeTaskPool, ParallelIterator};
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Player;
+    /// #
+    /// fn count_players_system(query: Query<&Player>, pool: Res<ComputeTaskPool>) {
+    ///     let count = AtomicUsize::new(0);
+    ///     query.par_iter().for_each(&pool, |_player| {
+    ///         count.fetch_add(1, Ordering::Relaxed);
+    ///     });
+    ///     println!("{} players", count.into_inner());
+    /// }
+    /// # bevy_ecs::system::assert_is_system(count_players_system);
+    /// ```
+    #[inline]
+    pub fn par_iter(&'s self) -> QueryParIter<'w, 's, Q, Q::ReadOnlyFetch, F> {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.par_iter_unchecked_manual(
+                self.world,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns a [`ParallelIterator`](bevy_tasks::ParallelIterator) over the query results.
+    /// See [`Self::par_iter`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_tasks::{ComputeTaskPool, ParallelIterator};
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Position(f32);
+    /// # #[derive(Component)]
+    /// # struct Velocity(f32);
+    /// #
+    /// fn move_system(mut query: Query<(&mut Position, &Velocity)>, pool: Res<ComputeTaskPool>) {
+    ///     query
+    ///         .par_iter_mut()
+    ///         .for_each(&pool, |(mut position, velocity)| position.0 += velocity.0);
+    /// }
+    /// # bevy_ecs::system::assert_is_system(move_system);
+    /// ```
+    #[inline]
+    pub fn par_iter_mut(&mut self) -> QueryParIter<'_, '_, Q, Q::Fetch, F> {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.par_iter_unchecked_manual(
+                self.world,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Returns the query result for the given [`Entity`].
     ///
     /// In case of a nonexisting entity or mismatched component, a [`QueryEntityError`] is
@@ -657,6 +730,71 @@ where
         }
     }
 
+    /// Returns the read-only query results for the given array of [`Entity`]s.
+    ///
+    /// In case of a nonexisting entity or mismatched component, a [`QueryEntityError`] is
+    /// returned instead. The same entity can be requested more than once.
+    ///
+    /// See [`get_many_mut`](Self::get_many_mut) for queries that contain at least one mutable
+    /// component.
+    #[inline]
+    pub fn get_many<const N: usize>(
+        &'s self,
+        entities: [Entity; N],
+    ) -> Result<[<Q::ReadOnlyFetch as Fetch<'w, 's>>::Item; N], QueryEntityError> {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.get_many_unchecked_manual::<Q::ReadOnlyFetch, N>(
+                self.world,
+                entities,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns the query results for the given array of [`Entity`]s.
+    ///
+    /// In case of a nonexisting entity or mismatched component, a [`QueryEntityError`] is
+    /// returned instead. [`QueryEntityError::AliasedMutability`] is returned if the same entity
+    /// is requested more than once, as that would give two mutable references to its components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Velocity(f32);
+    /// #
+    /// # struct Collision(Entity, Entity);
+    /// #
+    /// fn bounce_system(mut query: Query<&mut Velocity>, collision: Res<Collision>) {
+    ///     if let Ok([mut a, mut b]) = query.get_many_mut([collision.0, collision.1]) {
+    ///         std::mem::swap(&mut a.0, &mut b.0);
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(bounce_system);
+    /// ```
+    #[inline]
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[<Q::Fetch as Fetch<'_, '_>>::Item; N], QueryEntityError> {
+        verify_entities_unique(&entities)?;
+        // SAFE: system runs without conflicts with other systems, and the entities are unique.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.get_many_unchecked_manual::<Q::Fetch, N>(
+                self.world,
+                entities,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Returns the query result for the given [`Entity`].
     ///
     /// In case of a nonexisting entity or mismatched component, a [`QueryEntityError`] is
@@ -979,6 +1117,35 @@ where
             .is_empty(self.world, self.last_change_tick, self.change_tick)
     }
 
+    /// Sets the target of the [`RelatedTo<R>`](crate::query::RelatedTo) filters of this query. It
+    /// is used by the iterations that start after this call, and kept for the next runs of the
+    /// system.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, query::RelatedTo};
+    /// #
+    /// # struct OwnedBy;
+    /// # #[derive(Component)]
+    /// # struct Gold(u32);
+    /// # struct Player {
+    /// #     entity: Entity,
+    /// # }
+    /// #
+    /// fn player_gold_system(mut query: Query<&Gold, RelatedTo<OwnedBy>>, player: Res<Player>) {
+    ///     query.set_relation_target::<OwnedBy>(player.entity);
+    ///     let gold: u32 = query.iter().map(|gold| gold.0).sum();
+    ///     println!("the player owns {} gold", gold);
+    /// }
+    /// # bevy_ecs::system::assert_is_system(player_gold_system);
+    /// ```
+    #[inline]
+    pub fn set_relation_target<R: Send + Sync + 'static>(&mut self, target: Entity) {
+        self.state
+            .set_relation_target_manual::<R>(self.world, target);
+    }
+
     /// Returns `true` if the given [`Entity`] matches the query.
     ///
     /// # Example
@@ -233,6 +233,11 @@ impl<'w, T: Resource> Res<'w, T> {
             .is_changed(self.last_change_tick, self.change_tick)
     }
 
+    /// Returns the change tick recording the previous time this resource was changed.
+    pub fn last_changed(&self) -> u32 {
+        self.ticks.changed
+    }
+
     pub fn into_inner(self) -> &'w T {
         self.value
     }
@@ -745,6 +750,83 @@ impl<'w, 's, T: Component> SystemParamFetch<'w, 's> for RemovedComponentsState<T
     }
 }
 
+/// A [`SystemParam`] that tells if the `T` resource was removed.
+///
+/// Like [`RemovedComponents`], the removals are tracked until [`World::clear_trackers`] is
+/// called, which `bevy` does at the end of each pass of the game loop. A resource that was removed
+/// and then inserted again is still reported as removed; use [`Res::is_added`] to detect the new
+/// resource.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs::system::RemovedResources;
+/// #
+/// struct Connection;
+///
+/// fn react_on_disconnect(removed: RemovedResources<Connection>) {
+///     if removed.is_removed() {
+///         println!("disconnected");
+///     }
+/// }
+///
+/// # bevy_ecs::system::assert_is_system(react_on_disconnect);
+/// ```
+pub struct RemovedResources<'a, T: Resource> {
+    world: &'a World,
+    component_id: ComponentId,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: Resource> RemovedResources<'a, T> {
+    /// Returns `true` if the `T` resource was removed.
+    pub fn is_removed(&self) -> bool {
+        self.world.removed_resources.contains(&self.component_id)
+    }
+}
+
+// SAFE: Only reads World removed resources
+unsafe impl<T: Resource> ReadOnlySystemParamFetch for RemovedResourcesState<T> {}
+
+/// The [`SystemParamState`] of [`RemovedResources<T>`].
+pub struct RemovedResourcesState<T> {
+    component_id: ComponentId,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: Resource> SystemParam for RemovedResources<'a, T> {
+    type Fetch = RemovedResourcesState<T>;
+}
+
+// SAFE: no resource access. the removed resources can be read in parallel and are never mutably
+// borrowed during system execution
+unsafe impl<T: Resource> SystemParamState for RemovedResourcesState<T> {
+    fn init(world: &mut World, _system_meta: &mut SystemMeta) -> Self {
+        Self {
+            component_id: world.initialize_resource::<T>(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'w, 's, T: Resource> SystemParamFetch<'w, 's> for RemovedResourcesState<T> {
+    type Item = RemovedResources<'w, T>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'s mut Self,
+        _system_meta: &SystemMeta,
+        world: &'w World,
+        _change_tick: u32,
+    ) -> Self::Item {
+        RemovedResources {
+            world,
+            component_id: state.component_id,
+            marker: PhantomData,
+        }
+    }
+}
+
 /// Shared borrow of a non-[`Send`] resource.
 ///
 /// Only `Send` resources may be accessed with the [`Res`] [`SystemParam`]. In case that the
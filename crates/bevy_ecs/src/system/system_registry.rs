@@ -0,0 +1,275 @@
+use crate::{
+    archetype::{ArchetypeGeneration, ArchetypeId},
+    system::{BoxedSystem, IntoSystem},
+    world::World,
+};
+use thiserror::Error;
+
+/// A system stored in a [`World`] that can be run on demand, keeping its state (such as
+/// [`Local`](crate::system::Local)s and change ticks) between runs.
+pub(crate) struct StoredSystem<In, Out> {
+    system: BoxedSystem<In, Out>,
+    archetype_generation: ArchetypeGeneration,
+}
+
+impl<In: 'static, Out: 'static> StoredSystem<In, Out> {
+    /// Stores `system`, initializing it in `world`.
+    pub(crate) fn new(mut system: BoxedSystem<In, Out>, world: &mut World) -> Self {
+        system.initialize(world);
+        Self {
+            system,
+            archetype_generation: ArchetypeGeneration::initial(),
+        }
+    }
+
+    /// Runs the system and applies its buffers.
+    pub(crate) fn run(&mut self, input: In, world: &mut World) -> Out {
+        let archetypes = world.archetypes();
+        let new_generation = archetypes.generation();
+        let old_generation = std::mem::replace(&mut self.archetype_generation, new_generation);
+        for archetype_index in old_generation.value()..new_generation.value() {
+            self.system
+                .new_archetype(&archetypes[ArchetypeId::new(archetype_index)]);
+        }
+
+        let out = self.system.run(input, world);
+        self.system.apply_buffers(world);
+        out
+    }
+
+    pub(crate) fn check_change_tick(&mut self, change_tick: u32) {
+        self.system.check_change_tick(change_tick);
+    }
+}
+
+/// An identifier for a system registered with [`World::register_system`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemId(usize);
+
+/// An error that occurs when running a registered system.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegisteredSystemError {
+    #[error("System {0:?} is not registered in this World")]
+    SystemIdNotRegistered(SystemId),
+    #[error("System {0:?} tried to run itself")]
+    Recursive(SystemId),
+}
+
+/// Stores the systems registered with [`World::register_system`].
+#[derive(Default)]
+pub struct SystemRegistry {
+    /// `None` if the system was removed. The inner `None` marks a system that is currently
+    /// running, and has been temporarily taken out of the registry.
+    systems: Vec<Option<Option<StoredSystem<(), ()>>>>,
+}
+
+impl SystemRegistry {
+    /// Returns `true` if `id` refers to a system that has not been removed.
+    #[inline]
+    pub fn contains(&self, id: SystemId) -> bool {
+        matches!(self.systems.get(id.0), Some(Some(_)))
+    }
+
+    /// Returns the number of registered systems.
+    pub fn len(&self) -> usize {
+        self.systems.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
+        for system in self.systems.iter_mut().flatten().flatten() {
+            system.check_change_tick(change_tick);
+        }
+    }
+}
+
+impl World {
+    /// Registers `system` so that it can be run later with [`World::run_system`] or
+    /// [`Commands::run_system`](crate::system::Commands::run_system), and returns its
+    /// [`SystemId`].
+    ///
+    /// The system is initialized immediately. Its state, such as [`Local`](crate::system::Local)
+    /// parameters and the change ticks used by [`Changed`](crate::query::Changed) and
+    /// [`Added`](crate::query::Added) filters, is kept between runs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// fn increment(mut counter: Local<usize>, mut total: ResMut<usize>) {
+    ///     *counter += 1;
+    ///     *total += *counter;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(0usize);
+    /// let id = world.register_system(increment);
+    /// world.run_system(id).unwrap();
+    /// world.run_system(id).unwrap();
+    /// assert_eq!(*world.get_resource::<usize>().unwrap(), 3);
+    /// ```
+    pub fn register_system<Params>(&mut self, system: impl IntoSystem<(), (), Params>) -> SystemId {
+        let system = StoredSystem::new(Box::new(IntoSystem::into_system(system)), self);
+        let registry = &mut self.system_registry;
+        registry.systems.push(Some(Some(system)));
+        SystemId(registry.systems.len() - 1)
+    }
+
+    /// Runs the system registered as `id` and applies its buffers, such as
+    /// [`Commands`](crate::system::Commands), right after.
+    ///
+    /// Returns an error if `id` was not registered in this world or was removed, or if the system
+    /// is already running (for example, if it runs itself through a command).
+    pub fn run_system(&mut self, id: SystemId) -> Result<(), RegisteredSystemError> {
+        let mut system = match self.system_registry.systems.get_mut(id.0) {
+            Some(Some(slot)) => slot.take().ok_or(RegisteredSystemError::Recursive(id))?,
+            _ => return Err(RegisteredSystemError::SystemIdNotRegistered(id)),
+        };
+        system.run((), self);
+        // the system may have been removed while it was running
+        if let Some(Some(slot)) = self.system_registry.systems.get_mut(id.0) {
+            *slot = Some(system);
+        }
+        Ok(())
+    }
+
+    /// Removes the system registered as `id`. Returns `false` if there was no such system.
+    ///
+    /// The [`SystemId`] is not reused by later registrations.
+    pub fn remove_system(&mut self, id: SystemId) -> bool {
+        match self.system_registry.systems.get_mut(id.0) {
+            Some(slot) => slot.take().is_some(),
+            None => false,
+        }
+    }
+
+    /// Retrieves this world's [`SystemRegistry`].
+    #[inline]
+    pub fn system_registry(&self) -> &SystemRegistry {
+        &self.system_registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        component::Component,
+        query::Changed,
+        system::{Commands, Local, Query, RegisteredSystemError, Res, ResMut, SystemId},
+        world::World,
+    };
+
+    #[derive(Component)]
+    struct A(usize);
+
+    #[test]
+    fn local_state_is_kept() {
+        fn count(mut runs: Local<usize>, mut counts: ResMut<Vec<usize>>) {
+            *runs += 1;
+            counts.push(*runs);
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let id = world.register_system(count);
+        let other = world.register_system(count);
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        world.run_system(other).unwrap();
+        assert_eq!(world.system_registry().len(), 2);
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn change_ticks_are_kept() {
+        fn changed(query: Query<&A, Changed<A>>, mut counts: ResMut<Vec<usize>>) {
+            counts.push(query.iter().count());
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let entity = world.spawn().insert(A(0)).id();
+        world.spawn().insert(A(0));
+        let id = world.register_system(changed);
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        world.get_mut::<A>(entity).unwrap().0 = 1;
+        world.run_system(id).unwrap();
+        assert_eq!(*world.get_resource::<Vec<usize>>().unwrap(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn commands_are_applied() {
+        fn spawn(mut commands: Commands) {
+            commands.spawn().insert(A(0));
+        }
+
+        let mut world = World::new();
+        let id = world.register_system(spawn);
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        assert_eq!(world.query::<&A>().iter(&world).count(), 2);
+    }
+
+    #[test]
+    fn run_system_from_commands() {
+        struct Callback(SystemId);
+
+        fn button(mut commands: Commands, callback: Res<Callback>) {
+            commands.run_system(callback.0);
+        }
+
+        fn callback(mut commands: Commands) {
+            commands.spawn().insert(A(1));
+        }
+
+        let mut world = World::new();
+        let callback = world.register_system(callback);
+        world.insert_resource(Callback(callback));
+        let button = world.register_system(button);
+        world.run_system(button).unwrap();
+        assert_eq!(world.query::<&A>().iter(&world).count(), 1);
+    }
+
+    #[test]
+    fn recursive_system() {
+        struct SelfId(SystemId);
+
+        fn run_self(mut commands: Commands) {
+            commands.add(|world: &mut World| {
+                let id = world.get_resource::<SelfId>().unwrap().0;
+                let result = world.run_system(id);
+                world.insert_resource(result);
+            });
+        }
+
+        let mut world = World::new();
+        let id = world.register_system(run_self);
+        world.insert_resource(SelfId(id));
+        world.run_system(id).unwrap();
+        assert_eq!(
+            world.get_resource::<Result<(), RegisteredSystemError>>(),
+            Some(&Err(RegisteredSystemError::Recursive(id)))
+        );
+        assert!(world.system_registry().contains(id));
+    }
+
+    #[test]
+    fn removed_system() {
+        let mut world = World::new();
+        let id = world.register_system(|| {});
+        assert!(world.remove_system(id));
+        assert!(!world.remove_system(id));
+        assert!(!world.system_registry().contains(id));
+        assert_eq!(
+            world.run_system(id),
+            Err(RegisteredSystemError::SystemIdNotRegistered(id))
+        );
+    }
+}
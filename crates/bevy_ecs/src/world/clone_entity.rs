@@ -0,0 +1,545 @@
+use crate::{
+    entity::{Entity, EntityMap, MapEntitiesError},
+    reflect::{ReflectCloneMapped, ReflectComponent, ReflectMapEntities},
+    world::World,
+};
+use bevy_reflect::TypeRegistryArc;
+use thiserror::Error;
+
+/// An error that occurred while cloning entities with [`World::clone_entity`],
+/// [`World::clone_entities`] or [`World::clone_entities_to`].
+#[derive(Error, Debug)]
+pub enum CloneEntityError {
+    #[error("entity {0:?} does not exist")]
+    NoSuchEntity(Entity),
+    #[error("the world has no `TypeRegistryArc` resource to look up the components to clone")]
+    MissingTypeRegistry,
+    #[error("entity contains the unregistered component `{type_name}`. consider adding `#[reflect(Component)]` to your type")]
+    UnregisteredComponent { type_name: String },
+    #[error("entity contains the unregistered type `{type_name}`. consider registering the type using `app.register_type::<T>()`")]
+    UnregisteredType { type_name: String },
+    #[error("entity {0:?} is referenced by a cloned component, but isn't mapped to an entity of the destination world")]
+    UnmappedEntity(Entity),
+}
+
+impl World {
+    /// Spawns a new entity with a copy of every component of `entity`, and returns it.
+    ///
+    /// The components are copied through their [`ReflectComponent`] registration in the
+    /// [`TypeRegistryArc`] resource, so every component of `entity` must be registered with
+    /// `#[reflect(Component)]`. Entities referenced by the components are left as they are, except
+    /// that the components registered with [`ReflectCloneMapped`], such as the children of an
+    /// entity, are not copied. Use [`World::clone_entities`] to clone several entities that
+    /// reference each other.
+    ///
+    /// ```
+    /// use bevy_ecs::{prelude::*, reflect::ReflectComponent};
+    /// use bevy_reflect::{Reflect, TypeRegistryArc};
+    ///
+    /// #[derive(Component, Reflect, Default, PartialEq, Debug)]
+    /// #[reflect(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// let type_registry = TypeRegistryArc::default();
+    /// type_registry.write().register::<Health>();
+    /// world.insert_resource(type_registry);
+    ///
+    /// let entity = world.spawn().insert(Health(10)).id();
+    /// let clone = world.clone_entity(entity).unwrap();
+    /// assert_eq!(world.get::<Health>(clone), Some(&Health(10)));
+    /// ```
+    pub fn clone_entity(&mut self, entity: Entity) -> Result<Entity, CloneEntityError> {
+        let entity_map = self.clone_entities(&[entity])?;
+        Ok(entity_map.get(entity).unwrap())
+    }
+
+    /// Spawns a copy of each of `entities`, and returns the map from each entity to its clone.
+    ///
+    /// References between the cloned entities, such as a `Parent` component pointing to another
+    /// cloned entity, are remapped to the clones with the [`ReflectMapEntities`] registration of
+    /// the component. References to other entities are left as they are, unless the component is
+    /// registered with [`ReflectCloneMapped`], in which case it is not copied to the clone.
+    ///
+    /// Nothing is spawned if an error is returned.
+    pub fn clone_entities(&mut self, entities: &[Entity]) -> Result<EntityMap, CloneEntityError> {
+        if let Some(entity) = entities
+            .iter()
+            .find(|entity| !self.entities.contains(**entity))
+        {
+            return Err(CloneEntityError::NoSuchEntity(*entity));
+        }
+        let mut entity_map = EntityMap::default();
+        for entity in entities {
+            entity_map.insert(*entity, self.spawn().id());
+        }
+        if let Err(error) = self.clone_entities_into(&entity_map) {
+            for clone in entity_map.values() {
+                self.despawn(clone);
+            }
+            return Err(error);
+        }
+        Ok(entity_map)
+    }
+
+    /// Copies every component of each key of `entity_map` to the entity it maps to, which must
+    /// already exist. This is used by the commands that clone entities, as they reserve the clones
+    /// before the command is applied.
+    ///
+    /// References are remapped like in [`World::clone_entities`]. The components are checked
+    /// before anything is copied, so nothing changes if an error is returned.
+    pub fn clone_entities_into(&mut self, entity_map: &EntityMap) -> Result<(), CloneEntityError> {
+        let clones = component_registrations(self, self, entity_map)?;
+        for (source, destination, components) in &clones {
+            for (reflect_component, _, _) in components {
+                let component = reflect_component
+                    .reflect_component(self, *source)
+                    .unwrap()
+                    .clone_value();
+                reflect_component.add_component(self, *destination, &*component);
+            }
+        }
+        map_entities(
+            self,
+            entity_map,
+            None,
+            &clones,
+            |world, reflect_component, source, destination| {
+                let component = reflect_component
+                    .reflect_component(world, source)
+                    .unwrap()
+                    .clone_value();
+                reflect_component.apply_component(world, destination, &*component);
+            },
+        )
+    }
+
+    /// Spawns a copy of each of `entities` in the `destination` world, and returns the map from
+    /// each entity to its clone. This can be used to move data between the worlds of sub apps,
+    /// such as a server simulation and a client prediction world running in the same process.
+    ///
+    /// The components are looked up in the [`TypeRegistryArc`] resource of this world; the
+    /// `destination` world doesn't need one. References between the cloned entities are remapped
+    /// to the clones like in [`World::clone_entities`]. References to other entities are mapped
+    /// with `external`, which maps entities of this world to entities of `destination`, such as
+    /// the ones returned by a previous call. A reference to an entity that is in neither map
+    /// returns a [`CloneEntityError::UnmappedEntity`] error, except in the components registered
+    /// with [`ReflectCloneMapped`], which are not copied to the clone instead.
+    ///
+    /// Nothing is spawned if an error is returned.
+    ///
+    /// ```
+    /// use bevy_ecs::{entity::EntityMap, prelude::*, reflect::ReflectComponent};
+    /// use bevy_reflect::{Reflect, TypeRegistryArc};
+    ///
+    /// #[derive(Component, Reflect, Default, PartialEq, Debug)]
+    /// #[reflect(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut server = World::new();
+    /// let type_registry = TypeRegistryArc::default();
+    /// type_registry.write().register::<Health>();
+    /// server.insert_resource(type_registry);
+    ///
+    /// let mut client = World::new();
+    /// let entity = server.spawn().insert(Health(10)).id();
+    /// let entity_map = server
+    ///     .clone_entities_to(&[entity], &mut client, &EntityMap::default())
+    ///     .unwrap();
+    /// let clone = entity_map.get(entity).unwrap();
+    /// assert_eq!(client.get::<Health>(clone), Some(&Health(10)));
+    /// ```
+    pub fn clone_entities_to(
+        &self,
+        entities: &[Entity],
+        destination: &mut World,
+        external: &EntityMap,
+    ) -> Result<EntityMap, CloneEntityError> {
+        if let Some(entity) = entities
+            .iter()
+            .find(|entity| !self.entities.contains(**entity))
+        {
+            return Err(CloneEntityError::NoSuchEntity(*entity));
+        }
+        let mut entity_map = EntityMap::default();
+        for entity in entities {
+            entity_map.insert(*entity, destination.spawn().id());
+        }
+        if let Err(error) = self.clone_entities_into_world(destination, &entity_map, external) {
+            for clone in entity_map.values() {
+                destination.despawn(clone);
+            }
+            return Err(error);
+        }
+        Ok(entity_map)
+    }
+
+    /// Copies every component of each key of `entity_map` to the entity of the `destination`
+    /// world it maps to, which must already exist.
+    ///
+    /// References are remapped like in [`World::clone_entities_to`]. If an error is returned, the
+    /// components that were copied are removed again.
+    pub fn clone_entities_into_world(
+        &self,
+        destination: &mut World,
+        entity_map: &EntityMap,
+        external: &EntityMap,
+    ) -> Result<(), CloneEntityError> {
+        let clones = component_registrations(self, destination, entity_map)?;
+        for (source, clone, components) in &clones {
+            for (reflect_component, _, _) in components {
+                let component = reflect_component.reflect_component(self, *source).unwrap();
+                reflect_component.add_component(destination, *clone, component);
+            }
+        }
+        let result = map_entities(
+            destination,
+            entity_map,
+            Some(external),
+            &clones,
+            |destination, reflect_component, source, clone| {
+                let component = reflect_component.reflect_component(self, source).unwrap();
+                reflect_component.apply_component(destination, clone, component);
+            },
+        );
+        if result.is_err() {
+            for (_, clone, components) in &clones {
+                for (reflect_component, _, _) in components {
+                    reflect_component.remove_component(destination, *clone);
+                }
+            }
+        }
+        result
+    }
+
+    /// Moves each of `entities` to the `destination` world: they are cloned like with
+    /// [`World::clone_entities_to`], then despawned from this world. Returns the map from each
+    /// entity to the entity that replaces it in `destination`. References to entities that are not
+    /// moved are mapped with `external`.
+    ///
+    /// Nothing changes in either world if an error is returned.
+    pub fn move_entities_to(
+        &mut self,
+        entities: &[Entity],
+        destination: &mut World,
+        external: &EntityMap,
+    ) -> Result<EntityMap, CloneEntityError> {
+        let entity_map = self.clone_entities_to(entities, destination, external)?;
+        for entity in entities {
+            self.despawn(*entity);
+        }
+        Ok(entity_map)
+    }
+}
+
+/// The registrations of each component to clone, and whether it is registered with
+/// [`ReflectCloneMapped`].
+type ComponentRegistrations = Vec<(ReflectComponent, Option<ReflectMapEntities>, bool)>;
+
+/// Looks up the registrations of the components of each key of `entity_map` in the type registry
+/// of `source`, and checks that the entities it maps to exist in `destination`.
+fn component_registrations(
+    source: &World,
+    destination: &World,
+    entity_map: &EntityMap,
+) -> Result<Vec<(Entity, Entity, ComponentRegistrations)>, CloneEntityError> {
+    let type_registry = source
+        .get_resource::<TypeRegistryArc>()
+        .ok_or(CloneEntityError::MissingTypeRegistry)?
+        .read();
+
+    let mut clones = Vec::new();
+    for entity in entity_map.keys() {
+        let clone = entity_map.get(entity).unwrap();
+        let location = source
+            .entities
+            .get(entity)
+            .ok_or(CloneEntityError::NoSuchEntity(entity))?;
+        if !destination.entities.contains(clone) {
+            return Err(CloneEntityError::NoSuchEntity(clone));
+        }
+        let mut components = Vec::new();
+        for component_id in source.archetypes[location.archetype_id].components() {
+            let component_info = source
+                .components
+                .get_info(component_id)
+                .expect("component_ids in archetypes should have ComponentInfo");
+            let registration = component_info
+                .type_id()
+                .and_then(|type_id| type_registry.get(type_id))
+                .ok_or_else(|| CloneEntityError::UnregisteredType {
+                    type_name: component_info.name().to_string(),
+                })?;
+            let reflect_component = registration.data::<ReflectComponent>().ok_or_else(|| {
+                CloneEntityError::UnregisteredComponent {
+                    type_name: component_info.name().to_string(),
+                }
+            })?;
+            components.push((
+                reflect_component.clone(),
+                registration.data::<ReflectMapEntities>().cloned(),
+                registration.data::<ReflectCloneMapped>().is_some(),
+            ));
+        }
+        clones.push((entity, clone, components));
+    }
+    Ok(clones)
+}
+
+/// Remaps the references of the copied components of `clones` in `destination` with
+/// `entity_map`, then with `external` for a clone to another world. `reapply` applies the
+/// component of the source entity to the clone again.
+///
+/// The components registered with [`ReflectCloneMapped`] that refer to entities outside of
+/// `entity_map` are removed from the clone.
+fn map_entities(
+    destination: &mut World,
+    entity_map: &EntityMap,
+    external: Option<&EntityMap>,
+    clones: &[(Entity, Entity, ComponentRegistrations)],
+    mut reapply: impl FnMut(&mut World, &ReflectComponent, Entity, Entity),
+) -> Result<(), CloneEntityError> {
+    let mut references = EntityMap::default();
+    for map in external.into_iter().chain(Some(entity_map)) {
+        for entity in map.keys() {
+            references.insert(entity, map.get(entity).unwrap());
+        }
+    }
+    for (source, clone, components) in clones {
+        for (reflect_component, reflect_map_entities, clone_mapped) in components {
+            let reflect_map_entities = match reflect_map_entities {
+                Some(reflect_map_entities) => reflect_map_entities,
+                None => continue,
+            };
+            if *clone_mapped {
+                if reflect_map_entities
+                    .map_entity(destination, *clone, entity_map)
+                    .is_err()
+                {
+                    reflect_component.remove_component(destination, *clone);
+                }
+                continue;
+            }
+            while let Err(MapEntitiesError::EntityNotFound(entity)) =
+                reflect_map_entities.map_entity(destination, *clone, &references)
+            {
+                // `EntityMap` fails on entities it doesn't contain, so within a world the entities
+                // referenced from outside of the cloned set are added to the map as they are
+                // found, and mapped to themselves
+                if external.is_some() {
+                    return Err(CloneEntityError::UnmappedEntity(entity));
+                }
+                references.insert(entity, entity);
+                // the component may have been partially mapped, so start over from the source
+                reapply(destination, reflect_component, *source, *clone);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CloneEntityError;
+    use crate::{
+        self as bevy_ecs,
+        component::{Component, Disabled},
+        entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+        reflect::{ReflectComponent, ReflectMapEntities},
+        system::{CommandQueue, Commands},
+        world::{FromWorld, World},
+    };
+    use bevy_reflect::{Reflect, TypeRegistryArc};
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Component, MapEntities)]
+    struct Targets(Vec<Entity>);
+
+    impl FromWorld for Targets {
+        fn from_world(_world: &mut World) -> Self {
+            Targets(Vec::new())
+        }
+    }
+
+    impl MapEntities for Targets {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            for entity in &mut self.0 {
+                *entity = entity_map.get(*entity)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Component)]
+    struct Unregistered;
+
+    fn world_with_registry() -> World {
+        let mut world = World::new();
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Health>();
+            type_registry.register::<Targets>();
+            type_registry.register::<Disabled>();
+        }
+        world.insert_resource(type_registry);
+        world
+    }
+
+    #[test]
+    fn clone_entity_copies_components() {
+        let mut world = world_with_registry();
+        let entity = world.spawn().insert(Health(3)).id();
+        let clone = world.clone_entity(entity).unwrap();
+        assert_ne!(entity, clone);
+        assert_eq!(world.get::<Health>(clone), Some(&Health(3)));
+        assert_eq!(world.get::<Health>(entity), Some(&Health(3)));
+    }
+
+    #[test]
+    fn clone_disabled_entity() {
+        let mut world = world_with_registry();
+        let entity = world.spawn().insert_bundle((Health(3), Disabled)).id();
+        let clone = world.clone_entity(entity).unwrap();
+        assert_eq!(world.get::<Health>(clone), Some(&Health(3)));
+        assert!(world.get::<Disabled>(clone).is_some());
+    }
+
+    #[test]
+    fn clone_entities_maps_references() {
+        let mut world = world_with_registry();
+        let outside = world.spawn().id();
+        let a = world.spawn().id();
+        let b = world.spawn().insert(Targets(vec![a, outside])).id();
+        world.entity_mut(a).insert(Targets(vec![b]));
+
+        let entity_map = world.clone_entities(&[a, b]).unwrap();
+        let a_clone = entity_map.get(a).unwrap();
+        let b_clone = entity_map.get(b).unwrap();
+        assert_eq!(world.get::<Targets>(a_clone), Some(&Targets(vec![b_clone])));
+        assert_eq!(
+            world.get::<Targets>(b_clone),
+            Some(&Targets(vec![a_clone, outside]))
+        );
+        // the originals are untouched
+        assert_eq!(world.get::<Targets>(a), Some(&Targets(vec![b])));
+    }
+
+    #[test]
+    fn clone_entity_with_unregistered_component() {
+        let mut world = world_with_registry();
+        let entity = world.spawn().insert_bundle((Health(3), Unregistered)).id();
+        let entity_count = world.entities().len();
+        assert!(matches!(
+            world.clone_entity(entity),
+            Err(CloneEntityError::UnregisteredType { .. })
+        ));
+        assert_eq!(world.entities().len(), entity_count);
+
+        world.remove_resource::<TypeRegistryArc>();
+        world.entity_mut(entity).remove::<Unregistered>();
+        assert!(matches!(
+            world.clone_entity(entity),
+            Err(CloneEntityError::MissingTypeRegistry)
+        ));
+    }
+
+    #[test]
+    fn clone_entities_to_other_world() {
+        let mut server = world_with_registry();
+        let mut client = World::new();
+        let outside = server.spawn().id();
+        let a = server.spawn().insert(Health(1)).id();
+        let b = server.spawn().insert(Targets(vec![a, outside])).id();
+
+        // the reference to `outside` can't be mapped to the client world
+        assert!(matches!(
+            server.clone_entities_to(&[a, b], &mut client, &EntityMap::default()),
+            Err(CloneEntityError::UnmappedEntity(entity)) if entity == outside
+        ));
+        assert_eq!(client.entities().len(), 0);
+
+        let external = server
+            .clone_entities_to(&[outside], &mut client, &EntityMap::default())
+            .unwrap();
+        let entity_map = server
+            .clone_entities_to(&[a, b], &mut client, &external)
+            .unwrap();
+        let a_clone = entity_map.get(a).unwrap();
+        let b_clone = entity_map.get(b).unwrap();
+        let outside_clone = external.get(outside).unwrap();
+        assert_eq!(client.entities().len(), 3);
+        assert_eq!(client.get::<Health>(a_clone), Some(&Health(1)));
+        assert_eq!(
+            client.get::<Targets>(b_clone),
+            Some(&Targets(vec![a_clone, outside_clone]))
+        );
+        assert_eq!(server.get::<Targets>(b), Some(&Targets(vec![a, outside])));
+    }
+
+    #[test]
+    fn move_entities_to_other_world() {
+        let mut server = world_with_registry();
+        let mut client = World::new();
+        let a = server.spawn().insert(Health(1)).id();
+        let b = server.spawn().insert(Targets(vec![a])).id();
+
+        let entity_map = server
+            .move_entities_to(&[a, b], &mut client, &EntityMap::default())
+            .unwrap();
+        assert!(server.get_entity(a).is_none());
+        assert!(server.get_entity(b).is_none());
+        let a_clone = entity_map.get(a).unwrap();
+        let b_clone = entity_map.get(b).unwrap();
+        assert_eq!(
+            client.get::<Targets>(b_clone),
+            Some(&Targets(vec![a_clone]))
+        );
+
+        let unregistered = server.spawn().insert(Unregistered).id();
+        assert!(matches!(
+            server.move_entities_to(&[unregistered], &mut client, &EntityMap::default()),
+            Err(CloneEntityError::UnregisteredType { .. })
+        ));
+        assert!(server.get_entity(unregistered).is_some());
+        assert_eq!(client.entities().len(), 2);
+    }
+
+    #[test]
+    fn clone_entity_command() {
+        let mut world = world_with_registry();
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().insert(Health(3)).id();
+        let clone = {
+            let mut commands = Commands::new(&mut queue, &world);
+            let mut entity_commands = commands.entity(entity);
+            let mut clone = entity_commands.clone();
+            clone.insert(Targets(vec![entity]));
+            clone.id()
+        };
+        queue.apply(&mut world);
+        assert_eq!(world.get::<Health>(clone), Some(&Health(3)));
+        assert_eq!(world.get::<Targets>(clone), Some(&Targets(vec![entity])));
+        assert_eq!(world.get::<Targets>(entity), None);
+    }
+
+    #[test]
+    fn clone_entity_command_failure() {
+        let mut world = world_with_registry();
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().insert_bundle((Health(3), Unregistered)).id();
+        let clone = {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(entity).clone().insert(Health(1)).id()
+        };
+        queue.apply(&mut world);
+        // the error is logged, and the clone is left empty so that the commands added to it
+        // still apply instead of panicking
+        assert_eq!(world.get::<Health>(clone), Some(&Health(1)));
+        assert!(world.get::<Unregistered>(clone).is_none());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::{
     archetype::{Archetype, ArchetypeId, Archetypes},
-    bundle::{Bundle, BundleInfo},
+    bundle::{Bundle, BundleId, BundleInfo, ComponentPtr, DynamicBundle},
     change_detection::Ticks,
-    component::{Component, ComponentId, ComponentTicks, Components, StorageType},
+    component::{Component, ComponentId, ComponentTicks, Components, LifecycleEvent, StorageType},
     entity::{Entities, Entity, EntityLocation},
+    query::DynamicComponent,
+    relation::Relation,
     storage::{SparseSet, Storages},
     world::{Mut, World},
 };
@@ -89,6 +91,31 @@ impl<'w> EntityRef<'w> {
                 },
             })
     }
+
+    /// Gets the component `component_id` as a read-only [`DynamicComponent`], which also works for
+    /// components without a Rust type.
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<DynamicComponent<'w>> {
+        if !self.contains_id(component_id) {
+            return None;
+        }
+        // SAFE: the entity has the component, so `component_id` is valid, and the component is
+        // only accessed immutably
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| {
+                    DynamicComponent::new(
+                        component_id,
+                        value,
+                        ticks,
+                        false,
+                        self.world.last_change_tick(),
+                        self.world.read_change_tick(),
+                    )
+                },
+            )
+        }
+    }
 }
 
 pub struct EntityMut<'w> {
@@ -191,12 +218,108 @@ impl<'w> EntityMut<'w> {
             })
     }
 
+    /// Gets the component `component_id` as a read-only [`DynamicComponent`], which also works for
+    /// components without a Rust type.
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<DynamicComponent<'_>> {
+        if !self.contains_id(component_id) {
+            return None;
+        }
+        // SAFE: the entity has the component, so `component_id` is valid, and the component is
+        // only accessed immutably
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| {
+                    DynamicComponent::new(
+                        component_id,
+                        value,
+                        ticks,
+                        false,
+                        self.world.last_change_tick(),
+                        self.world.read_change_tick(),
+                    )
+                },
+            )
+        }
+    }
+
+    /// Gets the component `component_id` as a mutable [`DynamicComponent`], which also works for
+    /// components without a Rust type.
+    #[inline]
+    pub fn get_mut_by_id(&mut self, component_id: ComponentId) -> Option<DynamicComponent<'_>> {
+        if !self.contains_id(component_id) {
+            return None;
+        }
+        // SAFE: the entity has the component, so `component_id` is valid, and world access is
+        // unique
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| {
+                    DynamicComponent::new(
+                        component_id,
+                        value,
+                        ticks,
+                        true,
+                        self.world.last_change_tick(),
+                        self.world.change_tick(),
+                    )
+                },
+            )
+        }
+    }
+
     pub fn insert_bundle<T: Bundle>(&mut self, bundle: T) -> &mut Self {
-        let change_tick = self.world.change_tick();
-        let bundle_info = self
+        let bundle_id = self
             .world
             .bundles
-            .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+            .init_info::<T>(&mut self.world.components, &mut self.world.storages)
+            .id();
+        // SAFE: `T` matches the bundle with `bundle_id`
+        unsafe { self.insert_with_bundle_id(bundle_id, bundle) }
+    }
+
+    /// Inserts the value pointed to by `component` as the component `component_id`, which may
+    /// have no Rust type, such as a component registered with
+    /// [`World::init_component_with_descriptor`].
+    ///
+    /// # Safety
+    ///
+    /// `component` must point to a valid value of the component `component_id`. The value is
+    /// moved into the world, so the caller must not use or drop it afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `component_id` is not registered in this world.
+    pub unsafe fn insert_by_id(
+        &mut self,
+        component_id: ComponentId,
+        component: *mut u8,
+    ) -> &mut Self {
+        let bundle_id = self
+            .world
+            .bundles
+            .init_component_info(&self.world.components, component_id)
+            .id();
+        self.insert_with_bundle_id(bundle_id, ComponentPtr(component))
+    }
+
+    /// # Safety
+    /// `bundle` must match the bundle with `bundle_id`
+    unsafe fn insert_with_bundle_id<T: DynamicBundle>(
+        &mut self,
+        bundle_id: BundleId,
+        bundle: T,
+    ) -> &mut Self {
+        let change_tick = self.world.change_tick();
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
+        let old_archetype = &self.world.archetypes[self.location.archetype_id];
+        let (components, observers) = (&self.world.components, &self.world.observers);
+        let triggered = bundle_info
+            .component_ids
+            .iter()
+            .filter(|id| observers.has_listeners(components, **id))
+            .map(|id| (*id, !old_archetype.contains(*id)))
+            .collect::<Vec<_>>();
         let mut bundle_inserter = bundle_info.get_bundle_inserter(
             &mut self.world.entities,
             &mut self.world.archetypes,
@@ -206,15 +329,80 @@ impl<'w> EntityMut<'w> {
             change_tick,
         );
         // SAFE: location matches current entity. `T` matches `bundle_info`
-        unsafe {
-            self.location = bundle_inserter.insert(self.entity, self.location.index, bundle);
+        self.location = bundle_inserter.insert(self.entity, self.location.index, bundle);
+
+        if !triggered.is_empty() {
+            for (component_id, _) in triggered.iter().filter(|(_, added)| *added) {
+                self.trigger_lifecycle_event(LifecycleEvent::Add, *component_id);
+            }
+            for (component_id, _) in triggered.iter() {
+                self.trigger_lifecycle_event(LifecycleEvent::Insert, *component_id);
+            }
+            self.location =
+                self.world.entities.get(self.entity).expect(
+                    "entity was despawned by a component hook or observer during insertion",
+                );
         }
 
         self
     }
 
+    /// Runs the hook and observers of `component_id` for `event` on this entity, unless it has
+    /// already been despawned. The entity location must be updated afterwards.
+    fn trigger_lifecycle_event(&mut self, event: LifecycleEvent, component_id: ComponentId) {
+        if self.world.entities.contains(self.entity) {
+            self.world
+                .trigger_lifecycle_event(event, self.entity, component_id);
+        }
+    }
+
+    /// Runs the [`LifecycleEvent::Remove`] hooks and observers of the components of `T` that this
+    /// entity has, if it has all of them or `intersection` is true.
+    ///
+    /// Returns `false` if the entity was despawned by a hook or an observer.
+    fn trigger_remove_events<T: Bundle>(&mut self, intersection: bool) -> bool {
+        let bundle_info = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        if !intersection
+            && !bundle_info
+                .component_ids
+                .iter()
+                .all(|id| archetype.contains(*id))
+        {
+            return true;
+        }
+        let (components, observers) = (&self.world.components, &self.world.observers);
+        let triggered = bundle_info
+            .component_ids
+            .iter()
+            .cloned()
+            .filter(|id| archetype.contains(*id) && observers.has_listeners(components, *id))
+            .collect::<Vec<_>>();
+        if triggered.is_empty() {
+            return true;
+        }
+
+        for component_id in triggered {
+            self.trigger_lifecycle_event(LifecycleEvent::Remove, component_id);
+        }
+        match self.world.entities.get(self.entity) {
+            Some(location) => {
+                self.location = location;
+                true
+            }
+            None => false,
+        }
+    }
+
     // TODO: move to BundleInfo
     pub fn remove_bundle<T: Bundle>(&mut self) -> Option<T> {
+        if !self.trigger_remove_events::<T>(false) {
+            return None;
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -337,6 +525,10 @@ impl<'w> EntityMut<'w> {
     // TODO: move to BundleInfo
     /// Remove any components in the bundle that the entity has.
     pub fn remove_bundle_intersection<T: Bundle>(&mut self) {
+        if !self.trigger_remove_events::<T>(true) {
+            return;
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -403,8 +595,68 @@ impl<'w> EntityMut<'w> {
         self.remove_bundle::<(T,)>().map(|v| v.0)
     }
 
+    /// Adds a relation of kind `R` from this entity to `target`.
+    ///
+    /// Relating to the same target twice has no effect. See [`Relation`] for more details.
+    pub fn relate<R: Send + Sync + 'static>(&mut self, target: Entity) -> &mut Self {
+        let component_id = self.world.init_relation::<R>();
+        if let Some(mut relation) = self.get_mut::<Relation<R>>() {
+            if relation.contains(target) {
+                return self;
+            }
+            relation.targets.push(target);
+        } else {
+            let mut relation = Relation::<R>::new();
+            relation.targets.push(target);
+            self.insert(relation);
+        }
+        self.world
+            .relations
+            .add_source(component_id, self.entity, target);
+        self
+    }
+
+    /// Removes the relation of kind `R` from this entity to `target`, if it exists.
+    ///
+    /// The [`Relation<R>`] component is removed once its last target is gone.
+    pub fn unrelate<R: Send + Sync + 'static>(&mut self, target: Entity) -> &mut Self {
+        let is_empty = match self.get_mut::<Relation<R>>() {
+            Some(mut relation) if relation.contains(target) => {
+                relation.targets.retain(|entity| *entity != target);
+                relation.is_empty()
+            }
+            _ => return self,
+        };
+        if is_empty {
+            self.remove::<Relation<R>>();
+        }
+        if let Some(component_id) = self.world.components.get_id(TypeId::of::<Relation<R>>()) {
+            self.world
+                .relations
+                .remove_source(component_id, self.entity, target);
+        }
+        self
+    }
+
     pub fn despawn(self) {
         let world = self.world;
+        world.despawn_relations(self.entity);
+        if let Some(location) = world.entities.get(self.entity) {
+            let triggered = world.archetypes[location.archetype_id]
+                .components()
+                .filter(|id| world.observers.has_listeners(&world.components, *id))
+                .collect::<Vec<_>>();
+            for component_id in triggered {
+                if !world.entities.contains(self.entity) {
+                    break;
+                }
+                world.trigger_lifecycle_event(LifecycleEvent::Remove, self.entity, component_id);
+            }
+        }
+        if !world.entities.contains(self.entity) {
+            // a hook or an observer already despawned this entity
+            return;
+        }
         world.flush();
         let location = world
             .entities
@@ -727,6 +979,127 @@ fn sorted_remove<T: Eq + Ord + Copy>(source: &mut Vec<T>, remove: &[T]) {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        component::{ComponentDescriptor, ComponentId, StorageType},
+        world::World,
+    };
+    use std::{
+        alloc::Layout,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    fn init_runtime_component(
+        world: &mut World,
+        storage_type: StorageType,
+        drop: unsafe fn(*mut u8),
+    ) -> ComponentId {
+        // SAFE: the tests only insert `[u32; 2]` values, which `drop` does not read
+        world.init_component_with_descriptor(unsafe {
+            ComponentDescriptor::new_with_layout(
+                "Runtime",
+                storage_type,
+                Layout::new::<[u32; 2]>(),
+                Some(drop),
+            )
+        })
+    }
+
+    fn insert_runtime_component(world: &mut World, id: ComponentId, mut value: [u32; 2]) {
+        let entity = world.spawn().id();
+        // SAFE: `value` matches the layout of the component and is not used afterwards
+        unsafe {
+            world
+                .entity_mut(entity)
+                .insert_by_id(id, (&mut value as *mut [u32; 2]).cast());
+        }
+    }
+
+    #[test]
+    fn runtime_components() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+        unsafe fn count_drop(_: *mut u8) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+
+        for storage_type in [StorageType::Table, StorageType::SparseSet] {
+            DROPPED.store(0, Ordering::SeqCst);
+            let mut world = World::new();
+            let id = init_runtime_component(&mut world, storage_type, count_drop);
+            assert_eq!(world.components().get_info(id).unwrap().name(), "Runtime");
+
+            insert_runtime_component(&mut world, id, [1, 2]);
+            let entity = world.spawn().id();
+            let mut value = [3u32, 4];
+            // SAFE: `value` matches the layout of the component and is not used afterwards
+            unsafe {
+                world
+                    .entity_mut(entity)
+                    .insert_by_id(id, (&mut value as *mut [u32; 2]).cast());
+            }
+            let component = world.entity(entity).get_by_id(id).unwrap();
+            assert_eq!(component.id(), id);
+            assert!(!component.is_writable());
+            // SAFE: the component holds a `[u32; 2]`
+            assert_eq!(unsafe { *component.deref::<[u32; 2]>() }, [3, 4]);
+
+            let mut value = [5u32, 6];
+            // SAFE: `value` matches the layout of the component and is not used afterwards
+            unsafe {
+                world
+                    .entity_mut(entity)
+                    .insert_by_id(id, (&mut value as *mut [u32; 2]).cast());
+            }
+            assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+
+            let mut entity_mut = world.entity_mut(entity);
+            let mut component = entity_mut.get_mut_by_id(id).unwrap();
+            // SAFE: the component holds a `[u32; 2]`
+            unsafe { component.deref_mut::<[u32; 2]>().unwrap()[0] = 7 };
+            assert_eq!(
+                // SAFE: the component holds a `[u32; 2]`
+                unsafe {
+                    *world
+                        .entity(entity)
+                        .get_by_id(id)
+                        .unwrap()
+                        .deref::<[u32; 2]>()
+                },
+                [7, 6]
+            );
+
+            world.despawn(entity);
+            assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+            drop(world);
+            assert_eq!(DROPPED.load(Ordering::SeqCst), 3);
+        }
+    }
+
+    #[test]
+    fn runtime_components_run_hooks() {
+        #[derive(Default)]
+        struct Added(usize);
+
+        unsafe fn no_drop(_: *mut u8) {}
+
+        let mut world = World::new();
+        world.init_resource::<Added>();
+        let id = init_runtime_component(&mut world, StorageType::Table, no_drop);
+        world
+            .components_mut()
+            .get_hooks_mut(id)
+            .unwrap()
+            .on_add(|world, _, _| world.get_resource_mut::<Added>().unwrap().0 += 1);
+        insert_runtime_component(&mut world, id, [0, 0]);
+        assert_eq!(world.get_resource::<Added>().unwrap().0, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_by_unknown_id() {
+        let mut world = World::new();
+        insert_runtime_component(&mut world, ComponentId::new(100), [0, 0]);
+    }
+
     #[test]
     fn sorted_remove() {
         let mut a = vec![1, 2, 3, 4, 5, 6, 7];
@@ -1,8 +1,12 @@
+#[cfg(feature = "bevy_reflect")]
+mod clone_entity;
 mod entity_ref;
 mod spawn_batch;
 mod world_cell;
 
 pub use crate::change_detection::Mut;
+#[cfg(feature = "bevy_reflect")]
+pub use clone_entity::*;
 pub use entity_ref::*;
 pub use spawn_batch::*;
 pub use world_cell::*;
@@ -11,11 +15,17 @@ use crate::{
     archetype::{ArchetypeComponentId, ArchetypeComponentInfo, ArchetypeId, Archetypes},
     bundle::{Bundle, BundleInserter, BundleSpawner, Bundles},
     change_detection::Ticks,
-    component::{Component, ComponentId, ComponentTicks, Components, StorageType},
+    component::{
+        Component, ComponentDescriptor, ComponentHooks, ComponentId, ComponentTicks, Components,
+        Disabled, LifecycleEvent, StorageType,
+    },
     entity::{AllocAtWithoutReplacement, Entities, Entity},
+    index::ComponentIndices,
+    observer::Observers,
     query::{FilterFetch, QueryState, WorldQuery},
+    relation::{Relation, RelationTraversal, Relations},
     storage::{Column, SparseSet, Storages},
-    system::Resource,
+    system::{BoxedSystem, IntoSystem, Resource, StoredSystem, SystemRegistry},
 };
 use std::{
     any::TypeId,
@@ -83,6 +93,15 @@ pub struct World {
     pub(crate) storages: Storages,
     pub(crate) bundles: Bundles,
     pub(crate) removed_components: SparseSet<ComponentId, Vec<Entity>>,
+    pub(crate) removed_resources: Vec<ComponentId>,
+    pub(crate) relations: Relations,
+    pub(crate) observers: Observers,
+    pub(crate) system_registry: SystemRegistry,
+    pub(crate) component_indices: ComponentIndices,
+    /// Set once a [`CommandRecorder`](crate::recording::CommandRecorder) has been inserted, so
+    /// that applying commands only looks it up when it may exist.
+    #[cfg(feature = "bevy_reflect")]
+    pub(crate) records_commands: bool,
     /// Access cache used by [WorldCell].
     pub(crate) archetype_component_access: ArchetypeComponentAccess,
     main_thread_validator: MainThreadValidator,
@@ -92,14 +111,25 @@ pub struct World {
 
 impl Default for World {
     fn default() -> Self {
+        let mut components = Components::default();
+        let mut storages = Storages::default();
+        let mut archetypes = Archetypes::default();
+        archetypes.disabled_component = Some(components.init_component::<Disabled>(&mut storages));
         Self {
             id: WorldId::new().expect("More `bevy` `World`s have been created than is supported"),
             entities: Default::default(),
-            components: Default::default(),
-            archetypes: Default::default(),
-            storages: Default::default(),
+            components,
+            archetypes,
+            storages,
             bundles: Default::default(),
             removed_components: Default::default(),
+            removed_resources: Default::default(),
+            relations: Default::default(),
+            observers: Default::default(),
+            system_registry: Default::default(),
+            component_indices: Default::default(),
+            #[cfg(feature = "bevy_reflect")]
+            records_commands: false,
             archetype_component_access: Default::default(),
             main_thread_validator: Default::default(),
             // Default value is `1`, and `last_change_tick`s default to `0`, such that changes
@@ -170,6 +200,18 @@ impl World {
         &self.bundles
     }
 
+    /// Retrieves this world's [Relations] collection
+    #[inline]
+    pub fn relations(&self) -> &Relations {
+        &self.relations
+    }
+
+    /// Retrieves this world's [Observers] collection
+    #[inline]
+    pub fn observers(&self) -> &Observers {
+        &self.observers
+    }
+
     /// Retrieves a [`WorldCell`], which safely enables multiple mutable World accesses at the same
     /// time, provided those accesses do not conflict with each other.
     #[inline]
@@ -181,6 +223,37 @@ impl World {
         self.components.init_component::<T>(&mut self.storages)
     }
 
+    /// Registers a component described by `descriptor`, which does not need a Rust type, and
+    /// returns its [`ComponentId`].
+    ///
+    /// Values of the component can be inserted with [`EntityMut::insert_by_id`] and read with
+    /// [`EntityRef::get_by_id`].
+    ///
+    /// ```
+    /// use bevy_ecs::{component::{ComponentDescriptor, StorageType}, world::World};
+    /// use std::alloc::Layout;
+    ///
+    /// let mut world = World::new();
+    /// // SAFE: the component is only ever inserted as a `u32`, which needs no drop
+    /// let id = world.init_component_with_descriptor(unsafe {
+    ///     ComponentDescriptor::new_with_layout("Health", StorageType::Table, Layout::new::<u32>(), None)
+    /// });
+    ///
+    /// let mut value = 100u32;
+    /// // SAFE: `value` matches the layout of the component and is not used afterwards
+    /// let entity = unsafe { world.spawn().insert_by_id(id, (&mut value as *mut u32).cast()).id() };
+    /// let health = world.entity(entity).get_by_id(id).unwrap();
+    /// // SAFE: the component holds a `u32`
+    /// assert_eq!(unsafe { *health.deref::<u32>() }, 100);
+    /// ```
+    pub fn init_component_with_descriptor(
+        &mut self,
+        descriptor: ComponentDescriptor,
+    ) -> ComponentId {
+        self.components
+            .init_component_with_descriptor(&mut self.storages, descriptor)
+    }
+
     /// Retrieves an [`EntityRef`] that exposes read-only operations for the given `entity`.
     /// This will panic if the `entity` does not exist. Use [`World::get_entity`] if you want
     /// to check for entity existence instead of implicitly panic-ing.
@@ -477,6 +550,7 @@ impl World {
         for entities in self.removed_components.values_mut() {
             entities.clear();
         }
+        self.removed_resources.clear();
 
         self.last_change_tick = self.increment_change_tick();
     }
@@ -598,6 +672,190 @@ impl World {
         }
     }
 
+    /// Returns `true` if the resource of type `R` was removed since the last call to
+    /// [`World::clear_trackers`], even if it was inserted again since.
+    pub fn is_resource_removed<R: 'static>(&self) -> bool {
+        if let Some(component_id) = self.components.get_resource_id(TypeId::of::<R>()) {
+            self.removed_resources.contains(&component_id)
+        } else {
+            false
+        }
+    }
+
+    /// Registers the relation kind `R` and returns the [`ComponentId`] of [`Relation<R>`].
+    ///
+    /// This is done automatically the first time a relation of kind `R` is created.
+    pub fn init_relation<R: Send + Sync + 'static>(&mut self) -> ComponentId {
+        let component_id = self.init_component::<Relation<R>>();
+        self.relations.register::<R>(component_id);
+        component_id
+    }
+
+    /// Returns an iterator over the entities that have a relation of kind `R` to `target`.
+    pub fn related_to<R: Send + Sync + 'static>(
+        &self,
+        target: Entity,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        let component_id = self.components.get_id(TypeId::of::<Relation<R>>());
+        self.relations
+            .sources
+            .get(&target)
+            .into_iter()
+            .flatten()
+            .filter(move |(id, _)| Some(*id) == component_id)
+            .map(|(_, source)| *source)
+            // skip stale index entries left behind by removing `Relation<R>` directly
+            .filter(move |source| {
+                self.get::<Relation<R>>(*source)
+                    .map(|relation| relation.contains(target))
+                    .unwrap_or(false)
+            })
+    }
+
+    /// Returns an iterator that walks relations of kind `R` breadth-first, starting at the
+    /// targets of `source`.
+    ///
+    /// Each entity is visited at most once, so this terminates even if the relation graph
+    /// contains cycles. `source` itself is only yielded if it is reachable from its own targets.
+    pub fn traverse_relation<R: Send + Sync + 'static>(
+        &self,
+        source: Entity,
+    ) -> RelationTraversal<'_, R> {
+        RelationTraversal::new(self, source, |world, entity| {
+            world
+                .get::<Relation<R>>(entity)
+                .map(|relation| relation.targets().to_vec())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns an iterator that walks relations of kind `R` backwards (from targets to sources)
+    /// breadth-first, starting at the sources of `target`.
+    ///
+    /// Each entity is visited at most once, so this terminates even if the relation graph
+    /// contains cycles.
+    pub fn traverse_relation_sources<R: Send + Sync + 'static>(
+        &self,
+        target: Entity,
+    ) -> RelationTraversal<'_, R> {
+        RelationTraversal::new(self, target, |world, entity| {
+            world.related_to::<R>(entity).collect()
+        })
+    }
+
+    /// Removes `entity` from the reverse index and from every relation that targets it.
+    pub(crate) fn despawn_relations(&mut self, entity: Entity) {
+        if self.relations.is_empty() {
+            return;
+        }
+
+        if let Some(sources) = self.relations.sources.remove(&entity) {
+            for (component_id, source) in sources {
+                if source == entity {
+                    continue;
+                }
+                if let Some(info) = self.relations.infos.get(component_id) {
+                    (info.remove_target)(self, source, entity);
+                }
+            }
+        }
+
+        let location = match self.entities.get(entity) {
+            Some(location) => location,
+            None => return,
+        };
+        let relation_ids = self.archetypes[location.archetype_id]
+            .sparse_set_components()
+            .iter()
+            .cloned()
+            .filter(|id| self.relations.is_relation(*id))
+            .collect::<Vec<_>>();
+        for component_id in relation_ids {
+            let targets = (self.relations.infos.get(component_id).unwrap().targets)(self, entity);
+            for target in targets {
+                self.relations.remove_source(component_id, entity, target);
+            }
+        }
+    }
+
+    /// Returns the lifecycle hooks of the component `T`, registering `T` if needed, so they can
+    /// be changed. See [`ComponentHooks`] for more details.
+    pub fn register_component_hooks<T: Component>(&mut self) -> &mut ComponentHooks {
+        let component_id = self.init_component::<T>();
+        self.components.get_hooks_mut(component_id).unwrap()
+    }
+
+    /// Registers `system` as an observer of `event` for components of type `T`.
+    ///
+    /// The observer is run immediately, with the affected entity as its input, every time the
+    /// event occurs. Its [`Commands`](crate::system::Commands) are applied right after it runs.
+    /// See [`ComponentHooks`](crate::component::ComponentHooks) for when exactly each event is
+    /// triggered.
+    ///
+    /// An observer does not trigger itself: events of the same kind caused by an observer for the
+    /// same component while it is running are not observed by it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ecs::{component::LifecycleEvent, prelude::*};
+    ///
+    /// #[derive(Component)]
+    /// struct Name(&'static str);
+    ///
+    /// #[derive(Default)]
+    /// struct Names(Vec<&'static str>);
+    ///
+    /// fn index_name(In(entity): In<Entity>, query: Query<&Name>, mut names: ResMut<Names>) {
+    ///     names.0.push(query.get(entity).unwrap().0);
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.init_resource::<Names>();
+    /// world.observe::<Name, _>(LifecycleEvent::Insert, index_name);
+    ///
+    /// world.spawn().insert(Name("alice"));
+    /// assert_eq!(world.get_resource::<Names>().unwrap().0, vec!["alice"]);
+    /// ```
+    pub fn observe<T: Component, Params>(
+        &mut self,
+        event: LifecycleEvent,
+        system: impl IntoSystem<Entity, (), Params>,
+    ) {
+        let component_id = self.init_component::<T>();
+        let system: BoxedSystem<Entity, ()> = Box::new(IntoSystem::into_system(system));
+        let observer = StoredSystem::new(system, self);
+        self.observers.add(component_id, event, observer);
+    }
+
+    /// Runs the hook and the observers registered for `event` on `component_id`.
+    pub(crate) fn trigger_lifecycle_event(
+        &mut self,
+        event: LifecycleEvent,
+        entity: Entity,
+        component_id: ComponentId,
+    ) {
+        let hook = self
+            .components
+            .get_info(component_id)
+            .and_then(|info| info.hooks().get(event));
+        if let Some(hook) = hook {
+            hook(self, entity, component_id);
+        }
+
+        if !self.observers.contains(component_id) {
+            return;
+        }
+        let mut observers = self.observers.take(component_id, event);
+        for observer in &mut observers {
+            if !self.entities.contains(entity) {
+                break;
+            }
+            observer.run(entity, self);
+        }
+        self.observers.restore(component_id, event, observers);
+    }
+
     /// Inserts a new resource with standard starting values.
     ///
     /// If the resource already exists, nothing happens.
@@ -623,6 +881,10 @@ impl World {
     /// you will overwrite any existing data.
     #[inline]
     pub fn insert_resource<R: Resource>(&mut self, value: R) {
+        #[cfg(feature = "bevy_reflect")]
+        if TypeId::of::<R>() == TypeId::of::<crate::recording::CommandRecorder>() {
+            self.records_commands = true;
+        }
         let component_id = self.components.init_resource::<R>();
         // SAFE: component_id just initialized and corresponds to resource of type T
         unsafe { self.insert_resource_with_id(component_id, value) };
@@ -689,6 +951,9 @@ impl World {
         // SAFE: if a resource column exists, row 0 exists as well. caller takes ownership of the
         // ptr value / drop is called when R is dropped
         let (ptr, _) = unsafe { column.swap_remove_and_forget_unchecked(0) };
+        if !self.removed_resources.contains(&component_id) {
+            self.removed_resources.push(component_id);
+        }
         // SAFE: column is of type R
         Some(unsafe { ptr.cast::<R>().read() })
     }
@@ -1277,13 +1542,39 @@ impl World {
         for column in resource_archetype.unique_components.values_mut() {
             column.check_change_ticks(change_tick);
         }
+        self.observers.check_change_ticks(change_tick);
+        self.system_registry.check_change_ticks(change_tick);
+        self.check_index_change_ticks(change_tick);
     }
 
+    /// Despawns every entity in the world.
+    ///
+    /// The [`LifecycleEvent::Remove`] hooks and observers of their components run before the
+    /// entities are removed, so that, for example, component indices stay up to date. Unlike
+    /// [`World::despawn`], the removed components are not reported by [`World::removed`].
     pub fn clear_entities(&mut self) {
+        let mut triggered = Vec::new();
+        for archetype in self.archetypes.iter() {
+            let components = archetype
+                .components()
+                .filter(|id| self.observers.has_listeners(&self.components, *id))
+                .collect::<Vec<_>>();
+            for entity in archetype.entities() {
+                triggered.extend(components.iter().map(|id| (*entity, *id)));
+            }
+        }
+        for (entity, component_id) in triggered {
+            // a hook or an observer may have despawned the entity
+            if self.entities.contains(entity) {
+                self.trigger_lifecycle_event(LifecycleEvent::Remove, entity, component_id);
+            }
+        }
+
         self.storages.tables.clear();
         self.storages.sparse_sets.clear();
         self.archetypes.clear_entities();
         self.entities.clear();
+        self.relations.clear();
     }
 }
 
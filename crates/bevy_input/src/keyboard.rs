@@ -4,6 +4,7 @@ use bevy_ecs::system::ResMut;
 
 /// A key input event from a keyboard device
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyboardInput {
     pub scan_code: u32,
     pub key_code: Option<KeyCode>,
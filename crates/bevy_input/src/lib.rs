@@ -4,11 +4,13 @@ mod input;
 pub mod keyboard;
 pub mod mouse;
 pub mod system;
+mod testing;
 pub mod touch;
 
 pub use axis::*;
 use bevy_ecs::schedule::{ParallelSystemDescriptorCoercion, SystemLabel};
 pub use input::*;
+pub use testing::*;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -84,6 +86,11 @@ impl Plugin for InputPlugin {
                 CoreStage::PreUpdate,
                 touch_screen_input_system.label(InputSystem),
             );
+
+        #[cfg(feature = "bevy_ci_testing")]
+        app.add_ci_testing_event::<KeyboardInput>("KeyboardInput")
+            .add_ci_testing_event::<MouseButtonInput>("MouseButtonInput")
+            .add_ci_testing_event::<GamepadEventRaw>("GamepadEventRaw");
     }
 }
 
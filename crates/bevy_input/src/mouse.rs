@@ -4,6 +4,7 @@ use bevy_math::Vec2;
 
 /// A mouse button input event
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseButtonInput {
     pub button: MouseButton,
     pub state: ElementState,
@@ -0,0 +1,116 @@
+use crate::{
+    gamepad::{Gamepad, GamepadEventRaw, GamepadEventType},
+    keyboard::{KeyCode, KeyboardInput},
+    mouse::{MouseButton, MouseButtonInput},
+    ElementState,
+};
+use bevy_app::{App, Events};
+
+/// Trait that holds functions for injecting input events into an [`App`], e.g. when stepping it
+/// in tests with a [`TestApp`](bevy_app::TestApp).
+///
+/// The events are processed by the [`InputPlugin`](crate::InputPlugin) systems during the next
+/// update, as if they came from a device.
+pub trait SendInputExt {
+    /// Sends a [`KeyboardInput`] event pressing `key_code`.
+    fn press_key(&mut self, key_code: KeyCode) -> &mut Self;
+    /// Sends a [`KeyboardInput`] event releasing `key_code`.
+    fn release_key(&mut self, key_code: KeyCode) -> &mut Self;
+    /// Sends a [`MouseButtonInput`] event pressing `button`.
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+    /// Sends a [`MouseButtonInput`] event releasing `button`.
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self;
+    /// Sends a raw gamepad event, which updates the gamepad resources and is forwarded as a
+    /// [`GamepadEvent`](crate::gamepad::GamepadEvent).
+    fn send_gamepad_event(&mut self, gamepad: Gamepad, event_type: GamepadEventType) -> &mut Self;
+}
+
+impl SendInputExt for App {
+    fn press_key(&mut self, key_code: KeyCode) -> &mut Self {
+        send_keyboard_input(self, key_code, ElementState::Pressed)
+    }
+
+    fn release_key(&mut self, key_code: KeyCode) -> &mut Self {
+        send_keyboard_input(self, key_code, ElementState::Released)
+    }
+
+    fn press_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        send_mouse_button_input(self, button, ElementState::Pressed)
+    }
+
+    fn release_mouse_button(&mut self, button: MouseButton) -> &mut Self {
+        send_mouse_button_input(self, button, ElementState::Released)
+    }
+
+    fn send_gamepad_event(&mut self, gamepad: Gamepad, event_type: GamepadEventType) -> &mut Self {
+        self.world
+            .resource_mut::<Events<GamepadEventRaw>>()
+            .send(GamepadEventRaw(gamepad, event_type));
+        self
+    }
+}
+
+fn send_keyboard_input(app: &mut App, key_code: KeyCode, state: ElementState) -> &mut App {
+    app.world
+        .resource_mut::<Events<KeyboardInput>>()
+        .send(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+        });
+    app
+}
+
+fn send_mouse_button_input(app: &mut App, button: MouseButton, state: ElementState) -> &mut App {
+    app.world
+        .resource_mut::<Events<MouseButtonInput>>()
+        .send(MouseButtonInput { button, state });
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SendInputExt;
+    use crate::{
+        gamepad::{Gamepad, GamepadButton, GamepadButtonType, GamepadEventType},
+        keyboard::KeyCode,
+        mouse::MouseButton,
+        Input, InputPlugin,
+    };
+    use bevy_app::{App, TestApp};
+
+    #[test]
+    fn send_input() {
+        let mut app = App::new();
+        app.add_plugin(InputPlugin);
+        let mut app = TestApp::new(app);
+
+        app.press_key(KeyCode::Space)
+            .press_mouse_button(MouseButton::Left)
+            .send_gamepad_event(Gamepad(0), GamepadEventType::Connected)
+            .send_gamepad_event(
+                Gamepad(0),
+                GamepadEventType::ButtonChanged(GamepadButtonType::South, 1.0),
+            );
+        app.step();
+        assert!(app
+            .world
+            .resource::<Input<KeyCode>>()
+            .just_pressed(KeyCode::Space));
+        assert!(app
+            .world
+            .resource::<Input<MouseButton>>()
+            .pressed(MouseButton::Left));
+        assert!(app
+            .world
+            .resource::<Input<GamepadButton>>()
+            .pressed(GamepadButton(Gamepad(0), GamepadButtonType::South)));
+
+        app.release_key(KeyCode::Space);
+        app.step();
+        assert!(app
+            .world
+            .resource::<Input<KeyCode>>()
+            .just_released(KeyCode::Space));
+    }
+}
@@ -1,7 +1,7 @@
 use bevy_ecs::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
-    reflect::{ReflectComponent, ReflectMapEntities},
+    reflect::{ReflectCloneMapped, ReflectComponent, ReflectMapEntities},
 };
 use bevy_reflect::Reflect;
 use smallvec::SmallVec;
@@ -9,7 +9,7 @@ use std::ops::Deref;
 
 /// Contains references to the child entities of this entity
 #[derive(Component, Default, Clone, Debug, Reflect)]
-#[reflect(Component, MapEntities)]
+#[reflect(Component, MapEntities, CloneMapped)]
 pub struct Children(pub(crate) SmallVec<[Entity; 8]>);
 
 impl MapEntities for Children {
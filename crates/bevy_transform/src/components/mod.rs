@@ -1,9 +1,11 @@
 mod children;
 mod global_transform;
 mod parent;
+mod previous_transform;
 mod transform;
 
 pub use children::Children;
 pub use global_transform::*;
 pub use parent::{Parent, PreviousParent};
+pub use previous_transform::*;
 pub use transform::*;
@@ -1,7 +1,7 @@
 use bevy_ecs::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
-    reflect::{ReflectComponent, ReflectMapEntities},
+    reflect::{ReflectCloneMapped, ReflectComponent, ReflectMapEntities},
     world::{FromWorld, World},
 };
 use bevy_reflect::Reflect;
@@ -46,7 +46,7 @@ impl DerefMut for Parent {
 
 /// Component that holds the [`Parent`] this entity had previously
 #[derive(Component, Debug, Copy, Clone, Eq, PartialEq, Reflect)]
-#[reflect(Component, MapEntities, PartialEq)]
+#[reflect(Component, MapEntities, PartialEq, CloneMapped)]
 pub struct PreviousParent(pub(crate) Entity);
 
 impl MapEntities for PreviousParent {
@@ -0,0 +1,116 @@
+use super::Transform;
+use bevy_ecs::{component::Component, reflect::ReflectComponent, system::Query};
+use bevy_reflect::Reflect;
+
+/// The [`Transform`] of an entity at the start of the last fixed timestep, to interpolate the
+/// [`Transform`] between the last two steps when rendering.
+///
+/// It is updated by [`previous_transform_system`], added by the
+/// [`PreviousTransformPlugin`](crate::PreviousTransformPlugin) to the `FixedUpdateStage` from
+/// `bevy_core`, before the systems of the step that move the entity. Between
+/// two frames, the [`Transform`] is then one step ahead of [`PreviousTransform`], and the
+/// rendered transform can be [interpolated](PreviousTransform::interpolate) between them with the
+/// `overstep_percentage` of `Time<Fixed>`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_core::{Fixed, Time};
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_transform::prelude::*;
+/// #[derive(Component)]
+/// struct Rendered(Transform);
+///
+/// fn interpolate(
+///     fixed_time: Res<Time<Fixed>>,
+///     mut query: Query<(&mut Rendered, &Transform, &PreviousTransform)>,
+/// ) {
+///     for (mut rendered, transform, previous_transform) in query.iter_mut() {
+///         rendered.0 = previous_transform.interpolate(transform, fixed_time.overstep_percentage());
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, PartialEq, Clone, Copy, Default, Reflect)]
+#[reflect(Component, PartialEq)]
+pub struct PreviousTransform(pub Transform);
+
+impl PreviousTransform {
+    /// Returns the transform at `s` between this previous transform, at `0.0`, and `transform`,
+    /// at `1.0`. The translation and scale are linearly interpolated, and the rotation is
+    /// spherically interpolated.
+    #[inline]
+    pub fn interpolate(&self, transform: &Transform, s: f32) -> Transform {
+        Transform {
+            translation: self.0.translation.lerp(transform.translation, s),
+            rotation: self.0.rotation.slerp(transform.rotation, s),
+            scale: self.0.scale.lerp(transform.scale, s),
+        }
+    }
+}
+
+impl From<Transform> for PreviousTransform {
+    #[inline]
+    fn from(transform: Transform) -> Self {
+        PreviousTransform(transform)
+    }
+}
+
+/// Copies the [`Transform`] of each entity with a [`PreviousTransform`] into it.
+pub fn previous_transform_system(mut query: Query<(&Transform, &mut PreviousTransform)>) {
+    for (transform, mut previous_transform) in query.iter_mut() {
+        previous_transform.0 = *transform;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{components::*, PreviousTransformPlugin, TransformPlugin, TransformSystem};
+    use bevy_app::App;
+    use bevy_core::{CorePlugin, Fixed, FixedUpdateStage, Time, TimeUpdateStrategy};
+    use bevy_ecs::prelude::*;
+    use bevy_math::{Quat, Vec3};
+    use bevy_utils::Duration;
+
+    fn move_right(mut query: Query<&mut Transform>) {
+        for mut transform in query.iter_mut() {
+            transform.translation.x += 1.0;
+        }
+    }
+
+    #[test]
+    fn snapshot_each_step() {
+        let mut app = App::new();
+        app.insert_resource(Time::<Fixed>::from_duration(Duration::from_millis(100)))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )))
+            .add_plugin(CorePlugin)
+            .add_plugin(TransformPlugin)
+            .add_plugin(PreviousTransformPlugin)
+            .add_system_to_stage(
+                FixedUpdateStage,
+                move_right.after(TransformSystem::PreviousTransform),
+            );
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle((Transform::identity(), PreviousTransform::default()))
+            .id();
+
+        app.update();
+        // two steps are made, and the previous transform is from before the second one
+        app.update();
+        let transform = *app.world.get::<Transform>(entity).unwrap();
+        let previous_transform = *app.world.get::<PreviousTransform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(previous_transform.0.translation, Vec3::new(1.0, 0.0, 0.0));
+
+        let overstep_percentage = app.world.resource::<Time<Fixed>>().overstep_percentage();
+        let interpolated = previous_transform.interpolate(&transform, overstep_percentage);
+        assert!(interpolated
+            .translation
+            .abs_diff_eq(Vec3::new(1.5, 0.0, 0.0), 1e-5));
+        assert_eq!(interpolated.rotation, Quat::IDENTITY);
+        assert_eq!(interpolated.scale, Vec3::ONE);
+    }
+}
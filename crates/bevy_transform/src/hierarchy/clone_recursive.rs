@@ -0,0 +1,273 @@
+use crate::components::{Children, Parent};
+use bevy_ecs::{
+    entity::{Entity, EntityMap},
+    system::{Command, CommandError, EntityCommands, FallibleCommand, WithErrorHandler},
+    world::{CloneEntityError, World},
+};
+
+/// Clones the given entity and all its children recursively
+///
+/// If the clone fails, e.g. because a component isn't registered, the clones of the children are
+/// despawned and the error is passed to the
+/// [`CommandErrorHandler`](bevy_ecs::system::CommandErrorHandler). Like with
+/// [`Commands::clone_entity`](bevy_ecs::system::Commands::clone_entity), `destination` is left
+/// as an empty entity.
+#[derive(Debug)]
+pub struct CloneRecursive {
+    /// Entity to clone
+    pub source: Entity,
+    /// Empty entity that becomes the clone of `source`
+    pub destination: Entity,
+}
+
+/// Function for cloning an entity and all its children, returning the clone of the entity.
+///
+/// The components are copied with [`World::clone_entities`], so the [`Parent`] and [`Children`]
+/// components of the clones point to the other clones. The clone of `entity` keeps the parent of
+/// `entity`, and is added to its [`Children`].
+pub fn clone_with_children_recursive(
+    world: &mut World,
+    entity: Entity,
+) -> Result<Entity, CloneEntityError> {
+    let mut entities = vec![entity];
+    collect_descendants(world, entity, &mut entities);
+    let entity_map = world.clone_entities(&entities)?;
+    let clone = entity_map.get(entity).unwrap();
+    add_to_parent(world, clone);
+    Ok(clone)
+}
+
+fn collect_descendants(world: &World, entity: Entity, entities: &mut Vec<Entity>) {
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.iter() {
+            entities.push(*child);
+            collect_descendants(world, *child, entities);
+        }
+    }
+}
+
+// The `PreviousParent` of the clone isn't copied, as it refers to an entity that isn't cloned, so
+// `parent_update_system` would add the clone to the `Children` of its parent as well. Adding it
+// here makes the hierarchy consistent as soon as the clone is made.
+fn add_to_parent(world: &mut World, clone: Entity) {
+    if let Some(parent) = world.get::<Parent>(clone).map(|parent| parent.0) {
+        if let Some(mut children) = world.get_mut::<Children>(parent) {
+            children.0.push(clone);
+        }
+    }
+}
+
+impl FallibleCommand for CloneRecursive {
+    fn try_write(self, world: &mut World) -> Result<(), CommandError> {
+        let mut entities = Vec::new();
+        collect_descendants(world, self.source, &mut entities);
+        let mut entity_map = EntityMap::default();
+        entity_map.insert(self.source, self.destination);
+        for entity in entities.iter() {
+            entity_map.insert(*entity, world.spawn().id());
+        }
+        if let Err(error) = world.clone_entities_into(&entity_map) {
+            for clone in entity_map
+                .values()
+                .filter(|clone| *clone != self.destination)
+            {
+                world.despawn(clone);
+            }
+            return Err(match error {
+                CloneEntityError::NoSuchEntity(entity) => CommandError::NoSuchEntity {
+                    entity,
+                    action: "clone".to_string(),
+                },
+                error => CommandError::other(error),
+            });
+        }
+        add_to_parent(world, self.destination);
+        Ok(())
+    }
+}
+
+impl Command for CloneRecursive {
+    fn write(self, world: &mut World) {
+        WithErrorHandler {
+            command: self,
+            handler: None,
+        }
+        .write(world);
+    }
+}
+
+/// Trait that holds functions for cloning recursively down the transform hierarchy
+pub trait CloneRecursiveExt<'w, 's> {
+    /// Spawns a copy of the entity and of all its descendants, and returns an
+    /// [`EntityCommands`] for the copy of the entity.
+    fn clone_recursive(&mut self) -> EntityCommands<'w, 's, '_>;
+}
+
+impl<'w, 's, 'a> CloneRecursiveExt<'w, 's> for EntityCommands<'w, 's, 'a> {
+    fn clone_recursive(&mut self) -> EntityCommands<'w, 's, '_> {
+        let source = self.id();
+        let mut clone = self.commands().spawn();
+        let destination = clone.id();
+        clone.commands().add_fallible(CloneRecursive {
+            source,
+            destination,
+        });
+        clone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        component::Component,
+        reflect::ReflectComponent,
+        schedule::{Stage, SystemStage},
+        system::{CommandErrorHandler, CommandQueue, Commands},
+        world::World,
+    };
+    use bevy_reflect::{Reflect, TypeRegistryArc};
+
+    use super::{clone_with_children_recursive, CloneRecursiveExt};
+    use crate::{
+        components::{Children, Parent, PreviousParent},
+        hierarchy::{parent_update_system, BuildChildren, BuildWorldChildren},
+    };
+
+    #[derive(Component, Reflect, Default, Clone, Copy, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Idx(u32);
+
+    #[derive(Component)]
+    struct NotRegistered;
+
+    struct CloneFailed;
+
+    fn world_with_registry() -> World {
+        let mut world = World::default();
+        let type_registry = TypeRegistryArc::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Idx>();
+            type_registry.register::<Children>();
+            type_registry.register::<Parent>();
+            type_registry.register::<PreviousParent>();
+        }
+        world.insert_resource(type_registry);
+        world
+    }
+
+    #[test]
+    fn clone_recursive() {
+        let mut world = world_with_registry();
+        let mut queue = CommandQueue::default();
+        let grandparent;
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            grandparent = commands.spawn().insert(Idx(0)).id();
+            commands.entity(grandparent).with_children(|parent| {
+                parent.spawn().insert(Idx(1)).with_children(|parent| {
+                    parent.spawn().insert(Idx(2));
+                    parent.spawn().insert(Idx(3));
+                });
+            });
+        }
+        queue.apply(&mut world);
+        let parent = world.get::<Children>(grandparent).unwrap()[0];
+
+        let clone = {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(parent).clone_recursive().id()
+        };
+        queue.apply(&mut world);
+
+        assert_eq!(world.get::<Idx>(clone), Some(&Idx(1)));
+        assert_eq!(world.get::<Parent>(clone), Some(&Parent(grandparent)));
+        assert_eq!(
+            &**world.get::<Children>(grandparent).unwrap(),
+            &[parent, clone]
+        );
+
+        let children = world.get::<Children>(clone).unwrap().to_vec();
+        let original_children = world.get::<Children>(parent).unwrap().to_vec();
+        assert_eq!(children.len(), 2);
+        for (index, child) in children.iter().enumerate() {
+            assert!(!original_children.contains(child));
+            assert_eq!(world.get::<Idx>(*child), Some(&Idx(index as u32 + 2)));
+            assert_eq!(world.get::<Parent>(*child), Some(&Parent(clone)));
+        }
+    }
+
+    #[test]
+    fn clone_root_recursive() {
+        let mut world = world_with_registry();
+        let root = world.spawn().insert(Idx(0)).id();
+        world.entity_mut(root).with_children(|parent| {
+            parent.spawn().insert(Idx(1));
+        });
+
+        let clone = clone_with_children_recursive(&mut world, root).unwrap();
+        assert_eq!(world.get::<Parent>(clone), None);
+        let child = world.get::<Children>(clone).unwrap()[0];
+        assert_ne!(child, world.get::<Children>(root).unwrap()[0]);
+        assert_eq!(world.get::<Idx>(child), Some(&Idx(1)));
+        assert_eq!(world.get::<Parent>(child), Some(&Parent(clone)));
+        assert_eq!(world.query::<&Idx>().iter(&world).count(), 4);
+    }
+
+    #[test]
+    fn clone_recursive_failure() {
+        let mut world = world_with_registry();
+        let root = world.spawn().insert(Idx(0)).id();
+        world.entity_mut(root).with_children(|parent| {
+            parent.spawn().insert(Idx(1)).insert(NotRegistered);
+        });
+        let entity_count = world.entities().len();
+
+        let mut queue = CommandQueue::default();
+        let clone;
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.set_error_handler(CommandErrorHandler::custom(|world, _| {
+                world.insert_resource(CloneFailed);
+            }));
+            clone = commands.entity(root).clone_recursive().insert(Idx(2)).id();
+        }
+        queue.apply(&mut world);
+
+        // the error is handled, the clone of the child is despawned and the clone of the root is
+        // left empty, so the commands added to it still apply
+        assert!(world.get_resource::<CloneFailed>().is_some());
+        assert_eq!(world.entities().len(), entity_count + 1);
+        assert_eq!(world.get::<Idx>(clone), Some(&Idx(2)));
+        assert!(world.get::<Children>(clone).is_none());
+        assert_eq!(world.query::<&Idx>().iter(&world).count(), 3);
+    }
+
+    #[test]
+    fn clone_entity_skips_hierarchy() {
+        let mut world = world_with_registry();
+        let parent = world.spawn().insert(Idx(0)).id();
+        let mut child = parent;
+        world.entity_mut(parent).with_children(|parent| {
+            child = parent.spawn().insert(Idx(1)).id();
+        });
+        let mut update_stage = SystemStage::parallel().with_system(parent_update_system);
+        update_stage.run(&mut world);
+
+        // the clone of the parent doesn't take the children of the original
+        let parent_clone = world.clone_entity(parent).unwrap();
+        assert_eq!(world.get::<Idx>(parent_clone), Some(&Idx(0)));
+        assert!(world.get::<Children>(parent_clone).is_none());
+
+        // the clone of the child keeps its parent, and is added to its children by
+        // `parent_update_system`
+        let child_clone = world.clone_entity(child).unwrap();
+        assert_eq!(world.get::<Parent>(child_clone), Some(&Parent(parent)));
+        assert!(world.get::<PreviousParent>(child_clone).is_none());
+        update_stage.run(&mut world);
+        assert_eq!(
+            &**world.get::<Children>(parent).unwrap(),
+            &[child, child_clone]
+        );
+    }
+}
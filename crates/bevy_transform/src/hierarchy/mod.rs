@@ -1,8 +1,10 @@
 mod child_builder;
+mod clone_recursive;
 #[allow(clippy::module_inception)]
 mod hierarchy;
 mod hierarchy_maintenance_system;
 
 pub use child_builder::*;
+pub use clone_recursive::*;
 pub use hierarchy::*;
 pub use hierarchy_maintenance_system::*;
@@ -11,15 +11,21 @@ pub mod transform_propagate_system;
 #[doc(hidden)]
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{components::*, hierarchy::*, TransformBundle, TransformPlugin};
+    pub use crate::{
+        components::*, hierarchy::*, PreviousTransformPlugin, TransformBundle, TransformPlugin,
+    };
 }
 
 use bevy_app::prelude::*;
+use bevy_core::FixedUpdateStage;
 use bevy_ecs::{
     bundle::Bundle,
-    schedule::{ParallelSystemDescriptorCoercion, SystemLabel},
+    schedule::{ParallelSystemDescriptorCoercion, SystemLabel, SystemStage},
+};
+use prelude::{
+    parent_update_system, previous_transform_system, Children, GlobalTransform, Parent,
+    PreviousParent, PreviousTransform, Transform,
 };
-use prelude::{parent_update_system, Children, GlobalTransform, Parent, PreviousParent, Transform};
 
 /// A [`Bundle`] of the [`Transform`] and [`GlobalTransform`]
 /// [`Component`](bevy_ecs::component::Component)s, which describe the position of an entity.
@@ -85,6 +91,16 @@ impl From<Transform> for TransformBundle {
 #[derive(Default)]
 pub struct TransformPlugin;
 
+/// Saves the [`PreviousTransform`] of entities at the start of each step of the
+/// `FixedUpdateStage` from `bevy_core`, to interpolate their rendering between steps.
+///
+/// It must be added after the `CorePlugin`, which adds the `FixedUpdateStage`. Systems of this
+/// stage moving entities that have a [`PreviousTransform`] should run
+/// [after](bevy_ecs::schedule::ParallelSystemDescriptorCoercion::after)
+/// [`TransformSystem::PreviousTransform`].
+#[derive(Default)]
+pub struct PreviousTransformPlugin;
+
 /// Label enum for the types of systems relating to transform
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum TransformSystem {
@@ -92,6 +108,8 @@ pub enum TransformSystem {
     TransformPropagate,
     /// Updates [`Parent`] when changes in the hierarchy occur
     ParentUpdate,
+    /// Saves the [`Transform`] into the [`PreviousTransform`] at the start of a fixed step
+    PreviousTransform,
 }
 
 impl Plugin for TransformPlugin {
@@ -101,6 +119,7 @@ impl Plugin for TransformPlugin {
             .register_type::<PreviousParent>()
             .register_type::<Transform>()
             .register_type::<GlobalTransform>()
+            .register_type::<PreviousTransform>()
             // add transform systems to startup so the first update is "correct"
             .add_startup_system_to_stage(
                 StartupStage::PostStartup,
@@ -124,3 +143,19 @@ impl Plugin for TransformPlugin {
             );
     }
 }
+
+impl Plugin for PreviousTransformPlugin {
+    fn build(&self, app: &mut App) {
+        if app
+            .schedule
+            .get_stage::<SystemStage>(&FixedUpdateStage)
+            .is_none()
+        {
+            panic!("PreviousTransformPlugin requires the FixedUpdateStage added by CorePlugin");
+        }
+        app.add_system_to_stage(
+            FixedUpdateStage,
+            previous_transform_system.label(TransformSystem::PreviousTransform),
+        );
+    }
+}